@@ -0,0 +1,48 @@
+//! Compare converting a large array one value at a time with the batch
+//! kernel behind convert_slice.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use epochs::{convert_slice, Epoch};
+use std::hint::black_box;
+
+const LEN: usize = 100_000;
+
+/// A day or so of sorted times, a few per second, around 2009-02-13.
+fn input(epoch: Epoch) -> Vec<i64> {
+    let start = 1_234_567_890_i64;
+    (0..LEN as i64)
+        .map(|i| {
+            let ndt = chrono::DateTime::from_timestamp(start + i, (i % 1000) as u32 * 1_000_000)
+                .unwrap()
+                .naive_utc();
+            epoch.from_datetime(ndt)
+        })
+        .collect()
+}
+
+fn batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convert");
+    group.throughput(Throughput::Elements(LEN as u64));
+    for epoch in [Epoch::Unix, Epoch::Java, Epoch::Chrome, Epoch::WindowsFile] {
+        let input = input(epoch);
+        let mut out = Vec::with_capacity(LEN);
+        group.bench_with_input(BenchmarkId::new("single", epoch), &input, |b, input| {
+            b.iter(|| {
+                out.clear();
+                out.extend(input.iter().map(|&num| epoch.to_datetime(num)));
+                black_box(&out);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("slice", epoch), &input, |b, input| {
+            b.iter(|| {
+                out.clear();
+                convert_slice(epoch, input, &mut out);
+                black_box(&out);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, batch);
+criterion_main!(benches);
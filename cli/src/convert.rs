@@ -0,0 +1,175 @@
+//! The `convert` subcommand, which rescales numbers from one epoch to
+//! another.
+
+use std::io::{self, Write};
+
+use clap::Args;
+use epochs::{rescale, Epoch};
+use serde::Serialize;
+
+use crate::names;
+use crate::output::{Format, JsonWriter};
+use crate::parse_num;
+
+/// Convert numbers from one epoch to another.
+///
+/// The result is the number in the target epoch, rather than a date
+/// and time. Any remainder smaller than the target unit is rounded
+/// down. As with the main command, values are read from standard input
+/// if none are given.
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    /// The epoch the values are in.
+    #[arg(short, long, value_name = "EPOCH", value_parser = names::epoch())]
+    from: Epoch,
+
+    /// The epoch to convert them to.
+    #[arg(short, long, value_name = "EPOCH", value_parser = names::epoch())]
+    to: Epoch,
+
+    /// The values to convert.
+    pub values: Vec<String>,
+}
+
+/// The result of converting one value, as written in JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct ConvertRecord {
+    input: String,
+    from: &'static str,
+    to: &'static str,
+    value: Option<i64>,
+    error: Option<String>,
+}
+
+/// Convert one number, going through a datetime only for epochs (like
+/// Google Calendar) that cannot simply be rescaled.
+fn convert(from: Epoch, to: Epoch, num: i64) -> Result<i64, String> {
+    if let Some(n) = rescale(num, from, to) {
+        return Ok(n);
+    }
+    let ndt = from
+        .try_to_datetime(num)
+        .map_err(|e| format!("{} is not a {} time: {}", num, from, e))?;
+    to.try_from_datetime(ndt)
+        .map_err(|e| format!("{} cannot be a {} time: {}", num, to, e))
+}
+
+/// Run the subcommand. Returns whether every value converted.
+pub fn run<I, O, E>(
+    args: &ConvertArgs,
+    format: Format,
+    hex: bool,
+    values: I,
+    mut out: O,
+    mut err: E,
+) -> io::Result<bool>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    O: Write,
+    E: Write,
+{
+    // Blank lines have no result, but still get a line of text output.
+    let results = values.into_iter().map(|input| {
+        input.map(|input| {
+            let result = (!input.trim().is_empty())
+                .then(|| parse_num(&input, hex).and_then(|num| convert(args.from, args.to, num)));
+            (input, result)
+        })
+    });
+    let mut ok = true;
+    if format == Format::Text {
+        for (i, item) in results.enumerate() {
+            match item? {
+                (_, Some(Ok(n))) => writeln!(out, "{}", n)?,
+                (_, None) => writeln!(out)?,
+                (_, Some(Err(e))) => {
+                    writeln!(out)?;
+                    writeln!(err, "epochs: line {}: {}", i + 1, e)?;
+                    ok = false;
+                }
+            }
+        }
+        out.flush()?;
+    } else {
+        let mut writer = JsonWriter::new(out, format == Format::Ndjson);
+        for item in results {
+            if let (input, Some(result)) = item? {
+                ok &= result.is_ok();
+                writer.write(&ConvertRecord {
+                    input: input.trim().to_string(),
+                    from: args.from.name(),
+                    to: args.to.name(),
+                    value: result.as_ref().ok().copied(),
+                    error: result.err(),
+                })?;
+            }
+        }
+        writer.finish()?.flush()?;
+    }
+    Ok(ok)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn run_lines(from: Epoch, to: Epoch, format: Format, input: &str) -> (bool, String) {
+        let args = ConvertArgs {
+            from,
+            to,
+            values: Vec::new(),
+        };
+        let mut out = Vec::new();
+        let lines = input.lines().map(|line| Ok(line.to_string()));
+        let ok = run(&args, format, false, lines, &mut out, io::sink()).unwrap();
+        (ok, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn rescaled() {
+        assert_eq!(
+            convert(Epoch::WindowsFile, Epoch::Unix, 128_790_414_900_000_000),
+            Ok(1_234_567_890)
+        );
+        assert_eq!(
+            convert(Epoch::Unix, Epoch::Chrome, 1_234_567_890),
+            Ok(12_879_041_490_000_000)
+        );
+    }
+    #[test]
+    fn google_calendar() {
+        assert_eq!(
+            convert(Epoch::Unix, Epoch::GoogleCalendar, 1_234_567_890),
+            Ok(1_297_899_090)
+        );
+        assert_eq!(
+            convert(Epoch::GoogleCalendar, Epoch::Unix, 1_297_899_090),
+            Ok(1_234_567_890)
+        );
+    }
+    #[test]
+    fn out_of_range() {
+        assert!(convert(Epoch::Unix, Epoch::Apfs, i64::MAX).is_err());
+        // Numbers these epochs' own functions reject.
+        assert!(convert(Epoch::Unix, Epoch::Palm, -3_000_000_000).is_err());
+        assert!(convert(Epoch::Unix, Epoch::Zigbee, 0).is_err());
+        assert!(convert(Epoch::Palm, Epoch::Unix, -1).is_err());
+    }
+    #[test]
+    fn batch() {
+        let input = "128790414900000000\n\nbogus\n";
+        let (ok, out) = run_lines(Epoch::WindowsFile, Epoch::Unix, Format::Text, input);
+        assert!(!ok);
+        assert_eq!(out, "1234567890\n\n\n");
+    }
+    #[test]
+    fn json() {
+        let (ok, out) = run_lines(Epoch::Unix, Epoch::Java, Format::Ndjson, "1234567890\n");
+        assert!(ok);
+        assert_eq!(
+            out,
+            "{\"input\":\"1234567890\",\"from\":\"unix\",\"to\":\"java\",\"value\":1234567890000,\"error\":null}\n"
+        );
+    }
+}
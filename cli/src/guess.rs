@@ -0,0 +1,172 @@
+//! The `guess` subcommand, which lists every plausible reading of a
+//! number.
+
+use std::io::{self, Write};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use clap::Args;
+use epochs::{guess_in_range, parse_datetime, Epoch, GUESS_FROM, GUESS_TO};
+
+use crate::output::{iso8601, Format, JsonWriter, Record};
+use crate::parse_num;
+
+/// Guess which epoch a number is in.
+///
+/// Every epoch under which the number falls in the window is listed,
+/// with the most likely (for now, the closest to the present) first.
+#[derive(Debug, Args)]
+pub struct GuessArgs {
+    /// Only keep times at or after this year, date, or date and time
+    /// (e.g., 2015 or 2015-06-01).
+    #[arg(long, value_name = "WHEN", value_parser = parse_bound)]
+    after: Option<NaiveDateTime>,
+
+    /// Only keep times at or before this year, date, or date and time.
+    #[arg(long, value_name = "WHEN", value_parser = parse_bound)]
+    before: Option<NaiveDateTime>,
+
+    /// The number to guess about.
+    value: String,
+}
+
+/// Parse a year (meaning the start of it), a date, or a date and time.
+fn parse_bound(s: &str) -> Result<NaiveDateTime, String> {
+    let s = s.trim();
+    if let Ok(year) = s.parse::<i32>() {
+        return NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .ok_or_else(|| format!("year out of range: {}", year));
+    }
+    parse_datetime(s).ok_or_else(|| format!("expected a year, date, or date and time, not {:?}", s))
+}
+
+/// The guesses for `num` within the window, closest to `now` first.
+fn ranked(
+    num: i64,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Vec<(Epoch, NaiveDateTime)> {
+    let mut guesses = guess_in_range(num, from, to);
+    guesses.sort_by_key(|&(epoch, ndt)| ((ndt - now).abs(), epoch.name()));
+    guesses
+}
+
+/// Run the subcommand. Returns whether there were any guesses.
+pub fn run<O, E>(
+    args: &GuessArgs,
+    format: Format,
+    hex: bool,
+    now: NaiveDateTime,
+    mut out: O,
+    mut err: E,
+) -> io::Result<bool>
+where
+    O: Write,
+    E: Write,
+{
+    let num = match parse_num(&args.value, hex) {
+        Ok(num) => num,
+        Err(e) => {
+            writeln!(err, "epochs: {}", e)?;
+            return Ok(false);
+        }
+    };
+    let from = args.after.unwrap_or(GUESS_FROM);
+    let to = args.before.unwrap_or(GUESS_TO);
+    let guesses = ranked(num, from, to, now);
+
+    match format {
+        Format::Text => {
+            for (epoch, ndt) in &guesses {
+                writeln!(out, "{:<16} {}", epoch.name(), iso8601(*ndt))?;
+            }
+            out.flush()?;
+        }
+        Format::Json | Format::Ndjson => {
+            let mut writer = JsonWriter::new(out, format == Format::Ndjson);
+            for (epoch, ndt) in &guesses {
+                writer.write(&Record {
+                    input: args.value.trim().to_string(),
+                    epoch: epoch.name(),
+                    datetime: Some(iso8601(*ndt)),
+                    error: None,
+                })?;
+            }
+            writer.finish()?.flush()?;
+        }
+    }
+
+    if guesses.is_empty() {
+        writeln!(
+            err,
+            "epochs: {} is not a time between {} and {} in any epoch",
+            num,
+            iso8601(from),
+            iso8601(to)
+        )?;
+    }
+    Ok(!guesses.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn at(year: i32) -> NaiveDateTime {
+        parse_bound(&year.to_string()).unwrap()
+    }
+
+    #[test]
+    fn bounds() {
+        assert_eq!(iso8601(at(2015)), "2015-01-01T00:00:00");
+        assert_eq!(
+            iso8601(parse_bound("2015-06-01").unwrap()),
+            "2015-06-01T00:00:00"
+        );
+        assert_eq!(
+            iso8601(parse_bound("2015-06-01 12:34:56").unwrap()),
+            "2015-06-01T12:34:56"
+        );
+        assert!(parse_bound("June").is_err());
+        assert!(parse_bound("2015-13-01").is_err());
+    }
+    #[test]
+    fn closest_first() {
+        let num = 128_790_414_900_000_000;
+        let epochs: Vec<_> = ranked(num, GUESS_FROM, GUESS_TO, at(2010))
+            .into_iter()
+            .map(|(epoch, _)| epoch)
+            .collect();
+        assert_eq!(epochs, [Epoch::WindowsFile, Epoch::UuidV1]);
+    }
+    #[test]
+    fn window() {
+        let args = GuessArgs {
+            after: Some(at(2015)),
+            before: Some(at(2020)),
+            value: "1297899090".to_string(),
+        };
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        assert!(!run(&args, Format::Text, false, at(2020), &mut out, &mut err).unwrap());
+        assert!(out.is_empty());
+        assert!(String::from_utf8(err)
+            .unwrap()
+            .contains("not a time between"));
+
+        let args = GuessArgs {
+            after: Some(at(2011)),
+            ..args
+        };
+        let mut out = Vec::new();
+        assert!(run(&args, Format::Text, false, at(2020), &mut out, io::sink()).unwrap());
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "unix             2011-02-16T23:31:30\n\
+             unix_right       2011-02-16T23:31:06\n\
+             tai              2011-02-16T23:30:56\n"
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Convert epoch times from the command line.
+//!
+//! ```text
+//! $ epochs --from chrome 12879041490654321
+//! 2009-02-13T23:31:30.654321
+//! $ cut -f3 history.tsv | epochs --from chrome
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use chrono::NaiveDateTime;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use epochs::{parse_hex, parse_value, Epoch};
+
+use convert::ConvertArgs;
+use guess::GuessArgs;
+use now::NowArgs;
+use output::{iso8601, Format, JsonWriter, Record};
+use rewrite::CsvArgs;
+
+mod convert;
+mod guess;
+mod names;
+mod now;
+mod output;
+mod rewrite;
+
+/// Convert epoch times to dates and times.
+///
+/// With no values on the command line, values are read from standard
+/// input, one per line. Each line of output then matches a line of
+/// input, so a value that cannot be converted gives an empty line
+/// (and a message on standard error).
+#[derive(Debug, Parser)]
+#[command(name = "epochs", version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The epoch the values are in (e.g., chrome or windows_file).
+    /// Without it, every epoch is tried.
+    #[arg(short, long, value_name = "EPOCH", value_parser = names::epoch())]
+    from: Option<Epoch>,
+
+    /// Read values as hexadecimal, with or without a 0x prefix.
+    /// Otherwise, 0x, 0o, and 0b prefixes are recognized.
+    #[arg(short = 'x', long, global = true)]
+    hex: bool,
+
+    /// How to write the results.
+    #[arg(short, long, value_enum, default_value_t, global = true)]
+    output: Format,
+
+    /// The values to convert.
+    values: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Write a shell completion script to standard output.
+    ///
+    /// For example, for bash, `epochs completions bash >
+    /// ~/.local/share/bash-completion/completions/epochs`.
+    Completions {
+        /// The shell to write the script for.
+        shell: Shell,
+    },
+    Convert(ConvertArgs),
+    Csv(CsvArgs),
+    Guess(GuessArgs),
+    Now(NowArgs),
+}
+
+/// Parse a value as an integer, in any of the forms
+/// [parse_value](epochs::parse_value) accepts, or as hexadecimal.
+fn parse_num(input: &str, hex: bool) -> Result<i64, String> {
+    if hex {
+        return parse_hex(input).map_err(|e| e.to_string());
+    }
+    parse_value(input)
+        .map_err(|e| e.to_string())?
+        .as_i64()
+        .ok_or_else(|| format!("not an integer: {:?}", input.trim()))
+}
+
+/// Convert one value from the given epoch.
+fn convert(epoch: Epoch, num: i64) -> Result<NaiveDateTime, String> {
+    epoch
+        .try_to_datetime(num)
+        .map_err(|e| format!("{} is not a {} time: {}", num, epoch, e))
+}
+
+/// Convert one value from the given epoch, or from every epoch.
+fn records(cli: &Cli, input: &str) -> Vec<Record> {
+    let num = parse_num(input, cli.hex);
+    let epochs = match cli.from {
+        Some(epoch) => vec![epoch],
+        None => Epoch::ALL.to_vec(),
+    };
+    epochs
+        .into_iter()
+        .map(|epoch| {
+            let result = num.clone().and_then(|num| convert(epoch, num));
+            Record {
+                input: input.trim().to_string(),
+                epoch: epoch.name(),
+                datetime: result.as_ref().ok().map(|&ndt| iso8601(ndt)),
+                error: result.err(),
+            }
+        })
+        .collect()
+}
+
+/// Convert each value, writing the results to `out` and any errors to
+/// `err`. Returns whether every value converted (from at least one
+/// epoch, if none was given).
+fn run<I, O, E>(cli: &Cli, values: I, out: O, err: E) -> io::Result<bool>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    O: Write,
+    E: Write,
+{
+    match cli.output {
+        Format::Text => run_text(cli, values, out, err),
+        Format::Json => run_json(cli, values, JsonWriter::new(out, false)),
+        Format::Ndjson => run_json(cli, values, JsonWriter::new(out, true)),
+    }
+}
+
+fn run_text<I, O, E>(cli: &Cli, values: I, mut out: O, mut err: E) -> io::Result<bool>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    O: Write,
+    E: Write,
+{
+    let mut ok = true;
+    for (i, input) in values.into_iter().enumerate() {
+        let input = input?;
+        if input.trim().is_empty() {
+            writeln!(out)?;
+            continue;
+        }
+        let records = records(cli, &input);
+        if cli.from.is_some() {
+            let record = &records[0];
+            writeln!(out, "{}", record.datetime.as_deref().unwrap_or_default())?;
+            if let Some(e) = &record.error {
+                writeln!(err, "epochs: line {}: {}", i + 1, e)?;
+                ok = false;
+            }
+            continue;
+        }
+        if i > 0 {
+            writeln!(out)?;
+        }
+        for record in &records {
+            if let Some(datetime) = &record.datetime {
+                writeln!(out, "{:<16} {}", record.epoch, datetime)?;
+            }
+        }
+        if !converted(&records) {
+            writeln!(err, "epochs: {:?} is not a time in any epoch", input.trim())?;
+            ok = false;
+        }
+    }
+    out.flush()?;
+    Ok(ok)
+}
+
+/// Like [run_text], but the errors go in the records. Blank lines are
+/// skipped, since each record carries its input.
+fn run_json<I, O>(cli: &Cli, values: I, mut writer: JsonWriter<O>) -> io::Result<bool>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    O: Write,
+{
+    let mut ok = true;
+    for input in values {
+        let input = input?;
+        if input.trim().is_empty() {
+            continue;
+        }
+        let records = records(cli, &input);
+        for record in &records {
+            writer.write(record)?;
+        }
+        ok &= converted(&records);
+    }
+    writer.finish()?.flush()?;
+    Ok(ok)
+}
+
+/// Whether any of the records has a result.
+fn converted(records: &[Record]) -> bool {
+    records.iter().any(|record| record.error.is_none())
+}
+
+/// Write the completion script for the given shell.
+fn completions<O: Write>(shell: Shell, mut out: O) -> io::Result<()> {
+    clap_complete::generate(shell, &mut Cli::command(), "epochs", &mut out);
+    out.flush()
+}
+
+/// The given values or, if there are none, the lines of standard
+/// input.
+fn lines(values: &[String]) -> Box<dyn Iterator<Item = io::Result<String>> + '_> {
+    if values.is_empty() {
+        Box::new(io::stdin().lock().lines())
+    } else {
+        Box::new(values.iter().cloned().map(Ok))
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let stdout = io::stdout();
+    let out = BufWriter::new(stdout.lock());
+    let err = io::stderr();
+    let result = match &cli.command {
+        Some(Command::Completions { shell }) => completions(*shell, out).map(|()| true),
+        Some(Command::Convert(args)) => {
+            convert::run(args, cli.output, cli.hex, lines(&args.values), out, err)
+        }
+        Some(Command::Csv(args)) => {
+            if cli.output != Format::Text {
+                eprintln!("epochs: csv always writes CSV, so --output does not apply");
+                return ExitCode::FAILURE;
+            }
+            match args.path.as_deref() {
+                Some(path) if path != Path::new("-") => match File::open(path) {
+                    Ok(file) => rewrite::run(args, cli.hex, BufReader::new(file), out, err),
+                    Err(e) => {
+                        eprintln!("epochs: {}: {}", path.display(), e);
+                        return ExitCode::FAILURE;
+                    }
+                },
+                _ => rewrite::run(args, cli.hex, io::stdin().lock(), out, err),
+            }
+        }
+        Some(Command::Guess(args)) => {
+            let now = chrono::Utc::now().naive_utc();
+            guess::run(args, cli.output, cli.hex, now, out, err)
+        }
+        Some(Command::Now(args)) => now::run(args, cli.output, out, err),
+        None => run(&cli, lines(&cli.values), out, err),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        // Stop quietly when piped into something like head.
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("epochs: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn run_lines(args: &[&str], input: &str) -> (bool, String, String) {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let lines = input.lines().map(|line| Ok(line.to_string()));
+        let ok = run(&cli, lines, &mut out, &mut err).unwrap();
+        (
+            ok,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+    #[test]
+    fn from_epoch() {
+        let (ok, out, err) = run_lines(&["epochs", "--from", "chrome"], "12879041490654321\n");
+        assert!(ok);
+        assert_eq!(out, "2009-02-13T23:31:30.654321\n");
+        assert_eq!(err, "");
+    }
+    #[test]
+    fn batch_keeps_lines_aligned() {
+        let input = "1234567890\n\nbogus\n1,234,567,891\n";
+        let (ok, out, err) = run_lines(&["epochs", "-f", "unix"], input);
+        assert!(!ok);
+        assert_eq!(out, "2009-02-13T23:31:30\n\n\n2009-02-13T23:31:31\n");
+        assert_eq!(
+            err,
+            "epochs: line 3: invalid number: \"bogus\": expected a digit at offset 0\n"
+        );
+    }
+    #[test]
+    fn out_of_range() {
+        let (ok, out, err) = run_lines(&["epochs", "-f", "java"], &i64::MAX.to_string());
+        assert!(!ok);
+        assert_eq!(out, "\n");
+        assert!(err.contains("is not a java time"), "{}", err);
+    }
+    #[test]
+    fn every_epoch() {
+        let (ok, out, _) = run_lines(&["epochs"], "1234567890");
+        assert!(ok);
+        assert!(
+            out.contains("unix             2009-02-13T23:31:30\n"),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("java             1970-01-15T06:56:07.890\n"),
+            "{}",
+            out
+        );
+    }
+    #[test]
+    fn json_output() {
+        let args = ["epochs", "-f", "unix", "--output", "json"];
+        let (ok, out, err) = run_lines(&args, "1234567890\n\nbogus\n");
+        assert!(!ok);
+        assert_eq!(err, "");
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed[0]["input"], "1234567890");
+        assert_eq!(parsed[0]["epoch"], "unix");
+        assert_eq!(parsed[0]["datetime"], "2009-02-13T23:31:30");
+        assert_eq!(parsed[1]["input"], "bogus");
+        assert_eq!(parsed[1]["datetime"], serde_json::Value::Null);
+        assert_eq!(
+            parsed[1]["error"],
+            "invalid number: \"bogus\": expected a digit at offset 0"
+        );
+        assert_eq!(parsed.as_array().unwrap().len(), 2);
+    }
+    #[test]
+    fn ndjson_every_epoch() {
+        let (ok, out, _) = run_lines(&["epochs", "-o", "ndjson"], "1234567890");
+        assert!(ok);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), Epoch::ALL.len());
+        for line in lines {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(record["input"], "1234567890");
+        }
+    }
+    #[test]
+    fn guess_subcommand() {
+        let args = [
+            "epochs",
+            "guess",
+            "--after",
+            "2015",
+            "1297899090",
+            "-o",
+            "json",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.output, Format::Json);
+        assert!(matches!(cli.command, Some(Command::Guess(_))));
+        assert!(Cli::try_parse_from(["epochs", "guess"]).is_err());
+    }
+    #[test]
+    fn bases() {
+        let input = "0x1c98e333296f500\n0o7114343146245572400\n";
+        let (ok, out, _) = run_lines(&["epochs", "-f", "windows_file"], input);
+        assert!(ok);
+        assert_eq!(out, "2009-02-13T23:31:30\n2009-02-13T23:31:30\n");
+        let input = "1c98e333296f500\n0x1c98e333296f500\n";
+        let (ok, out, _) = run_lines(&["epochs", "-f", "windows_file", "--hex"], input);
+        assert!(ok);
+        assert_eq!(out, "2009-02-13T23:31:30\n2009-02-13T23:31:30\n");
+    }
+    #[test]
+    fn completion_scripts() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut out = Vec::new();
+            completions(shell, &mut out).unwrap();
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("windows_file"), "{}", shell);
+            assert!(script.contains("guess"), "{}", shell);
+        }
+    }
+    #[test]
+    fn unknown_epoch() {
+        assert!(Cli::try_parse_from(["epochs", "--from", "bogus"]).is_err());
+    }
+}
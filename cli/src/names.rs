@@ -0,0 +1,112 @@
+//! Parsing epoch names in arguments, in a way that lets clap list them
+//! in help and shell completions.
+
+use std::ffi::OsStr;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Arg, Command, Error};
+use epochs::Epoch;
+
+/// A value parser for anything named after an epoch. Parsing is left
+/// to `FromStr`, so the aliases and loose spellings the library
+/// accepts still work, but only the canonical names (plus any extras)
+/// are offered.
+#[derive(Clone, Debug)]
+pub struct NameParser<T> {
+    extra: &'static [&'static str],
+    value: PhantomData<fn() -> T>,
+}
+
+/// A parser for epoch names.
+pub fn epoch() -> NameParser<Epoch> {
+    NameParser {
+        extra: &[],
+        value: PhantomData,
+    }
+}
+
+/// A parser for epoch names and the given extra names.
+pub fn epoch_or<T>(extra: &'static [&'static str]) -> NameParser<T> {
+    NameParser {
+        extra,
+        value: PhantomData,
+    }
+}
+
+impl<T> NameParser<T> {
+    fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.extra
+            .iter()
+            .copied()
+            .chain(Epoch::ALL.iter().map(|epoch| epoch.name()))
+    }
+}
+
+impl<T> TypedValueParser for NameParser<T>
+where
+    T: FromStr + Clone + Send + Sync + 'static,
+{
+    type Value = T;
+
+    fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<T, Error> {
+        let s = value.to_string_lossy();
+        s.parse().map_err(|_| {
+            let mut err = Error::new(ErrorKind::InvalidValue).with_cmd(cmd);
+            if let Some(arg) = arg {
+                err.insert(
+                    ContextKind::InvalidArg,
+                    ContextValue::String(arg.to_string()),
+                );
+            }
+            err.insert(
+                ContextKind::InvalidValue,
+                ContextValue::String(s.into_owned()),
+            );
+            err.insert(
+                ContextKind::ValidValue,
+                ContextValue::Strings(self.names().map(str::to_string).collect()),
+            );
+            err
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(self.names().map(PossibleValue::new)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Debug, Parser)]
+    struct Test {
+        #[arg(long, value_parser = epoch())]
+        from: Epoch,
+    }
+
+    #[test]
+    fn aliases() {
+        let test = Test::try_parse_from(["test", "--from", "FileTime"]).unwrap();
+        assert_eq!(test.from, Epoch::WindowsFile);
+    }
+    #[test]
+    fn invalid() {
+        let err = Test::try_parse_from(["test", "--from", "bogus"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+        let message = err.to_string();
+        assert!(message.contains("'bogus'"), "{}", message);
+        assert!(message.contains("windows_file"), "{}", message);
+    }
+    #[test]
+    fn extras() {
+        let names: Vec<_> = epoch_or::<String>(&["iso8601"]).names().collect();
+        assert_eq!(names[0], "iso8601");
+        assert_eq!(names.len(), Epoch::ALL.len() + 1);
+    }
+}
@@ -0,0 +1,159 @@
+//! The `now` subcommand, which shows the current time in every epoch.
+
+use std::io::{self, IsTerminal, Write};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, Utc};
+use clap::Args;
+use epochs::Epoch;
+use serde::Serialize;
+
+use crate::output::{iso8601, Format, JsonWriter};
+
+/// Show the current time in every epoch.
+#[derive(Debug, Args)]
+pub struct NowArgs {
+    /// Keep showing the time, refreshing at this interval (e.g., 1s,
+    /// 500ms, or 2m), until interrupted.
+    #[arg(short, long, value_name = "INTERVAL", value_parser = parse_interval)]
+    watch: Option<Duration>,
+}
+
+/// The current time in one epoch, as written in JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct NowRecord {
+    epoch: &'static str,
+    value: i64,
+    datetime: String,
+}
+
+/// Parse an interval: a number followed by ms, s, m, or h, or a bare
+/// number of seconds.
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let num: f64 = num
+        .parse()
+        .map_err(|_| format!("expected an interval like 1s or 500ms, not {:?}", s))?;
+    let seconds = match unit {
+        "ms" => num / 1000.0,
+        "" | "s" => num,
+        "m" => num * 60.0,
+        "h" => num * 60.0 * 60.0,
+        _ => return Err(format!("unknown unit {:?} (use ms, s, m, or h)", unit)),
+    };
+    match Duration::try_from_secs_f64(seconds) {
+        Ok(interval) if !interval.is_zero() => Ok(interval),
+        _ => Err(format!("interval must be positive, not {:?}", s)),
+    }
+}
+
+/// Write the given instant in every epoch.
+fn show<O: Write>(now: NaiveDateTime, format: Format, mut out: O) -> io::Result<()> {
+    let values = Epoch::ALL.map(|epoch| (epoch, epoch.from_datetime(now)));
+    match format {
+        Format::Text => {
+            writeln!(out, "{:<16} {}", "iso8601", iso8601(now))?;
+            for (epoch, value) in values {
+                writeln!(out, "{:<16} {}", epoch.name(), value)?;
+            }
+            out.flush()
+        }
+        Format::Json | Format::Ndjson => {
+            let mut writer = JsonWriter::new(out, format == Format::Ndjson);
+            for (epoch, value) in values {
+                writer.write(&NowRecord {
+                    epoch: epoch.name(),
+                    value,
+                    datetime: iso8601(now),
+                })?;
+            }
+            writer.finish()?.flush()
+        }
+    }
+}
+
+/// Run the subcommand. Only returns early (with false) for options
+/// that make no sense together.
+pub fn run<O: Write, E: Write>(
+    args: &NowArgs,
+    format: Format,
+    mut out: O,
+    mut err: E,
+) -> io::Result<bool> {
+    let Some(interval) = args.watch else {
+        show(Utc::now().naive_utc(), format, out)?;
+        return Ok(true);
+    };
+    if format == Format::Json {
+        writeln!(
+            err,
+            "epochs: --watch never finishes a JSON array, so use --output ndjson"
+        )?;
+        return Ok(false);
+    }
+    let clear = format == Format::Text && io::stdout().is_terminal();
+    loop {
+        if clear {
+            // Clear the screen and go home.
+            write!(out, "\x1b[2J\x1b[H")?;
+        }
+        show(Utc::now().naive_utc(), format, &mut out)?;
+        if format == Format::Text && !clear {
+            writeln!(out)?;
+        }
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::DateTime;
+
+    #[test]
+    fn intervals() {
+        assert_eq!(parse_interval("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_interval("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_interval("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_interval("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_interval("0.5"), Ok(Duration::from_millis(500)));
+        assert!(parse_interval("0s").is_err());
+        assert!(parse_interval("-1s").is_err());
+        assert!(parse_interval("1d").is_err());
+        assert!(parse_interval("soon").is_err());
+    }
+    #[test]
+    fn every_epoch() {
+        let now = DateTime::from_timestamp(1234567890, 0).unwrap().naive_utc();
+        let mut out = Vec::new();
+        show(now, Format::Text, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), Epoch::ALL.len() + 1);
+        assert!(out.starts_with("iso8601          2009-02-13T23:31:30\n"));
+        assert!(out.contains("unix             1234567890\n"));
+        assert!(out.contains("chrome           12879041490000000\n"));
+    }
+    #[test]
+    fn ndjson() {
+        let now = DateTime::from_timestamp(1234567890, 0).unwrap().naive_utc();
+        let mut out = Vec::new();
+        show(now, Format::Ndjson, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains(
+            "{\"epoch\":\"java\",\"value\":1234567890000,\"datetime\":\"2009-02-13T23:31:30\"}\n"
+        ));
+    }
+    #[test]
+    fn watch_json_array() {
+        let args = NowArgs {
+            watch: Some(Duration::from_secs(1)),
+        };
+        let mut err = Vec::new();
+        assert!(!run(&args, Format::Json, io::sink(), &mut err).unwrap());
+        assert!(String::from_utf8(err).unwrap().contains("ndjson"));
+    }
+}
@@ -0,0 +1,124 @@
+//! Writing conversion results as text, JSON, or newline-delimited
+//! JSON.
+
+use std::io::{self, Write};
+
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Format a NaiveDateTime as ISO 8601, with as many fractional digits
+/// as it needs.
+pub fn iso8601(ndt: NaiveDateTime) -> String {
+    ndt.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+}
+
+/// How to write the results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// One date and time per line.
+    #[default]
+    Text,
+    /// A JSON array of records.
+    Json,
+    /// One JSON record per line.
+    Ndjson,
+}
+
+/// The result of converting one value from one epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Record {
+    /// The value as given.
+    pub input: String,
+    /// The name of the epoch it was converted from.
+    pub epoch: &'static str,
+    /// The result, in ISO 8601.
+    pub datetime: Option<String>,
+    /// Why the value could not be converted.
+    pub error: Option<String>,
+}
+
+/// Writes records as they come, so a long stream of input never has
+/// to be held in memory.
+pub struct JsonWriter<W: Write> {
+    out: W,
+    lines: bool,
+    count: usize,
+}
+
+impl<W: Write> JsonWriter<W> {
+    /// A writer for a JSON array or, if `lines` is true,
+    /// newline-delimited JSON.
+    pub fn new(out: W, lines: bool) -> JsonWriter<W> {
+        JsonWriter {
+            out,
+            lines,
+            count: 0,
+        }
+    }
+
+    /// Write one record.
+    pub fn write<T: Serialize>(&mut self, record: &T) -> io::Result<()> {
+        if !self.lines {
+            self.out
+                .write_all(if self.count == 0 { b"[\n" } else { b",\n" })?;
+        }
+        serde_json::to_writer(&mut self.out, record)?;
+        if self.lines {
+            writeln!(self.out)?;
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Close the array (if any) and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.lines {
+            self.out
+                .write_all(if self.count == 0 { b"[]\n" } else { b"\n]\n" })?;
+        }
+        Ok(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn record(input: &str) -> Record {
+        Record {
+            input: input.to_string(),
+            epoch: "unix",
+            datetime: Some("2009-02-13T23:31:30".to_string()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn json_array() {
+        let mut writer = JsonWriter::new(Vec::new(), false);
+        writer.write(&record("1234567890")).unwrap();
+        writer.write(&record("1234567890")).unwrap();
+        let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 2);
+        assert_eq!(parsed[0]["epoch"], "unix");
+        assert_eq!(parsed[0]["error"], serde_json::Value::Null);
+    }
+    #[test]
+    fn empty_array() {
+        let writer = JsonWriter::new(Vec::new(), false);
+        assert_eq!(writer.finish().unwrap(), b"[]\n");
+    }
+    #[test]
+    fn json_lines() {
+        let mut writer = JsonWriter::new(Vec::new(), true);
+        writer.write(&record("1234567890")).unwrap();
+        let out = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(
+            out,
+            "{\"input\":\"1234567890\",\"epoch\":\"unix\",\"datetime\":\"2009-02-13T23:31:30\",\"error\":null}\n"
+        );
+    }
+}
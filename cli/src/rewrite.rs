@@ -0,0 +1,213 @@
+//! The `csv` subcommand, which converts columns of a CSV file and
+//! leaves the rest of each row alone.
+
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use epochs::stream::{CsvEpochRewriter, RewriteError, Target};
+use epochs::Epoch;
+
+use crate::names;
+
+/// Convert columns of a CSV file.
+///
+/// The given columns are rewritten and the rest of each row is copied
+/// through, with quoting added wherever it is needed. A cell that
+/// cannot be converted is left as it is (and reported on standard
+/// error). Empty cells are left empty.
+#[derive(Debug, Args)]
+pub struct CsvArgs {
+    /// The columns to convert, by number (starting at 1) or, with
+    /// --header, by name. Give more than one separated by commas or by
+    /// repeating the flag.
+    #[arg(
+        short,
+        long = "column",
+        value_name = "COLUMN",
+        value_delimiter = ',',
+        required = true
+    )]
+    columns: Vec<String>,
+
+    /// The epoch the columns are in.
+    #[arg(short, long, value_name = "EPOCH", value_parser = names::epoch())]
+    from: Epoch,
+
+    /// What to convert them to: iso8601, or the name of an epoch.
+    #[arg(
+        short,
+        long,
+        value_name = "TARGET",
+        default_value_t = Target::Iso8601,
+        value_parser = names::epoch_or::<Target>(&["iso8601"])
+    )]
+    to: Target,
+
+    /// The first row is a header, to be copied through unchanged.
+    #[arg(long)]
+    header: bool,
+
+    /// The field delimiter (e.g., '\t' for tab-separated values).
+    #[arg(short, long, default_value_t = ',', value_parser = parse_delimiter)]
+    delimiter: char,
+
+    /// The CSV file to read. Reads standard input if not given or "-".
+    pub path: Option<PathBuf>,
+}
+
+/// Parse a single-byte delimiter, allowing `\t` for tab.
+fn parse_delimiter(s: &str) -> Result<char, String> {
+    match s {
+        "\\t" | "tab" => Ok('\t'),
+        _ => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii() => Ok(c),
+                _ => Err(format!("expected a single ASCII character, not {:?}", s)),
+            }
+        }
+    }
+}
+
+/// Add a rule for each column to the rewriter.
+fn add_columns<R, W>(
+    args: &CsvArgs,
+    mut rewriter: CsvEpochRewriter<R, W>,
+) -> Result<CsvEpochRewriter<R, W>, String>
+where
+    R: Read,
+    W: Write,
+{
+    for column in &args.columns {
+        rewriter = match column.parse::<usize>() {
+            Ok(n) => {
+                let index = n
+                    .checked_sub(1)
+                    .ok_or_else(|| "columns are numbered from 1".to_string())?;
+                rewriter.column(index, args.from, args.to)
+            }
+            Err(_) if args.header => rewriter.named_column(column, args.from, args.to),
+            Err(_) => {
+                return Err(format!(
+                    "column {:?} is not a number (use --header for names)",
+                    column
+                ))
+            }
+        };
+    }
+    Ok(rewriter)
+}
+
+/// Run the subcommand. Returns whether every cell converted.
+pub fn run<R, O, E>(args: &CsvArgs, hex: bool, input: R, out: O, mut err: E) -> io::Result<bool>
+where
+    R: Read,
+    O: Write,
+    E: Write,
+{
+    let rewriter = CsvEpochRewriter::new(input, out)
+        .delimiter(args.delimiter as u8)
+        .has_headers(args.header)
+        .hex(hex);
+    let rewriter = match add_columns(args, rewriter) {
+        Ok(rewriter) => rewriter,
+        Err(e) => {
+            writeln!(err, "epochs: {}", e)?;
+            return Ok(false);
+        }
+    };
+    // The error handler cannot return an error, so keep the first
+    // failure to write to stderr for later.
+    let mut write_error = None;
+    let result = rewriter.run_with(|e| {
+        if write_error.is_none() {
+            write_error = writeln!(err, "epochs: {}", e).err();
+        }
+    });
+    if let Some(e) = write_error {
+        return Err(e);
+    }
+    match result {
+        Ok(summary) => Ok(summary.failed == 0),
+        Err(RewriteError::Csv(e)) => Err(e.into()),
+        Err(e) => {
+            writeln!(err, "epochs: {}", e)?;
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Test {
+        #[command(flatten)]
+        args: CsvArgs,
+    }
+
+    fn rewrite(args: &[&str], input: &str) -> (bool, String, String) {
+        let mut argv = vec!["csv"];
+        argv.extend(args);
+        let args = Test::try_parse_from(argv).unwrap().args;
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let ok = run(&args, false, input.as_bytes(), &mut out, &mut err).unwrap();
+        (
+            ok,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn one_column() {
+        let input = "1,\"Hello, world\",12879041490654321\n2,plain,12879041490000000\n";
+        let (ok, out, _) = rewrite(&["-c", "3", "-f", "chrome"], input);
+        assert!(ok);
+        assert_eq!(
+            out,
+            "1,\"Hello, world\",2009-02-13T23:31:30.654321\n2,plain,2009-02-13T23:31:30\n"
+        );
+    }
+    #[test]
+    fn columns_by_name() {
+        let input = "id,created,modified\n7,1234567890,1234567891\n";
+        let args = [
+            "--header",
+            "-c",
+            "created,modified",
+            "-f",
+            "unix",
+            "-t",
+            "java",
+        ];
+        let (ok, out, _) = rewrite(&args, input);
+        assert!(ok);
+        assert_eq!(out, "id,created,modified\n7,1234567890000,1234567891000\n");
+    }
+    #[test]
+    fn bad_cells_are_kept() {
+        let input = "1234567890\tx\nbogus\n\t\n";
+        let (ok, out, err) = rewrite(&["-c", "1", "-f", "unix", "-d", "\\t"], input);
+        assert!(!ok);
+        assert_eq!(out, "2009-02-13T23:31:30\tx\nbogus\n\t\n");
+        assert_eq!(
+            err,
+            "epochs: line 2, column 1: invalid number: \"bogus\": expected a digit at offset 0\n"
+        );
+    }
+    #[test]
+    fn bad_columns() {
+        let (ok, _, err) = rewrite(&["-c", "0", "-f", "unix"], "1\n");
+        assert!(!ok);
+        assert!(err.contains("numbered from 1"));
+        let (ok, _, err) = rewrite(&["-c", "when", "-f", "unix"], "1\n");
+        assert!(!ok);
+        assert!(err.contains("--header"));
+    }
+}
@@ -0,0 +1,113 @@
+//! AmigaOS `DateStamp` times.
+
+use chrono::{DateTime, NaiveDateTime, Timelike};
+
+/// Day zero of a `DateStamp`, 1978-01-01, is 2,922 days after the Unix
+/// epoch.
+const AMIGA_DAYS: i64 = 2_922;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// Ticks are fiftieths of a second.
+const TICKS_PER_SECOND: i64 = 50;
+
+const TICKS_PER_MINUTE: i64 = 60 * TICKS_PER_SECOND;
+
+/// AmigaOS time (`struct DateStamp`) is three numbers: the days since
+/// 1978-01-01, the minutes since midnight, and the ticks (fiftieths of
+/// a second) since the start of the minute. Returns None if the
+/// minutes are not within a day or the ticks are not within a minute.
+///
+/// ```
+/// use epochs::amiga;
+/// let ndt = amiga(11366, 1411, 1500).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn amiga(days: i64, minutes: i64, ticks: i64) -> Option<NaiveDateTime> {
+    if !(0..MINUTES_PER_DAY).contains(&minutes) || !(0..TICKS_PER_MINUTE).contains(&ticks) {
+        return None;
+    }
+    let t = days
+        .checked_add(AMIGA_DAYS)?
+        .checked_mul(MINUTES_PER_DAY)?
+        .checked_add(minutes)?
+        .checked_mul(60)?
+        .checked_add(ticks / TICKS_PER_SECOND)?;
+    let nanos = (ticks % TICKS_PER_SECOND) as u32 * (1_000_000_000 / TICKS_PER_SECOND as u32);
+    DateTime::from_timestamp(t, nanos).map(|dt| dt.naive_utc())
+}
+
+/// Convert the given NaiveDateTime to an [AmigaOS](fn.amiga.html)
+/// time, returning the days, minutes, and ticks. Fractions of a tick
+/// are dropped.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_amiga;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_amiga(ndt), (11366, 1411, 1500));
+/// ```
+pub fn to_amiga(ndt: NaiveDateTime) -> (i64, i64, i64) {
+    let t = ndt.and_utc().timestamp();
+    let minutes = t.div_euclid(60);
+    let ticks = t.rem_euclid(60) * TICKS_PER_SECOND
+        + i64::from(ndt.nanosecond().min(999_999_999)) / (1_000_000_000 / TICKS_PER_SECOND);
+    (
+        minutes.div_euclid(MINUTES_PER_DAY) - AMIGA_DAYS,
+        minutes.rem_euclid(MINUTES_PER_DAY),
+        ticks,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn amiga_day_zero() {
+        let ndt = amiga(0, 0, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1978-01-01 00:00:00");
+    }
+    #[test]
+    fn amiga_ticks() {
+        let ndt = amiga(0, 0, 2999).unwrap();
+        assert_eq!(ndt.to_string(), "1978-01-01 00:00:59.980");
+    }
+    #[test]
+    fn amiga_before_1978() {
+        let ndt = amiga(-1, 1439, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1977-12-31 23:59:00");
+    }
+    #[test]
+    fn amiga_bad_fields() {
+        assert!(amiga(0, -1, 0).is_none());
+        assert!(amiga(0, 1440, 0).is_none());
+        assert!(amiga(0, 0, -1).is_none());
+        assert!(amiga(0, 0, 3000).is_none());
+    }
+    #[test]
+    fn amiga_too_big() {
+        assert!(amiga(i64::MAX, 0, 0).is_none());
+        assert!(amiga(i64::MIN, 0, 0).is_none());
+    }
+    #[test]
+    fn to_amiga_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(1977, 12, 31)
+            .unwrap()
+            .and_hms_milli_opt(23, 59, 59, 980)
+            .unwrap();
+        assert_eq!(to_amiga(ndt), (-1, 1439, 2999));
+        assert_eq!(amiga(-1, 1439, 2999), Some(ndt));
+    }
+    #[test]
+    fn to_amiga_drops_fraction() {
+        let ndt = NaiveDate::from_ymd_opt(1978, 1, 1)
+            .unwrap()
+            .and_hms_milli_opt(0, 0, 0, 39)
+            .unwrap();
+        assert_eq!(to_amiga(ndt), (0, 0, 1));
+    }
+}
@@ -0,0 +1,105 @@
+//! Convert whole [Apache Arrow](https://arrow.apache.org) arrays of
+//! epoch values, available with the `arrow` feature.
+
+use arrow_array::{Array, Int64Array, TimestampNanosecondArray};
+use chrono::DateTime;
+
+use crate::{convert_slice, Epoch};
+
+/// Convert an array of values in the given epoch to an array of
+/// nanosecond timestamps. Null values stay null, as do values that
+/// cannot be converted or that fall outside the range of nanosecond
+/// timestamps (roughly 1677 to 2262). The values are converted all
+/// together, as by [convert_slice](../fn.convert_slice.html).
+///
+/// ```
+/// use arrow_array::{Array, Int64Array};
+/// use epochs::{arrow::to_timestamp_array, Epoch};
+/// let input = Int64Array::from(vec![Some(12_879_041_490_000_000), None]);
+/// let output = to_timestamp_array(Epoch::Chrome, &input);
+/// assert_eq!(output.value(0), 1_234_567_890_000_000_000);
+/// assert!(output.is_null(1));
+/// ```
+pub fn to_timestamp_array(epoch: Epoch, input: &Int64Array) -> TimestampNanosecondArray {
+    // Convert the whole buffer at once, then put the nulls back.
+    let mut out = Vec::with_capacity(input.len());
+    convert_slice(epoch, input.values(), &mut out);
+    out.into_iter()
+        .enumerate()
+        .map(|(i, ndt)| {
+            if input.is_valid(i) {
+                ndt?.and_utc().timestamp_nanos_opt()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Convert an array of nanosecond timestamps to an array of values in
+/// the given epoch. Null values stay null.
+///
+/// ```
+/// use arrow_array::{Array, TimestampNanosecondArray};
+/// use epochs::{arrow::from_timestamp_array, Epoch};
+/// let input = TimestampNanosecondArray::from(vec![Some(1_234_567_890_000_000_000), None]);
+/// let output = from_timestamp_array(Epoch::Chrome, &input);
+/// assert_eq!(output.value(0), 12_879_041_490_000_000);
+/// assert!(output.is_null(1));
+/// ```
+pub fn from_timestamp_array(epoch: Epoch, input: &TimestampNanosecondArray) -> Int64Array {
+    let f = epoch.inverter();
+    let values: Vec<_> = input
+        .values()
+        .iter()
+        .map(|&nanos| f(DateTime::from_timestamp_nanos(nanos).naive_utc()))
+        .collect();
+    Int64Array::new(values.into(), input.nulls().cloned())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn to_timestamp_array_out_of_range() {
+        let input = Int64Array::from(vec![0, 1234567890, i64::MAX]);
+        let output = to_timestamp_array(Epoch::Unix, &input);
+        assert_eq!(output.len(), 3);
+        assert_eq!(output.value(1), 1_234_567_890_000_000_000);
+        assert!(output.is_null(2));
+    }
+    #[test]
+    fn to_timestamp_array_not_representable() {
+        // Cocoa's year 0 is fine as a NaiveDateTime, but not as nanoseconds.
+        let input = Int64Array::from(vec![-63_000_000_000]);
+        let output = to_timestamp_array(Epoch::Cocoa, &input);
+        assert!(output.is_null(0));
+    }
+    #[test]
+    fn nulls_stay_null() {
+        // The zero under the null would convert, but must not show through.
+        let input = Int64Array::from(vec![Some(1234567890), None]);
+        assert_eq!(input.values()[1], 0);
+        let output = to_timestamp_array(Epoch::Unix, &input);
+        assert!(output.is_valid(0));
+        assert!(output.is_null(1));
+        let output = from_timestamp_array(Epoch::Unix, &output);
+        assert!(output.is_valid(0));
+        assert!(output.is_null(1));
+    }
+    #[test]
+    fn round_trip() {
+        let input = Int64Array::from(vec![
+            Some(128790414900000000),
+            None,
+            Some(0x1cabbaa00ca9000),
+        ]);
+        let output = from_timestamp_array(
+            Epoch::WindowsFile,
+            &to_timestamp_array(Epoch::WindowsFile, &input),
+        );
+        assert_eq!(output, input);
+    }
+}
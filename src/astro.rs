@@ -0,0 +1,286 @@
+//! Astronomical epochs: J2000.0, Julian epoch years, and Besselian
+//! years.
+//!
+//! These count from noon on 2000-01-01 in whatever time scale the
+//! datetime is in (for J2000.0 that is properly Terrestrial Time); no
+//! time scale conversion is done by them. To get from UTC to TT or TDB
+//! and back, use [utc_to_tt](fn.utc_to_tt.html) and the like.
+
+use chrono::{DateTime, Duration, NaiveDateTime};
+
+use crate::leap::{from_tai_nanos, from_unix_nanos, to_tai_nanos, NANOS};
+
+/// TT is ahead of TAI by exactly 32.184 seconds.
+const TT_TAI_NANOS: i128 = 32_184_000_000;
+
+/// J2000.0, 2000-01-01 12:00:00, as a Unix time.
+const J2000: i64 = 946_728_000;
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// The Julian year, exactly 365.25 days.
+const JULIAN_YEAR: f64 = 365.25;
+
+/// The tropical year at B1900.0, in days (Lieske 1979).
+const BESSELIAN_YEAR: f64 = 365.242_198_781;
+
+/// B1900.0, JD 2415020.31352, in days from J2000.0 (JD 2451545.0).
+const B1900: f64 = -36_524.686_48;
+
+/// A NaiveDateTime from (possibly fractional) seconds since J2000.0,
+/// rounded to the nearest nanosecond.
+fn from_j2000(seconds: f64) -> Option<NaiveDateTime> {
+    if !seconds.is_finite() || seconds.abs() >= 1e16 {
+        return None;
+    }
+    let whole = seconds.floor();
+    let nanos = ((seconds - whole) * 1e9).round() as i64;
+    let t = (whole as i64).checked_add(J2000)? + nanos / 1_000_000_000;
+    DateTime::from_timestamp(t, (nanos % 1_000_000_000) as u32).map(|dt| dt.naive_utc())
+}
+
+/// J2000 seconds are the number of SI seconds since J2000.0, noon on
+/// 2000-01-01 (*e.g.*, SPICE's ephemeris time).
+///
+/// ```
+/// use epochs::j2000_seconds;
+/// let ndt = j2000_seconds(287_839_890.5).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.500");
+/// ```
+pub fn j2000_seconds(seconds: f64) -> Option<NaiveDateTime> {
+    from_j2000(seconds)
+}
+
+/// Convert the given NaiveDateTime to [J2000
+/// seconds](fn.j2000_seconds.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_j2000_seconds;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_j2000_seconds(ndt), 287_839_890.5);
+/// ```
+pub fn to_j2000_seconds(ndt: NaiveDateTime) -> f64 {
+    let dt = ndt.and_utc();
+    (dt.timestamp() - J2000) as f64 + f64::from(dt.timestamp_subsec_nanos()) / 1e9
+}
+
+/// A Julian epoch year (*e.g.*, J2015.5) counts Julian years of
+/// exactly 365.25 days from J2000.0.
+///
+/// ```
+/// use epochs::julian_epoch_year;
+/// let ndt = julian_epoch_year(2015.5).unwrap();
+/// assert_eq!(ndt.to_string(), "2015-07-02 21:00:00");
+/// ```
+pub fn julian_epoch_year(year: f64) -> Option<NaiveDateTime> {
+    from_j2000((year - 2000.0) * JULIAN_YEAR * SECONDS_PER_DAY)
+}
+
+/// Convert the given NaiveDateTime to a [Julian epoch
+/// year](fn.julian_epoch_year.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_julian_epoch_year;
+/// let ndt = NaiveDateTime::parse_from_str("2015-07-02 21:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_julian_epoch_year(ndt), 2015.5);
+/// ```
+pub fn to_julian_epoch_year(ndt: NaiveDateTime) -> f64 {
+    2000.0 + to_j2000_seconds(ndt) / SECONDS_PER_DAY / JULIAN_YEAR
+}
+
+/// A Besselian year (*e.g.*, B1950.0) counts tropical years of
+/// 365.242198781 days from B1900.0, using the usual modern definition
+/// B = 1900.0 + (JD - 2415020.31352) / 365.242198781.
+///
+/// ```
+/// use epochs::besselian_year;
+/// let ndt = besselian_year(1950.0).unwrap();
+/// assert_eq!(ndt.format("%F %T").to_string(), "1949-12-31 22:09:46");
+/// ```
+pub fn besselian_year(year: f64) -> Option<NaiveDateTime> {
+    let days = B1900 + (year - 1900.0) * BESSELIAN_YEAR;
+    from_j2000(days * SECONDS_PER_DAY)
+}
+
+/// Convert the given NaiveDateTime to a [Besselian
+/// year](fn.besselian_year.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_besselian_year;
+/// let ndt = NaiveDateTime::parse_from_str("2000-01-01 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert!((to_besselian_year(ndt) - 2000.001_277_513_8).abs() < 1e-9);
+/// ```
+pub fn to_besselian_year(ndt: NaiveDateTime) -> f64 {
+    let days = to_j2000_seconds(ndt) / SECONDS_PER_DAY;
+    1900.0 + (days - B1900) / BESSELIAN_YEAR
+}
+
+/// Convert the given UTC NaiveDateTime to Terrestrial Time (TT),
+/// which is TAI + 32.184 seconds, using the leap second table. The
+/// result is a NaiveDateTime in the TT time scale, with no leap
+/// seconds.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::utc_to_tt;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(utc_to_tt(ndt).unwrap().to_string(), "2009-02-13 23:32:36.184");
+/// ```
+pub fn utc_to_tt(ndt: NaiveDateTime) -> Option<NaiveDateTime> {
+    from_unix_nanos(to_tai_nanos(ndt) + TT_TAI_NANOS)
+}
+
+/// Convert the given Terrestrial Time (see [utc_to_tt](fn.utc_to_tt.html))
+/// to UTC.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::tt_to_utc;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:32:36.184", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(tt_to_utc(ndt).unwrap().to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn tt_to_utc(ndt: NaiveDateTime) -> Option<NaiveDateTime> {
+    let dt = ndt.and_utc();
+    let t = i128::from(dt.timestamp()) * NANOS + i128::from(dt.timestamp_subsec_nanos());
+    from_tai_nanos(t - TT_TAI_NANOS)
+}
+
+/// TDB - TT in seconds, from the usual two-term approximation (good
+/// to about 30 microseconds), with g the Earth's mean anomaly.
+fn tdb_minus_tt(ndt: NaiveDateTime) -> f64 {
+    let days = to_j2000_seconds(ndt) / SECONDS_PER_DAY;
+    let g = (357.53 + 0.985_600_28 * days).to_radians();
+    0.001_657 * g.sin() + 0.000_014 * (2.0 * g).sin()
+}
+
+/// Convert the given Terrestrial Time to Barycentric Dynamical Time
+/// (TDB), which differs from it periodically by at most about 1.7 ms.
+/// This uses the usual two-term approximation, so it is good to about
+/// 30 microseconds.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::tt_to_tdb;
+/// let ndt = NaiveDateTime::parse_from_str("2000-01-01 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let tdb = tt_to_tdb(ndt).unwrap();
+/// assert_eq!(tdb.format("%T%.6f").to_string(), "11:59:59.999927");
+/// ```
+pub fn tt_to_tdb(ndt: NaiveDateTime) -> Option<NaiveDateTime> {
+    let nanos = (tdb_minus_tt(ndt) * 1e9).round() as i64;
+    ndt.checked_add_signed(Duration::nanoseconds(nanos))
+}
+
+/// Convert the given Barycentric Dynamical Time (see
+/// [tt_to_tdb](fn.tt_to_tdb.html)) to Terrestrial Time.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::{tdb_to_tt, tt_to_tdb};
+/// let tt = NaiveDateTime::parse_from_str("2009-02-13 23:32:36.184", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(tdb_to_tt(tt_to_tdb(tt).unwrap()), Some(tt));
+/// ```
+pub fn tdb_to_tt(ndt: NaiveDateTime) -> Option<NaiveDateTime> {
+    // The difference changes so slowly that evaluating it at TDB rather
+    // than TT makes no difference at this precision.
+    let nanos = (tdb_minus_tt(ndt) * 1e9).round() as i64;
+    ndt.checked_sub_signed(Duration::nanoseconds(nanos))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn j2000_epoch() {
+        let ndt = j2000_seconds(0.0).unwrap();
+        assert_eq!(ndt.to_string(), "2000-01-01 12:00:00");
+        assert_eq!(julian_epoch_year(2000.0), Some(ndt));
+        assert_eq!(to_julian_epoch_year(ndt), 2000.0);
+    }
+    #[test]
+    fn j2000_negative() {
+        let ndt = j2000_seconds(-0.25).unwrap();
+        assert_eq!(ndt.to_string(), "2000-01-01 11:59:59.750");
+        assert_eq!(to_j2000_seconds(ndt), -0.25);
+    }
+    #[test]
+    fn j2000_not_finite() {
+        assert!(j2000_seconds(f64::NAN).is_none());
+        assert!(j2000_seconds(f64::INFINITY).is_none());
+        assert!(j2000_seconds(1e300).is_none());
+    }
+    #[test]
+    fn julian_years() {
+        // J1900.0 is JD 2415020.0, 1899-12-31 12:00.
+        let ndt = julian_epoch_year(1900.0).unwrap();
+        assert_eq!(ndt.to_string(), "1899-12-31 12:00:00");
+        let ndt = julian_epoch_year(2100.0).unwrap();
+        assert_eq!(ndt.to_string(), "2100-01-01 12:00:00");
+    }
+    #[test]
+    fn besselian_epochs() {
+        // B1900.0 is JD 2415020.31352.
+        let ndt = besselian_year(1900.0).unwrap();
+        let jd = NaiveDate::from_ymd_opt(1899, 12, 31)
+            .unwrap()
+            .and_hms_milli_opt(19, 31, 28, 128)
+            .unwrap();
+        assert!((ndt - jd).num_microseconds().unwrap().abs() < 1);
+        assert!((to_besselian_year(ndt) - 1900.0).abs() < 1e-12);
+    }
+    #[test]
+    fn besselian_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(1975, 6, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let back = besselian_year(to_besselian_year(ndt)).unwrap();
+        assert!((back - ndt).num_microseconds().unwrap().abs() < 10);
+    }
+    #[test]
+    fn tt_at_j2000() {
+        // J2000.0 is 2000-01-01 12:00:00 TT, 11:58:55.816 UTC.
+        let utc = NaiveDate::from_ymd_opt(2000, 1, 1)
+            .unwrap()
+            .and_hms_milli_opt(11, 58, 55, 816)
+            .unwrap();
+        let tt = utc_to_tt(utc).unwrap();
+        assert_eq!(to_j2000_seconds(tt), 0.0);
+        assert_eq!(tt_to_utc(tt), Some(utc));
+    }
+    #[test]
+    fn tt_over_leap_second() {
+        let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_milli_opt(23, 59, 59, 1_500)
+            .unwrap();
+        let tt = utc_to_tt(leap).unwrap();
+        assert_eq!(tt.to_string(), "2017-01-01 00:01:08.684");
+        assert_eq!(tt_to_utc(tt), Some(leap));
+    }
+    #[test]
+    fn tdb_stays_close() {
+        for month in 1..=12 {
+            let tt = NaiveDate::from_ymd_opt(2024, month, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap();
+            let tdb = tt_to_tdb(tt).unwrap();
+            assert!((tdb - tt).num_microseconds().unwrap().abs() <= 1_671);
+            let back = tdb_to_tt(tdb).unwrap();
+            assert!((back - tt).num_nanoseconds().unwrap().abs() <= 1);
+        }
+    }
+}
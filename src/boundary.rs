@@ -0,0 +1,404 @@
+//! Boundary tests feeding extreme inputs to every public conversion,
+//! to check that none of them panics.
+
+use std::num::NonZeroU32;
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime};
+
+use crate::*;
+
+fn ints() -> Vec<i64> {
+    let mut nums = vec![
+        i64::MIN,
+        i64::MIN + 1,
+        i64::MAX - 1,
+        i64::MAX,
+        i32::MIN.into(),
+        i32::MAX.into(),
+        u32::MAX.into(),
+        -1,
+        0,
+        1,
+    ];
+    for shift in (8..63).step_by(4) {
+        nums.push(1 << shift);
+        nums.push(-(1 << shift));
+        nums.push((1 << shift) - 1);
+    }
+    nums
+}
+
+fn floats() -> Vec<f64> {
+    let mut nums = vec![
+        f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::MAX,
+        f64::MIN,
+        f64::MIN_POSITIVE,
+        f64::EPSILON,
+        -0.0,
+        0.0,
+        0.5,
+        -0.5,
+    ];
+    for exp in [1.0, 5.0, 9.0, 12.0, 15.0, 18.0, 18.96, 19.0, 300.0] {
+        nums.push(10f64.powf(exp));
+        nums.push(-(10f64.powf(exp)));
+    }
+    nums.extend(ints().into_iter().map(|n| n as f64));
+    nums
+}
+
+fn datetimes() -> Vec<NaiveDateTime> {
+    let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+        .unwrap()
+        .and_hms_nano_opt(23, 59, 59, 1_999_999_999)
+        .unwrap();
+    let last_leap = NaiveDate::MAX
+        .and_hms_nano_opt(23, 59, 59, 1_999_999_999)
+        .unwrap();
+    vec![
+        NaiveDateTime::MIN,
+        NaiveDateTime::MAX,
+        last_leap,
+        leap,
+        DateTime::UNIX_EPOCH.naive_utc(),
+        NaiveDate::from_ymd_opt(1, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap(),
+        NaiveDate::from_ymd_opt(-1, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap(),
+    ]
+}
+
+fn offsets() -> Vec<FixedOffset> {
+    vec![
+        FixedOffset::east_opt(0).unwrap(),
+        FixedOffset::east_opt(86_399).unwrap(),
+        FixedOffset::west_opt(86_399).unwrap(),
+    ]
+}
+
+fn units() -> Vec<Unit> {
+    vec![
+        Unit::Seconds,
+        Unit::Milliseconds,
+        Unit::Hectonanoseconds,
+        Unit::Nanoseconds,
+        Unit::PerSecond(NonZeroU32::MIN),
+        Unit::PerSecond(NonZeroU32::new(300).unwrap()),
+        Unit::PerSecond(NonZeroU32::MAX),
+    ]
+}
+
+fn durations() -> Vec<Duration> {
+    vec![
+        Duration::MIN,
+        Duration::MAX,
+        Duration::zero(),
+        Duration::seconds(-1),
+    ]
+}
+
+#[test]
+fn integer_inputs() {
+    for n in ints() {
+        let i = n as i32;
+        let u = n as u32;
+        let w = n as u64;
+        for epoch in Epoch::ALL {
+            let _ = epoch.to_datetime(n);
+            let _ = epoch.try_to_datetime(n);
+            let _ = format(epoch, n, "%+");
+            #[cfg(feature = "clock")]
+            let _ = epoch.to_local(n);
+            let _ = verify_roundtrip(epoch, n);
+            let _ = duration_between(epoch, n, Epoch::Unix, n);
+            let _ = saturating_duration_between(epoch, n, Epoch::Unix, n);
+            let _ = bytes::from_le_bytes(epoch, &n.to_le_bytes());
+            let _ = bytes::from_be_bytes(epoch, &n.to_be_bytes());
+            for to in Epoch::ALL {
+                let _ = rescale(n, epoch, to);
+            }
+        }
+        for unit in units() {
+            for offset in [i64::MIN, 0, i64::MAX, n] {
+                let custom = CustomEpoch::new(unit, offset);
+                let _ = custom.to_datetime(n);
+                let _ = custom.try_to_datetime(n);
+            }
+        }
+        let _ = convert_vec(Epoch::Chrome, &[n]);
+        let _ = guess(n);
+        let _ = view_all(n);
+        for m in [i64::MIN, -1, 0, 1, i64::MAX] {
+            let _ = amiga(n, m, m);
+            let _ = amiga(m, n, m);
+            let _ = amiga(m, m, n);
+            let _ = horolog(n, m);
+            let _ = horolog(m, n);
+        }
+        let _ = filemaker(n);
+        let _ = google_calendar(n);
+        let _ = tandem(n);
+        let _ = cdma(n);
+        let _ = cdma_frames(n);
+        let _ = dotnet_binary(n);
+        let _ = excel_1900_date(n);
+        let _ = excel_1904_date(n);
+        let _ = mjd_days(n);
+        let _ = rata_die(n);
+        let _ = sas_date(n);
+        let _ = stata_date(n);
+        let _ = tai(n);
+        let _ = unix_right(n);
+        let _ = java_instant(n, u);
+        let _ = java_instant(n, 999_999_999);
+        let _ = labview(n, w);
+        let _ = ptp(n, u);
+        let _ = ptp_utc(n, 0, i);
+        let _ = ptp_utc(n, 999_999_999, i32::MIN);
+        let _ = ext4(i, u);
+        let _ = unix_i32(i);
+        let _ = unix_u32(u);
+        let _ = garmin_fit(u);
+        let _ = palm(u);
+        let _ = palm_or_unix(u);
+        let _ = zigbee(u);
+        let _ = windows_file_parts(u, u);
+        let _ = quicktime(w);
+        let _ = ntp(w);
+        let _ = ntp_date(u128::from(w) << 64 | u128::from(w));
+        let _ = ntp_short(u);
+        let _ = tod_clock(w);
+        let _ = tod_clock_extended(u128::from(w) << 64 | u128::from(w));
+        let _ = as400_dts(w);
+        let _ = mssql_datetime(i, i);
+        let _ = mssql_smalldatetime(n as u16, u as u16);
+        let _ = glonass(u, u, 0.0);
+        let _ = y2038_headroom(i);
+        let _ = y2038_unwrap(i, NaiveDateTime::MAX);
+        let _ = y2038_unwrap(i, NaiveDateTime::MIN);
+        for margin in durations() {
+            let _ = y2038_at_risk(i, margin);
+        }
+        let _ = mssql_datetime2(&n.to_le_bytes(), 7);
+        let _ = mssql_datetimeoffset(&n.to_le_bytes(), 0);
+        let _ = parse_value(&n.to_string());
+        let _ = parse_hex(&format!("{:x}", n));
+        // The epoch types, but not their operators, which panic on
+        // overflow like the integer ones.
+        for m in [i64::MIN, -1, 1, i64::MAX] {
+            let _ = UnixSeconds(n).add_seconds(m);
+            let _ = JavaMillis(n).add_millis(m);
+            let _ = ChromeMicros(n).add_micros(m);
+            let _ = WindowsFileTime(n).add_ticks(m);
+            let _ = ApfsNanos(n).add_nanos(m);
+        }
+        for d in durations() {
+            let _ = ApfsNanos(n).checked_add_duration(d);
+            let _ = UnixSeconds(n).checked_sub_duration(d);
+        }
+        let _ = NaiveDateTime::try_from(ApfsNanos(n));
+        let _ = UnixSeconds(n).to_datetime();
+    }
+}
+
+#[test]
+fn float_inputs() {
+    for x in floats() {
+        let _ = cocoa_f64(x);
+        let _ = icq(x);
+        let _ = excel_1900(x);
+        let _ = excel_1904(x);
+        let _ = j2000_seconds(x);
+        let _ = julian_epoch_year(x);
+        let _ = besselian_year(x);
+        let _ = bplist_date(&x.to_be_bytes());
+        for week in [i32::MIN, -1, 0, 1, i32::MAX] {
+            let _ = gps_week_tow(week, x);
+            let _ = galileo_st(week, x);
+            let _ = beidou_t(week, x);
+        }
+        for near in [NaiveDateTime::MIN, NaiveDateTime::MAX] {
+            let _ = gps_week_tow_near(0, x, near);
+            let _ = gps_week_tow_near(u16::MAX, x, near);
+        }
+        let _ = glonass(1, 1, x);
+        let _ = glonass(u32::MAX, u32::MAX, x);
+        let _ = parse_value(&x.to_string());
+    }
+}
+
+#[test]
+fn datetime_inputs() {
+    for ndt in datetimes() {
+        for epoch in Epoch::ALL {
+            let _ = epoch.from_datetime(ndt);
+            let _ = epoch.try_from_datetime(ndt);
+            let _ = epoch.from_datetime_saturating(ndt);
+            let _ = bytes::to_le_bytes(epoch, ndt);
+            let _ = bytes::to_be_bytes(epoch, ndt);
+        }
+        for unit in units() {
+            for offset in [i64::MIN, 0, i64::MAX] {
+                let custom = CustomEpoch::new(unit, offset);
+                let _ = custom.from_datetime(ndt);
+                let _ = custom.try_from_datetime(ndt);
+            }
+        }
+        let _ = guess_in_range(0, ndt, NaiveDateTime::MAX);
+        let _ = to_amiga(ndt);
+        let _ = to_as400_dts(ndt);
+        let _ = to_bcd_rtc(ndt, false);
+        let _ = to_bcd_rtc(ndt, true);
+        let _ = to_beidou_t(ndt);
+        let _ = to_besselian_year(ndt);
+        let _ = to_bplist_date(ndt);
+        let _ = to_cdma(ndt);
+        let _ = to_cdma_frames(ndt);
+        let _ = to_cocoa_f64(ndt);
+        let _ = to_dotnet_binary(ndt, DotnetKind::Utc);
+        let _ = to_dotnet_binary(ndt, DotnetKind::Local);
+        let _ = to_excel_1900(ndt);
+        let _ = to_excel_1900_date(ndt);
+        let _ = to_excel_1904(ndt);
+        let _ = to_excel_1904_date(ndt);
+        let _ = to_ext4(ndt);
+        let _ = to_fat(ndt);
+        let _ = to_filemaker(ndt);
+        let _ = to_filemaker_saturating(ndt);
+        let _ = to_galileo_st(ndt);
+        let _ = to_garmin_fit(ndt);
+        let _ = to_garmin_fit_saturating(ndt);
+        let _ = to_glonass(ndt);
+        let _ = to_google_calendar(ndt);
+        let _ = to_gps_week_tow(ndt);
+        let _ = to_horolog(ndt);
+        let _ = to_icq(ndt);
+        let _ = to_j2000_seconds(ndt);
+        let _ = to_java_instant(ndt);
+        let _ = to_julian_epoch_year(ndt);
+        let _ = to_labview(ndt);
+        let _ = to_mjd_days(ndt);
+        let _ = to_mssql_datetime(ndt);
+        let _ = to_mssql_smalldatetime(ndt);
+        let _ = to_ntp(ndt);
+        let _ = to_ntp_date(ndt);
+        let _ = to_oracle_date(ndt);
+        let _ = to_oracle_timestamp(ndt);
+        let _ = to_palm(ndt);
+        let _ = to_parquet_int96(ndt);
+        let _ = to_palm_saturating(ndt);
+        let _ = to_ptp(ndt);
+        let _ = to_ptp_utc(ndt, i32::MIN);
+        let _ = to_ptp_utc(ndt, i32::MAX);
+        let _ = to_quicktime(ndt);
+        let _ = to_quicktime_saturating(ndt);
+        let _ = to_rata_die(ndt);
+        let _ = to_sas_date(ndt);
+        let _ = to_stata_date(ndt);
+        let _ = to_tai(ndt);
+        let _ = to_tandem(ndt);
+        let _ = to_tod_clock(ndt);
+        let _ = to_tod_clock_extended(ndt);
+        let _ = to_unix_i32(ndt);
+        let _ = to_unix_i32_saturating(ndt);
+        let _ = to_unix_right(ndt);
+        let _ = to_unix_u32(ndt);
+        let _ = to_unix_u32_saturating(ndt);
+        let _ = to_windows_file_parts(ndt);
+        let _ = to_zigbee(ndt);
+        let _ = to_zigbee_saturating(ndt);
+        let _ = datetime_to_systemtime(ndt);
+        let _ = utc_tai_offset(ndt);
+        let _ = tt_to_tdb(ndt);
+        let _ = tdb_to_tt(ndt);
+        let _ = tt_to_utc(ndt);
+        let _ = utc_to_tt(ndt);
+        for scale in [0, 7, 8, u8::MAX] {
+            let _ = to_mssql_datetime2(ndt, scale);
+        }
+        for offset in offsets() {
+            if let Some(dt) = ndt.and_local_timezone(offset).single() {
+                let _ = to_bcd_rtc_with_offset(dt, false);
+                let _ = to_exfat_with_offset(dt);
+                let _ = to_fat_with_offset(dt);
+                let _ = datetime_to_systemtime_with_offset(dt);
+            }
+            let _ = to_exfat(ndt, Some(offset));
+            let _ = to_go_binary(ndt, offset);
+            let _ = to_gsm_scts(ndt, offset);
+            let _ = to_iso9660_dec_datetime(ndt, offset);
+            let _ = to_iso9660_dir_datetime(ndt, offset);
+            for scale in [0, 7, 8, u8::MAX] {
+                let _ = to_mssql_datetimeoffset(ndt, offset, scale);
+            }
+        }
+        for window in durations() {
+            let _ = utc_to_smeared(ndt, window);
+            let _ = smeared_to_utc(ndt, window);
+            let _ = y2038_at_risk_datetime(ndt, window);
+        }
+        for d in durations() {
+            let _ = to_ntp_short(d);
+            for unit in units() {
+                let _ = unit.from_duration(d);
+            }
+        }
+        let _ = gps_week_tow_near(0, 0.0, ndt);
+    }
+}
+
+#[test]
+fn byte_inputs() {
+    for byte in [0x00, 0x01, 0x09, 0x0a, 0x59, 0x7f, 0x80, 0x99, 0xff] {
+        let _ = oracle_date(&[byte; 7]);
+        let _ = parquet_int96(&[byte; 12]);
+        let _ = oracle_timestamp(&[byte; 11]);
+        let _ = gsm_scts(&[byte; 7]);
+        let _ = iso9660_dir_datetime(&[byte; 7]);
+        let _ = iso9660_dec_datetime(&[byte; 17]);
+        let _ = systemtime_from_le_bytes(&[byte; 16]);
+        #[cfg(feature = "clock")]
+        {
+            let _ = systemtime_from_le_bytes_local(&[byte; 16]);
+            let _ = bcd_rtc_local(&[byte; 6]);
+            let _ = fat_local(u16::from(byte) << 8, u16::from(byte), Some(byte));
+            let _ = exfat_local(u32::from(byte) << 24, byte, byte as i8);
+        }
+        for offset in offsets() {
+            let _ = systemtime_from_le_bytes_with_offset(&[byte; 16], offset);
+            let _ = bcd_rtc_with_offset(&[byte; 6], offset);
+            let _ = fat_with_offset(u16::from(byte) << 8, u16::from(byte), Some(byte), offset);
+            let _ = exfat_with_offset(u32::from(byte) << 24, byte, byte as i8, offset);
+        }
+        let _ = bplist_date(&[byte; 8]);
+        for len in [0, 5, 6, 7, 8, 10, 15, 16, 20] {
+            let bytes = vec![byte; len];
+            let _ = bcd_rtc(&bytes);
+            let _ = go_binary(&bytes);
+            for scale in [0, 3, 7, 8, u8::MAX] {
+                let _ = mssql_datetime2(&bytes, scale);
+                let _ = mssql_datetimeoffset(&bytes, scale);
+            }
+            for epoch in Epoch::ALL {
+                let _ = bytes::from_le_bytes(epoch, &bytes);
+                let _ = bytes::from_be_bytes(epoch, &bytes);
+                for stride in [0, 1, 7, 8, 9, usize::MAX] {
+                    bytes::from_le_bytes_strided(epoch, &bytes, stride).for_each(drop);
+                }
+            }
+        }
+        let field = u16::from(byte) << 8 | u16::from(byte);
+        let _ = systemtime_to_datetime(field, field, field, field, field, field, field);
+        for date in [0, 0x21, 0xffff] {
+            let _ = fat(date, u16::from(byte) << 8, Some(byte));
+            let _ = exfat(u32::from(date) << 16, byte, byte as i8);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Convert raw bytes, as found on disk or on the wire, with an
+//! explicit byte order.
+//!
+//! Values may be 4, 8, or 16 bytes wide. Values narrower than 8 bytes
+//! are sign-extended for epochs which use negative values (see
+//! [EpochInfo](../struct.EpochInfo.html)) and zero-extended for the
+//! rest. Values wider than 8 bytes must fit in an i64.
+
+use chrono::NaiveDateTime;
+
+use crate::{Epoch, GUESS_FROM, GUESS_TO};
+
+/// Convert the given little-endian bytes in the given epoch.
+///
+/// ```
+/// use epochs::{bytes::from_le_bytes, Epoch};
+/// // An 8-byte FILETIME, as it appears in an NTFS $STANDARD_INFORMATION attribute.
+/// let raw = [0x00, 0x90, 0xca, 0x00, 0xaa, 0xbb, 0xca, 0x01];
+/// let ndt = from_le_bytes(Epoch::WindowsFile, &raw).unwrap();
+/// assert_eq!(ndt.to_string(), "2010-03-04 14:50:16.559001600");
+/// ```
+pub fn from_le_bytes(epoch: Epoch, bytes: &[u8]) -> Option<NaiveDateTime> {
+    epoch.to_datetime(to_i64(epoch, bytes, Order::Little)?)
+}
+
+/// Convert the given big-endian bytes in the given epoch.
+///
+/// ```
+/// use epochs::{bytes::from_be_bytes, Epoch};
+/// let ndt = from_be_bytes(Epoch::Unix, &[0x49, 0x96, 0x02, 0xd2]).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn from_be_bytes(epoch: Epoch, bytes: &[u8]) -> Option<NaiveDateTime> {
+    epoch.to_datetime(to_i64(epoch, bytes, Order::Big)?)
+}
+
+/// Convert the given NaiveDateTime to 8 little-endian bytes in the
+/// given epoch.
+///
+/// ```
+/// use epochs::{bytes::{from_le_bytes, to_le_bytes}, Epoch};
+/// let ndt = epochs::unix(1234567890).unwrap();
+/// let raw = to_le_bytes(Epoch::WindowsFile, ndt);
+/// assert_eq!(from_le_bytes(Epoch::WindowsFile, &raw), Some(ndt));
+/// ```
+pub fn to_le_bytes(epoch: Epoch, ndt: NaiveDateTime) -> [u8; 8] {
+    epoch.from_datetime(ndt).to_le_bytes()
+}
+
+/// Convert the given NaiveDateTime to 8 big-endian bytes in the given
+/// epoch.
+///
+/// ```
+/// use epochs::{bytes::to_be_bytes, Epoch};
+/// let ndt = epochs::unix(1234567890).unwrap();
+/// assert_eq!(to_be_bytes(Epoch::Unix, ndt), [0, 0, 0, 0, 0x49, 0x96, 0x02, 0xd2]);
+/// ```
+pub fn to_be_bytes(epoch: Epoch, ndt: NaiveDateTime) -> [u8; 8] {
+    epoch.from_datetime(ndt).to_be_bytes()
+}
+
+/// Convert a column of 8-byte little-endian values in the given epoch,
+/// straight out of the given bytes (*e.g.*, a memory-mapped disk
+/// image) without copying them into a vector first. The values begin
+/// every `stride` bytes; to start partway into the first record, slice
+/// the bytes first. A last record shorter than the stride is still read
+/// if the value itself fits. A stride of less than 8 bytes, which would
+/// make the values overlap, yields nothing.
+///
+/// ```
+/// use epochs::{bytes::from_le_bytes_strided, Epoch};
+/// // FILETIMEs at the start of 12-byte records.
+/// let mut raw = Vec::new();
+/// for filetime in [128_790_414_900_000_000_i64, 128_790_414_910_000_000] {
+///     raw.extend(filetime.to_le_bytes());
+///     raw.extend([0xaa; 4]);
+/// }
+/// let times: Vec<_> = from_le_bytes_strided(Epoch::WindowsFile, &raw, 12)
+///     .map(|ndt| ndt.unwrap().to_string())
+///     .collect();
+/// assert_eq!(times, ["2009-02-13 23:31:30", "2009-02-13 23:31:31"]);
+/// ```
+pub fn from_le_bytes_strided(
+    epoch: Epoch,
+    bytes: &[u8],
+    stride: usize,
+) -> impl Iterator<Item = Option<NaiveDateTime>> + '_ {
+    let f = epoch.converter();
+    let count = match bytes.len() {
+        _ if stride < 8 => 0,
+        len if len < 8 => 0,
+        len => (len - 8) / stride + 1,
+    };
+    (0..count).map(move |i| {
+        let start = i * stride;
+        f(to_i64(epoch, &bytes[start..start + 8], Order::Little)?)
+    })
+}
+
+/// A byte order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    /// Least significant byte first, as on x86 and ARM.
+    Little,
+    /// Most significant byte first, as on the wire.
+    Big,
+}
+
+/// One way of reading a timestamp out of unknown bytes, found by
+/// [interpret_bytes](fn.interpret_bytes.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interpretation {
+    /// Where the value starts in the bytes.
+    pub offset: usize,
+    /// How many bytes wide the value is.
+    pub width: usize,
+    /// The byte order the value was read in.
+    pub order: Order,
+    /// The epoch the value was converted from.
+    pub epoch: Epoch,
+    /// The value itself.
+    pub value: i64,
+    /// The time the value stands for.
+    pub datetime: NaiveDateTime,
+}
+
+/// Look for timestamps in the given bytes, as when carving an unknown
+/// binary blob. Every 4- and 8-byte window, at every offset, is read
+/// in both byte orders and converted in every epoch, and the results
+/// which fall between [GUESS_FROM](../constant.GUESS_FROM.html) and
+/// [GUESS_TO](../constant.GUESS_TO.html) are returned, ordered by
+/// offset, then width, byte order, and epoch. Windows of all zeroes
+/// are skipped.
+///
+/// ```
+/// use epochs::bytes::{interpret_bytes, Order};
+/// use epochs::Epoch;
+/// let mut blob = vec![0x00, 0x01, 0x02];
+/// blob.extend(128_790_414_900_000_000_i64.to_le_bytes());
+/// let found = interpret_bytes(&blob);
+/// let filetime = found
+///     .iter()
+///     .find(|i| i.epoch == Epoch::WindowsFile)
+///     .unwrap();
+/// assert_eq!((filetime.offset, filetime.width, filetime.order), (3, 8, Order::Little));
+/// assert_eq!(filetime.datetime.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn interpret_bytes(bytes: &[u8]) -> Vec<Interpretation> {
+    let mut found = Vec::new();
+    for offset in 0..bytes.len() {
+        for width in [4, 8] {
+            let Some(window) = bytes.get(offset..offset + width) else {
+                continue;
+            };
+            // Zeroes are padding far more often than the very start of
+            // an epoch (which is plausible for Cocoa time).
+            if window.iter().all(|&b| b == 0) {
+                continue;
+            }
+            for order in [Order::Little, Order::Big] {
+                for epoch in Epoch::ALL {
+                    let Some(value) = to_i64(epoch, window, order) else {
+                        continue;
+                    };
+                    let Some(datetime) = epoch.to_datetime(value) else {
+                        continue;
+                    };
+                    if (GUESS_FROM..=GUESS_TO).contains(&datetime) {
+                        found.push(Interpretation {
+                            offset,
+                            width,
+                            order,
+                            epoch,
+                            value,
+                            datetime,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Assemble the given bytes into an i64 in the given byte order,
+/// treating them as signed or unsigned according to the epoch.
+fn to_i64(epoch: Epoch, bytes: &[u8], order: Order) -> Option<i64> {
+    let signed = epoch.info().signed;
+    match bytes.len() {
+        4 => {
+            let b = bytes.try_into().ok()?;
+            let n = match order {
+                Order::Little => u32::from_le_bytes(b),
+                Order::Big => u32::from_be_bytes(b),
+            };
+            Some(if signed { n as i32 as i64 } else { n as i64 })
+        }
+        8 => {
+            let b = bytes.try_into().ok()?;
+            let n = match order {
+                Order::Little => u64::from_le_bytes(b),
+                Order::Big => u64::from_be_bytes(b),
+            };
+            if signed {
+                Some(n as i64)
+            } else {
+                i64::try_from(n).ok()
+            }
+        }
+        16 => {
+            let b = bytes.try_into().ok()?;
+            let n = match order {
+                Order::Little => u128::from_le_bytes(b),
+                Order::Big => u128::from_be_bytes(b),
+            };
+            if signed {
+                i64::try_from(n as i128).ok()
+            } else {
+                i64::try_from(n).ok()
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn four_bytes_signed() {
+        let ndt = from_be_bytes(Epoch::Unix, &[0xb6, 0x69, 0xfd, 0x2e]).unwrap();
+        assert_eq!(ndt.to_string(), "1930-11-18 00:28:30");
+    }
+    #[test]
+    fn four_bytes_unsigned() {
+        // Chrome doesn't use negative values, so this is after 1601.
+        let ndt = from_le_bytes(Epoch::Chrome, &[0x00, 0x00, 0x00, 0x80]).unwrap();
+        assert_eq!(ndt.to_string(), "1601-01-01 00:35:47.483648");
+    }
+    #[test]
+    fn eight_bytes_be() {
+        let raw = 128790414900000000i64.to_be_bytes();
+        let ndt = from_be_bytes(Epoch::WindowsFile, &raw).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn eight_bytes_unsigned_too_big() {
+        assert!(from_le_bytes(Epoch::WindowsFile, &[0xff; 8]).is_none());
+    }
+    #[test]
+    fn eight_bytes_signed_negative() {
+        let raw = (-1_234_567_890_000i64).to_le_bytes();
+        let ndt = from_le_bytes(Epoch::Java, &raw).unwrap();
+        assert_eq!(ndt.to_string(), "1930-11-18 00:28:30");
+    }
+    #[test]
+    fn sixteen_bytes() {
+        let raw = 1234567890i128.to_le_bytes();
+        let ndt = from_le_bytes(Epoch::Unix, &raw).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+        let raw = (-1234567890i128).to_be_bytes();
+        let ndt = from_be_bytes(Epoch::Unix, &raw).unwrap();
+        assert_eq!(ndt.to_string(), "1930-11-18 00:28:30");
+    }
+    #[test]
+    fn sixteen_bytes_too_big() {
+        assert!(from_be_bytes(Epoch::Unix, &(1i128 << 64).to_be_bytes()).is_none());
+    }
+    #[test]
+    fn strided() {
+        let mut raw = Vec::new();
+        for num in [1_234_567_890_000_i64, -1, i64::MAX] {
+            raw.extend(num.to_le_bytes());
+            raw.extend([0; 8]);
+        }
+        // The last record has no padding.
+        raw.truncate(raw.len() - 8);
+        let out: Vec<_> = from_le_bytes_strided(Epoch::Java, &raw, 16).collect();
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[0].unwrap().to_string(), "2009-02-13 23:31:30");
+        assert_eq!(out[1].unwrap().to_string(), "1969-12-31 23:59:59.999");
+        assert_eq!(out[2], None);
+        // Unsigned epochs treat the high bit the same as one at a time.
+        let raw = [0xff; 8];
+        let out: Vec<_> = from_le_bytes_strided(Epoch::WindowsFile, &raw, 8).collect();
+        assert_eq!(out, [None]);
+    }
+    #[test]
+    fn strided_short() {
+        assert_eq!(from_le_bytes_strided(Epoch::Unix, &[0; 7], 8).count(), 0);
+        assert_eq!(from_le_bytes_strided(Epoch::Unix, &[0; 23], 8).count(), 2);
+    }
+    #[test]
+    fn strided_overlapping() {
+        assert_eq!(from_le_bytes_strided(Epoch::Unix, &[0; 16], 4).count(), 0);
+        assert_eq!(from_le_bytes_strided(Epoch::Unix, &[0; 16], 0).count(), 0);
+    }
+    #[test]
+    fn interpret_both_orders() {
+        let mut blob = 1_234_567_890_u32.to_be_bytes().to_vec();
+        blob.extend([0xff; 4]);
+        blob.extend(1_234_567_890_000_i64.to_le_bytes());
+        let found = interpret_bytes(&blob);
+        let unix = found
+            .iter()
+            .find(|i| i.epoch == Epoch::Unix && i.width == 4)
+            .unwrap();
+        assert_eq!(
+            (unix.offset, unix.order, unix.value),
+            (0, Order::Big, 1_234_567_890)
+        );
+        let java = found.iter().find(|i| i.epoch == Epoch::Java).unwrap();
+        assert_eq!((java.offset, java.width, java.order), (8, 8, Order::Little));
+        assert_eq!(java.datetime.to_string(), "2009-02-13 23:31:30");
+        let offsets: Vec<_> = found.iter().map(|i| i.offset).collect();
+        assert!(offsets.windows(2).all(|w| w[0] <= w[1]));
+    }
+    #[test]
+    fn interpret_nothing() {
+        assert!(interpret_bytes(&[]).is_empty());
+        assert!(interpret_bytes(&[0x49, 0x96, 0x02]).is_empty());
+        assert!(interpret_bytes(&[0; 32]).is_empty());
+    }
+    #[test]
+    fn other_widths() {
+        assert!(from_le_bytes(Epoch::Unix, &[]).is_none());
+        assert!(from_le_bytes(Epoch::Unix, &[1, 2]).is_none());
+        assert!(from_be_bytes(Epoch::Unix, &[0; 12]).is_none());
+    }
+}
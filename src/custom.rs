@@ -0,0 +1,242 @@
+//! Epochs defined at run time.
+
+use std::error::Error;
+use std::fmt;
+use std::num::NonZeroU32;
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::{epoch2time, epoch2time_checked, time2epoch, time2epoch_checked};
+
+/// The unit counted by a [CustomEpoch](struct.CustomEpoch.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Seconds,
+    Deciseconds,
+    Centiseconds,
+    Milliseconds,
+    Microseconds,
+    Hectonanoseconds,
+    Nanoseconds,
+    /// Some other number of ticks per second (*e.g.*, the 1/300 s of
+    /// SQL Server's datetime), which needn't divide a nanosecond
+    /// evenly.
+    PerSecond(NonZeroU32),
+}
+
+impl Unit {
+    /// The number of these units in one second.
+    pub const fn per_second(self) -> i64 {
+        match self {
+            Unit::Seconds => 1,
+            Unit::Deciseconds => 10,
+            Unit::Centiseconds => 100,
+            Unit::Milliseconds => 1000,
+            Unit::Microseconds => 1_000_000,
+            Unit::Hectonanoseconds => 10_000_000,
+            Unit::Nanoseconds => 1_000_000_000,
+            Unit::PerSecond(n) => n.get() as i64,
+        }
+    }
+
+    /// The number of these units in the given Duration, truncated
+    /// toward zero. Returns None if it doesn't fit in an i64.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::Duration;
+    /// use epochs::Unit;
+    /// assert_eq!(Unit::Hectonanoseconds.from_duration(Duration::microseconds(-3)), Some(-30));
+    /// assert_eq!(Unit::Seconds.from_duration(Duration::milliseconds(-1500)), Some(-1));
+    /// ```
+    pub fn from_duration(self, d: Duration) -> Option<i64> {
+        let nanos = i128::from(d.num_seconds()) * 1_000_000_000 + i128::from(d.subsec_nanos());
+        i64::try_from(nanos * i128::from(self.per_second()) / 1_000_000_000).ok()
+    }
+}
+
+/// An epoch that isn't built into the crate, described by the unit it
+/// counts and the number of seconds its origin is after (or, if
+/// negative, before) the Unix epoch.
+///
+/// ```
+/// use epochs::{to_unix_const, CustomEpoch, Unit};
+/// // Centiseconds since 1995-06-01.
+/// let origin = to_unix_const(1995, 6, 1, 0, 0, 0).unwrap();
+/// let epoch = CustomEpoch::new(Unit::Centiseconds, origin);
+/// let ndt = epoch.to_datetime(43_260_309_000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(epoch.from_datetime(ndt), 43_260_309_000);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CustomEpoch {
+    unit: Unit,
+    offset_seconds: i64,
+}
+
+impl CustomEpoch {
+    /// Create an epoch counting the given unit from the given number of
+    /// seconds after the Unix epoch.
+    pub const fn new(unit: Unit, offset_seconds: i64) -> CustomEpoch {
+        CustomEpoch {
+            unit,
+            offset_seconds,
+        }
+    }
+
+    /// The unit this epoch counts.
+    pub const fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// The number of seconds this epoch's origin is after the Unix
+    /// epoch.
+    pub const fn offset_seconds(&self) -> i64 {
+        self.offset_seconds
+    }
+
+    /// Convert the given number in this epoch to a NaiveDateTime.
+    pub fn to_datetime(&self, num: i64) -> Option<NaiveDateTime> {
+        epoch2time(num, self.unit.per_second(), self.offset_seconds)
+    }
+
+    /// Convert the given NaiveDateTime to a number in this epoch,
+    /// saturating at the limits of i64.
+    pub fn from_datetime(&self, ndt: NaiveDateTime) -> i64 {
+        time2epoch(ndt, self.unit.per_second(), self.offset_seconds)
+    }
+
+    /// Like [to_datetime](#method.to_datetime), but says why the
+    /// conversion failed.
+    ///
+    /// ```
+    /// use epochs::{ConversionError, CustomEpoch, Unit};
+    /// let epoch = CustomEpoch::new(Unit::Seconds, i64::MAX);
+    /// assert_eq!(epoch.try_to_datetime(1), Err(ConversionError::Offset));
+    /// let epoch = CustomEpoch::new(Unit::Seconds, 0);
+    /// assert_eq!(epoch.try_to_datetime(i64::MAX), Err(ConversionError::Timestamp));
+    /// ```
+    pub fn try_to_datetime(&self, num: i64) -> Result<NaiveDateTime, ConversionError> {
+        epoch2time_checked(num, self.unit.per_second(), self.offset_seconds)
+    }
+
+    /// Like [from_datetime](#method.from_datetime), but says which
+    /// stage overflowed rather than saturating.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDate;
+    /// use epochs::{ConversionError, CustomEpoch, Unit};
+    /// let ndt = NaiveDate::from_ymd_opt(2300, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    /// let epoch = CustomEpoch::new(Unit::Nanoseconds, 0);
+    /// assert_eq!(epoch.try_from_datetime(ndt), Err(ConversionError::Scale));
+    /// ```
+    pub fn try_from_datetime(&self, ndt: NaiveDateTime) -> Result<i64, ConversionError> {
+        time2epoch_checked(ndt, self.unit.per_second(), self.offset_seconds)
+    }
+}
+
+/// The stage at which a conversion failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConversionError {
+    /// Scaling between units and seconds overflowed an i64.
+    Scale,
+    /// Shifting between the epoch's origin and the Unix epoch
+    /// overflowed an i64.
+    Offset,
+    /// The time is outside of the range of NaiveDateTime.
+    Timestamp,
+    /// The number is outside of the range the epoch itself allows
+    /// (*e.g.*, FileMaker time before the year 1).
+    Range,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stage = match self {
+            ConversionError::Scale => "overflow scaling units",
+            ConversionError::Offset => "overflow adding the epoch offset",
+            ConversionError::Timestamp => "time out of range for NaiveDateTime",
+            ConversionError::Range => "number out of range for the epoch",
+        };
+        f.write_str(stage)
+    }
+}
+
+impl Error for ConversionError {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn matches_chrome() {
+        let epoch = CustomEpoch::new(Unit::Microseconds, -11_644_473_600);
+        let ndt = epoch.to_datetime(12_912_187_816_559_001).unwrap();
+        assert_eq!(ndt, crate::chrome(12_912_187_816_559_001).unwrap());
+    }
+    #[test]
+    fn from_datetime_run() {
+        let epoch = CustomEpoch::new(Unit::Deciseconds, 0);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_milli_opt(23, 31, 30, 500)
+            .unwrap();
+        assert_eq!(epoch.from_datetime(ndt), 12345678905);
+    }
+    #[test]
+    fn per_second_ticks() {
+        let epoch = CustomEpoch::new(Unit::PerSecond(NonZeroU32::new(3).unwrap()), 0);
+        let ndt = epoch.to_datetime(3_703_703_671).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.333333333");
+        let ndt = epoch.to_datetime(-1).unwrap();
+        assert_eq!(ndt.to_string(), "1969-12-31 23:59:59.666666666");
+    }
+    #[test]
+    fn checked_stages() {
+        let ndt = NaiveDate::from_ymd_opt(1, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let epoch = CustomEpoch::new(Unit::Seconds, i64::MAX);
+        assert_eq!(epoch.try_from_datetime(ndt), Err(ConversionError::Offset));
+        assert_eq!(epoch.from_datetime(ndt), i64::MIN);
+        let epoch = CustomEpoch::new(Unit::Nanoseconds, 0);
+        assert_eq!(epoch.try_from_datetime(ndt), Err(ConversionError::Scale));
+        assert_eq!(epoch.from_datetime(ndt), i64::MIN);
+        assert_eq!(
+            epoch.try_to_datetime(i64::MIN),
+            Ok(epoch.to_datetime(i64::MIN).unwrap())
+        );
+        assert_eq!(
+            ConversionError::Offset.to_string(),
+            "overflow adding the epoch offset"
+        );
+    }
+    #[test]
+    fn from_datetime_is_exact() {
+        // Past 2^53, where a float would lose the low ticks.
+        let epoch = CustomEpoch::new(Unit::Hectonanoseconds, -11_644_473_600);
+        let ndt = epoch.to_datetime(0x0000_0002_3b9a_ca05).unwrap();
+        assert_eq!(epoch.try_from_datetime(ndt), Ok(0x0000_0002_3b9a_ca05));
+        let ndt = epoch.to_datetime(i64::MAX).unwrap();
+        assert_eq!(epoch.from_datetime(ndt), i64::MAX);
+    }
+    #[test]
+    fn i64_min_is_exact() {
+        // The whole seconds alone overflow i64 nanoseconds here, though
+        // the fraction brings it back in range.
+        let epoch = CustomEpoch::new(Unit::Nanoseconds, 0);
+        let ndt = epoch.to_datetime(i64::MIN).unwrap();
+        assert_eq!(ndt.to_string(), "1677-09-21 00:12:43.145224192");
+        assert_eq!(epoch.try_from_datetime(ndt), Ok(i64::MIN));
+        assert_eq!(epoch.from_datetime(ndt), i64::MIN);
+    }
+    #[test]
+    fn too_big() {
+        let epoch = CustomEpoch::new(Unit::Seconds, i64::MAX);
+        assert!(epoch.to_datetime(1).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+//! .NET `DateTime.ToBinary` values.
+
+use chrono::NaiveDateTime;
+
+use crate::WindowsDateTicks;
+
+/// The top two bits of a `ToBinary` value hold the `DateTimeKind`.
+const KIND_SHIFT: u32 = 62;
+
+const TICKS_MASK: i64 = (1 << KIND_SHIFT) - 1;
+
+/// `DateTime.MaxValue`, 9999-12-31 23:59:59.9999999, in ticks.
+const MAX_TICKS: i64 = 3_155_378_975_999_999_999;
+
+/// The `DateTimeKind` stored with a serialized .NET DateTime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DotnetKind {
+    Unspecified,
+    Utc,
+    Local,
+}
+
+/// .NET's `DateTime.ToBinary` stores the `Kind` of the DateTime in the
+/// top two bits and [Windows Date](fn.windows_date.html) ticks in the
+/// other 62. For a `Local` DateTime, the ticks are of the UTC time (a
+/// negative one wraps around 2^62), so the NaiveDateTime returned is
+/// UTC for both `Utc` and `Local` kinds; for `Unspecified` it is
+/// whatever the ticks say. Returns None if the ticks are beyond
+/// `DateTime.MaxValue`.
+///
+/// ```
+/// use epochs::{dotnet_binary, DotnetKind};
+/// let (ndt, kind) = dotnet_binary(0x48cb_5c4a_550d_f500).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(kind, DotnetKind::Utc);
+/// ```
+pub fn dotnet_binary(num: i64) -> Option<(NaiveDateTime, DotnetKind)> {
+    let mut ticks = num & TICKS_MASK;
+    let kind = match (num as u64) >> KIND_SHIFT {
+        0 => DotnetKind::Unspecified,
+        1 => DotnetKind::Utc,
+        // 3 marks a Local time in the ambiguous hour after a change
+        // from daylight saving time.
+        _ => {
+            if ticks > MAX_TICKS {
+                ticks -= 1 << KIND_SHIFT;
+            }
+            DotnetKind::Local
+        }
+    };
+    if ticks > MAX_TICKS {
+        return None;
+    }
+    Some((WindowsDateTicks::EPOCH.to_datetime(ticks)?, kind))
+}
+
+/// Convert the given NaiveDateTime to a [.NET
+/// ToBinary](fn.dotnet_binary.html) value of the given kind. For
+/// `Local`, the NaiveDateTime should be the UTC time. Anything finer
+/// than a tick (100 ns) is dropped. Returns None if it is past
+/// `DateTime.MaxValue`, or before 0001-01-01 (a day earlier is allowed
+/// for `Local`).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::{to_dotnet_binary, DotnetKind};
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_dotnet_binary(ndt, DotnetKind::Utc), Some(0x48cb_5c4a_550d_f500));
+/// ```
+pub fn to_dotnet_binary(ndt: NaiveDateTime, kind: DotnetKind) -> Option<i64> {
+    let seconds = ndt.and_utc().timestamp() - WindowsDateTicks::EPOCH.offset_seconds();
+    let ticks = i64::try_from(
+        i128::from(seconds) * 10_000_000 + i128::from(ndt.and_utc().timestamp_subsec_nanos() / 100),
+    )
+    .ok()?;
+    if ticks > MAX_TICKS {
+        return None;
+    }
+    Some(match kind {
+        DotnetKind::Unspecified if ticks >= 0 => ticks,
+        DotnetKind::Utc if ticks >= 0 => ticks | 1 << KIND_SHIFT,
+        // FromBinary only unwraps negative local ticks within a day.
+        DotnetKind::Local if ticks >= -24 * 60 * 60 * 10_000_000 => {
+            (ticks & TICKS_MASK) | (2 << KIND_SHIFT)
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn dotnet_binary_kinds() {
+        let (ndt, kind) = dotnet_binary(633_701_646_900_000_000).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+        assert_eq!(kind, DotnetKind::Unspecified);
+        let local = 633_701_646_900_000_000 | (2 << KIND_SHIFT);
+        let (ndt, kind) = dotnet_binary(local).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+        assert_eq!(kind, DotnetKind::Local);
+        let ambiguous = 633_701_646_900_000_000 | (3 << KIND_SHIFT);
+        assert_eq!(dotnet_binary(ambiguous).unwrap().1, DotnetKind::Local);
+    }
+    #[test]
+    fn dotnet_binary_local_wraps() {
+        // An hour before 0001-01-01 UTC.
+        let num = -(36_000_000_000_i64) & TICKS_MASK | (2 << KIND_SHIFT);
+        let (ndt, _) = dotnet_binary(num).unwrap();
+        assert_eq!(ndt.to_string(), "0000-12-31 23:00:00");
+    }
+    #[test]
+    fn dotnet_binary_too_big() {
+        let (ndt, _) = dotnet_binary(MAX_TICKS).unwrap();
+        assert_eq!(ndt.to_string(), "9999-12-31 23:59:59.999999900");
+        assert!(dotnet_binary(MAX_TICKS + 1).is_none());
+        assert!(dotnet_binary(TICKS_MASK | (1 << KIND_SHIFT)).is_none());
+    }
+    #[test]
+    fn to_dotnet_binary_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_nano_opt(12, 34, 56, 123_456_700)
+            .unwrap();
+        for kind in [DotnetKind::Unspecified, DotnetKind::Utc, DotnetKind::Local] {
+            let num = to_dotnet_binary(ndt, kind).unwrap();
+            assert_eq!(dotnet_binary(num), Some((ndt, kind)));
+        }
+    }
+    #[test]
+    fn to_dotnet_binary_out_of_range() {
+        let ndt = NaiveDate::from_ymd_opt(1, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let before = ndt - chrono::Duration::hours(1);
+        assert!(to_dotnet_binary(before, DotnetKind::Utc).is_none());
+        assert!(to_dotnet_binary(before, DotnetKind::Local).is_some());
+        let ndt = NaiveDate::from_ymd_opt(10000, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert!(to_dotnet_binary(ndt, DotnetKind::Unspecified).is_none());
+    }
+}
@@ -0,0 +1,1009 @@
+//! The supported integer epochs, for choosing a conversion at run
+//! time.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[cfg(feature = "clock")]
+use chrono::{DateTime, Local, Utc};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::{
+    ApfsNanos, ChromeMicros, CicsMillis, CocoaSeconds, ConversionError, CustomEpoch, JavaMillis,
+    MozillaMicros, SymbianMicros, UnixSeconds, UuidV1Ticks, WindowsDateTicks, WindowsFileTime,
+};
+
+/// Define the Epoch enum, and everything that would otherwise need a
+/// match arm for each epoch, from one list. Each epoch gives its name
+/// (and any aliases), its conversion functions, the equivalent
+/// CustomEpoch if it is a simple count of units, and, if it accepts
+/// only some numbers, which. An epoch defined with
+/// [define_epoch!](../macro.define_epoch.html) gives just its newtype
+/// instead (`Unix = "unix" => UnixSeconds,`), which has all of those.
+macro_rules! epochs {
+    (@range) => {
+        i64::MIN..=i64::MAX
+    };
+    (@range $range:expr) => {
+        $range
+    };
+    (
+        $(#[$meta:meta])*
+        pub enum Epoch {
+            $($rows:tt)*
+        }
+    ) => {
+        epochs!(@rows [$(#[$meta])*] [] $($rows)*);
+    };
+    (
+        @rows $meta:tt [$($done:tt)*]
+        $variant:ident = $name:literal $(| $alias:literal)* => $newtype:ident,
+        $($rest:tt)*
+    ) => {
+        epochs!(@rows $meta [
+            $($done)*
+            $variant = $name $(| $alias)* {
+                convert: |num| $newtype(num).to_datetime(),
+                invert: |ndt| $newtype::from_datetime(ndt).0,
+                linear: Some($newtype::EPOCH),
+            },
+        ] $($rest)*);
+    };
+    (
+        @rows $meta:tt [$($done:tt)*]
+        $variant:ident = $name:literal $(| $alias:literal)* { $($body:tt)* },
+        $($rest:tt)*
+    ) => {
+        epochs!(@rows $meta [
+            $($done)*
+            $variant = $name $(| $alias)* { $($body)* },
+        ] $($rest)*);
+    };
+    (@rows [$(#[$meta:meta])*] [$($done:tt)*]) => {
+        epochs!(@enum $(#[$meta])* pub enum Epoch { $($done)* });
+    };
+    (
+        @enum
+        $(#[$meta:meta])*
+        pub enum Epoch {
+            $(
+                $variant:ident = $name:literal $(| $alias:literal)* {
+                    convert: $convert:expr,
+                    invert: $invert:expr,
+                    linear: $linear:expr,
+                    $(range: $range:expr,)?
+                },
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Epoch {
+            $($variant,)*
+        }
+
+        impl Epoch {
+            /// Every supported epoch, in alphabetical order.
+            pub const ALL: [Epoch; [$($name),*].len()] = [$(Epoch::$variant),*];
+
+            /// The name of this epoch, which is also the name of its
+            /// conversion function.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Epoch::$variant => $name,)*
+                }
+            }
+
+            /// The equivalent CustomEpoch, for the epochs which are a
+            /// simple count of units.
+            pub(crate) const fn linear(self) -> Option<CustomEpoch> {
+                match self {
+                    $(Epoch::$variant => $linear,)*
+                }
+            }
+
+            /// The numbers this epoch accepts at all, before any
+            /// arithmetic.
+            pub(crate) const fn range(self) -> RangeInclusive<i64> {
+                match self {
+                    $(Epoch::$variant => epochs!(@range $($range)?),)*
+                }
+            }
+
+            /// The function that converts a number in this epoch to a
+            /// NaiveDateTime.
+            pub(crate) fn converter(self) -> fn(i64) -> Option<NaiveDateTime> {
+                match self {
+                    $(Epoch::$variant => $convert,)*
+                }
+            }
+
+            /// The function that converts a NaiveDateTime to a number in
+            /// this epoch.
+            pub(crate) fn inverter(self) -> fn(NaiveDateTime) -> i64 {
+                match self {
+                    $(Epoch::$variant => $invert,)*
+                }
+            }
+
+            /// The epoch with the given name or alias, already folded
+            /// to lower case with underscores.
+            fn from_name(name: &str) -> Option<Epoch> {
+                match name {
+                    $($name $(| $alias)* => Some(Epoch::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+epochs! {
+    /// An integer epoch supported by this crate.
+    ///
+    /// Each variant corresponds to one of the conversion pairs at the top
+    /// level of the crate (*e.g.*, `Epoch::Chrome` goes with
+    /// [chrome](fn.chrome.html) and [to_chrome](fn.to_chrome.html)).
+    /// Epochs counting whole days (*e.g.*, `Epoch::MjdDays`) convert to
+    /// midnight, and drop the time of day going the other way.
+    ///
+    /// Only epochs which are a single count that fits in an i64 have a
+    /// variant. Those counting fractional days (ICQ or Excel times of
+    /// day), those whose 64-bit values overflow an i64 (NTP, the z/OS
+    /// TOD clock), and those split across fields or bytes (FAT, PTP,
+    /// and the rest) have only their own functions;
+    /// [all_epochs](fn.all_epochs.html) lists everything.
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// let ndt = Epoch::Chrome.to_datetime(12_879_041_490_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// assert_eq!(Epoch::Unix.from_datetime(ndt), 1234567890);
+    /// ```
+    ///
+    /// Epochs can also be chosen by name, which is handy for
+    /// configuration files and command-line flags. Names are those of the
+    /// conversion functions, but case, hyphens, and a handful of common
+    /// aliases (*e.g.*, "filetime" or "webkit") are accepted too.
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// let epoch: Epoch = "Windows-File".parse().unwrap();
+    /// assert_eq!(epoch, Epoch::WindowsFile);
+    /// assert_eq!("filetime".parse::<Epoch>().unwrap(), Epoch::WindowsFile);
+    /// assert_eq!(epoch.to_string(), "windows_file");
+    /// ```
+    pub enum Epoch {
+        Apfs = "apfs" => ApfsNanos,
+        Cdma = "cdma" {
+            convert: crate::cdma,
+            invert: crate::to_cdma,
+            linear: None,
+        },
+        CdmaFrames = "cdma_frames" {
+            convert: crate::cdma_frames,
+            invert: crate::to_cdma_frames,
+            linear: None,
+        },
+        Chrome = "chrome" | "webkit" => ChromeMicros,
+        Cics = "cics" | "abstime" => CicsMillis,
+        Cocoa = "cocoa" | "cfabsolutetime" | "mac_absolute" => CocoaSeconds,
+        Excel1900Date = "excel_1900_date" | "excel_date" | "excel" {
+            convert: |serial| midnight(crate::excel_1900_date(serial)),
+            invert: |ndt| crate::excel::excel_1900_serial(ndt),
+            linear: None,
+            range: 0..=crate::excel::EXCEL_1900_LAST,
+        },
+        Excel1904Date = "excel_1904_date" {
+            convert: |serial| midnight(crate::excel_1904_date(serial)),
+            invert: |ndt| crate::excel::excel_1904_serial(ndt),
+            linear: None,
+            range: 0..=crate::excel::EXCEL_1904_LAST,
+        },
+        Filemaker = "filemaker" | "file_maker" {
+            convert: crate::filemaker,
+            invert: |ndt| crate::FILEMAKER.from_datetime(ndt),
+            linear: Some(crate::FILEMAKER),
+            range: 0..=crate::FILEMAKER_MAX,
+        },
+        GarminFit = "garmin_fit" | "garmin" | "fit" {
+            convert: |num| crate::garmin_fit(num.try_into().ok()?),
+            invert: |ndt| crate::GARMIN_FIT.from_datetime(ndt),
+            linear: Some(crate::GARMIN_FIT),
+            range: 0..=u32::MAX as i64,
+        },
+        GoogleCalendar = "google_calendar" | "google" {
+            convert: crate::google_calendar,
+            invert: crate::to_google_calendar,
+            linear: None,
+        },
+        Java = "java" | "javascript" | "js" => JavaMillis,
+        MjdDays = "mjd_days" | "mjd" | "firebird" {
+            convert: |days| midnight(crate::mjd_days(days)),
+            invert: |ndt| crate::to_mjd_days(ndt),
+            linear: None,
+        },
+        Mozilla = "mozilla" | "firefox" | "prtime" => MozillaMicros,
+        Palm = "palm" | "palm_os" | "pdb" {
+            convert: |num| crate::palm(num.try_into().ok()?),
+            invert: |ndt| crate::MAC_1904.from_datetime(ndt),
+            linear: Some(crate::MAC_1904),
+            range: 0..=u32::MAX as i64,
+        },
+        QuickTime = "quicktime" | "mp4" | "mov" {
+            convert: |num| crate::quicktime(num.try_into().ok()?),
+            invert: |ndt| crate::MAC_1904.from_datetime(ndt),
+            linear: Some(crate::MAC_1904),
+            range: 0..=i64::MAX,
+        },
+        RataDie = "rata_die" | "rd" {
+            convert: |days| midnight(crate::rata_die(days)),
+            invert: |ndt| crate::to_rata_die(ndt),
+            linear: None,
+        },
+        SasDate = "sas_date" | "sas" {
+            convert: |days| midnight(crate::sas_date(days)),
+            invert: |ndt| crate::to_sas_date(ndt),
+            linear: None,
+        },
+        StataDate = "stata_date" | "stata" | "td" {
+            convert: |days| midnight(crate::stata_date(days)),
+            invert: |ndt| crate::to_stata_date(ndt),
+            linear: None,
+        },
+        Symbian = "symbian" => SymbianMicros,
+        Tai = "tai" {
+            convert: crate::tai,
+            invert: crate::to_tai,
+            linear: None,
+        },
+        Tandem = "tandem" | "juliantimestamp" | "nonstop" {
+            convert: crate::tandem,
+            invert: crate::to_tandem,
+            linear: Some(crate::TANDEM),
+        },
+        Unix = "unix" | "posix" => UnixSeconds,
+        UnixRight = "unix_right" | "right" {
+            convert: crate::unix_right,
+            invert: crate::to_unix_right,
+            linear: None,
+        },
+        UuidV1 = "uuid_v1" | "uuidv1" | "uuid" => UuidV1Ticks,
+        WindowsDate = "windows_date" | "dotnet" | ".net" | "ticks" => WindowsDateTicks,
+        WindowsFile = "windows_file" | "filetime" | "ntfs" => WindowsFileTime,
+        Zigbee = "zigbee" {
+            convert: |num| crate::zigbee(num.try_into().ok()?),
+            invert: |ndt| crate::ZIGBEE.from_datetime(ndt),
+            linear: Some(crate::ZIGBEE),
+            range: 0..=(u32::MAX - 1) as i64,
+        },
+    }
+}
+
+impl Epoch {
+    /// Convert the given number in this epoch to a NaiveDateTime.
+    pub fn to_datetime(self, num: i64) -> Option<NaiveDateTime> {
+        self.converter()(num)
+    }
+
+    /// Convert the given NaiveDateTime to a number in this epoch.
+    pub fn from_datetime(self, ndt: NaiveDateTime) -> i64 {
+        self.inverter()(ndt)
+    }
+
+    /// Like [to_datetime](#method.to_datetime), but says why the
+    /// conversion failed.
+    ///
+    /// ```
+    /// use epochs::{ConversionError, Epoch};
+    /// assert_eq!(Epoch::Filemaker.try_to_datetime(-1), Err(ConversionError::Range));
+    /// assert_eq!(Epoch::Unix.try_to_datetime(i64::MAX), Err(ConversionError::Timestamp));
+    /// ```
+    pub fn try_to_datetime(self, num: i64) -> Result<NaiveDateTime, ConversionError> {
+        if let Some(ndt) = self.to_datetime(num) {
+            return Ok(ndt);
+        }
+        let err = match self.linear() {
+            _ if !self.range().contains(&num) => Err(ConversionError::Range),
+            Some(linear) => linear.try_to_datetime(num).and(Err(ConversionError::Range)),
+            None => Err(ConversionError::Timestamp),
+        };
+        #[cfg(feature = "tracing")]
+        if let Err(e) = err {
+            tracing::debug!(epoch = self.name(), value = num, error = %e, "conversion failed");
+        }
+        err
+    }
+
+    /// Like [from_datetime](#method.from_datetime), but says which
+    /// stage overflowed rather than saturating, and fails for numbers
+    /// the epoch does not accept.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDate;
+    /// use epochs::{ConversionError, Epoch};
+    /// let ndt = NaiveDate::from_ymd_opt(2300, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    /// assert_eq!(Epoch::Apfs.try_from_datetime(ndt), Err(ConversionError::Scale));
+    /// let ndt = NaiveDate::from_ymd_opt(1900, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    /// assert_eq!(Epoch::Palm.try_from_datetime(ndt), Err(ConversionError::Range));
+    /// ```
+    pub fn try_from_datetime(self, ndt: NaiveDateTime) -> Result<i64, ConversionError> {
+        let num = match self.linear() {
+            Some(linear) => linear.try_from_datetime(ndt)?,
+            None => self.from_datetime(ndt),
+        };
+        if self.range().contains(&num) {
+            Ok(num)
+        } else {
+            Err(ConversionError::Range)
+        }
+    }
+
+    /// Like [to_datetime](#method.to_datetime), but for a number of
+    /// any integer type, as database columns often are; None if it
+    /// does not fit in an i64.
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// let ndt = Epoch::Unix.to_datetime_from(1_234_567_890_u32).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// assert_eq!(Epoch::WindowsFile.to_datetime_from(u64::MAX), None);
+    /// ```
+    pub fn to_datetime_from<T: TryInto<i64>>(self, num: T) -> Option<NaiveDateTime> {
+        self.to_datetime(num.try_into().ok()?)
+    }
+
+    /// Like [try_from_datetime](#method.try_from_datetime), but for a
+    /// number of any integer type; None if the result does not fit in
+    /// that type, rather than truncating it.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDate;
+    /// use epochs::Epoch;
+    /// let ndt = NaiveDate::from_ymd_opt(2009, 2, 13).unwrap().and_hms_opt(23, 31, 30).unwrap();
+    /// assert_eq!(Epoch::Unix.from_datetime_into::<u32>(ndt), Some(1_234_567_890));
+    /// assert_eq!(Epoch::Java.from_datetime_into::<u32>(ndt), None);
+    /// ```
+    pub fn from_datetime_into<T: TryFrom<i64>>(self, ndt: NaiveDateTime) -> Option<T> {
+        T::try_from(self.try_from_datetime(ndt).ok()?).ok()
+    }
+
+    /// The current time (in UTC) as a number in this epoch.
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// let now = Epoch::WindowsFile.now();
+    /// assert!(now > 128_790_414_900_000_000);
+    /// ```
+    #[cfg(feature = "clock")]
+    pub fn now(self) -> i64 {
+        self.from_datetime(Utc::now().naive_utc())
+    }
+
+    /// Convert the given number in this epoch to a DateTime in the
+    /// local time zone. Since epochs count from an instant, this is
+    /// never ambiguous.
+    ///
+    /// ```
+    /// use chrono::Utc;
+    /// use epochs::Epoch;
+    /// let dt = Epoch::Chrome.to_local(12_879_041_490_000_000).unwrap();
+    /// assert_eq!(dt.with_timezone(&Utc).to_rfc3339(), "2009-02-13T23:31:30+00:00");
+    /// ```
+    #[cfg(feature = "clock")]
+    pub fn to_local(self, num: i64) -> Option<DateTime<Local>> {
+        Some(self.to_datetime(num)?.and_utc().with_timezone(&Local))
+    }
+
+    /// The smallest number this epoch can convert to a NaiveDateTime:
+    /// either the limit of the epoch itself (*e.g.*, FileMaker time
+    /// starts at 0) or of NaiveDateTime, which covers about 262,000
+    /// years either side of the common era, or of i64.
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// assert_eq!(Epoch::Filemaker.min_value(), 0);
+    /// assert_eq!(Epoch::WindowsFile.min_value(), i64::MIN);
+    /// ```
+    pub fn min_value(self) -> i64 {
+        match self {
+            // Google Calendar time has gaps, but always increases.
+            Epoch::GoogleCalendar => self.from_datetime(NaiveDateTime::MIN),
+            _ => edge(self, self.start(), *self.range().start()),
+        }
+    }
+
+    /// The largest number this epoch can convert to a NaiveDateTime
+    /// (see [min_value](#method.min_value)).
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// assert_eq!(Epoch::WindowsFile.max_value(), i64::MAX);
+    /// assert_eq!(Epoch::Unix.max_value(), 8_210_266_876_799);
+    /// ```
+    pub fn max_value(self) -> i64 {
+        match self {
+            Epoch::GoogleCalendar => self.from_datetime(NaiveDateTime::MAX),
+            _ => edge(self, self.start(), *self.range().end()),
+        }
+    }
+
+    /// A number which converts, to search for the limits from: the Unix
+    /// epoch (the default NaiveDateTime), or the nearest number to it
+    /// the epoch accepts.
+    fn start(self) -> i64 {
+        let range = self.range();
+        self.from_datetime(NaiveDateTime::default())
+            .clamp(*range.start(), *range.end())
+    }
+
+    /// Like [from_datetime](#method.from_datetime), but clamps the
+    /// result to [min_value](#method.min_value) and
+    /// [max_value](#method.max_value), so it always converts back.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDate;
+    /// use epochs::Epoch;
+    /// let ndt = NaiveDate::from_ymd_opt(5000, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    /// let num = Epoch::Filemaker.from_datetime_saturating(ndt);
+    /// assert_eq!(Epoch::Filemaker.to_datetime(num).unwrap().to_string(), "4000-12-31 23:59:59");
+    /// ```
+    pub fn from_datetime_saturating(self, ndt: NaiveDateTime) -> i64 {
+        self.from_datetime(ndt)
+            .clamp(self.min_value(), self.max_value())
+    }
+
+    /// The earliest time this epoch can represent, the time of its
+    /// [min_value](#method.min_value).
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// assert_eq!(Epoch::Filemaker.min_datetime().to_string(), "0001-01-01 00:00:00");
+    /// ```
+    pub fn min_datetime(self) -> NaiveDateTime {
+        self.to_datetime(self.min_value())
+            .expect("the minimum value converts")
+    }
+
+    /// The latest time this epoch can represent, the time of its
+    /// [max_value](#method.max_value).
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// let ndt = Epoch::WindowsFile.max_datetime();
+    /// assert_eq!(ndt.to_string(), "+30828-09-14 02:48:05.477580700");
+    /// ```
+    pub fn max_datetime(self) -> NaiveDateTime {
+        self.to_datetime(self.max_value())
+            .expect("the maximum value converts")
+    }
+}
+
+/// The start of the given day, for the epochs which count whole days.
+fn midnight(date: Option<NaiveDate>) -> Option<NaiveDateTime> {
+    Some(date?.and_time(NaiveTime::MIN))
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Epoch {
+    type Err = ParseEpochError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase().replace(['-', ' '], "_");
+        Epoch::from_name(&name).ok_or_else(|| ParseEpochError(s.to_string()))
+    }
+}
+
+/// The error returned when parsing an unknown epoch name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseEpochError(String);
+
+impl fmt::Display for ParseEpochError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown epoch: {:?}", self.0)
+    }
+}
+
+impl Error for ParseEpochError {}
+
+/// Rescale a number in one epoch to the same instant in another,
+/// without going through a NaiveDateTime. Any sub-unit remainder is
+/// rounded down. This is a `const fn`, so it can be used to compute
+/// constants. Returns None if either epoch is not a linear count of
+/// units (Google Calendar, CDMA, TAI, the leap-second-aware Unix time,
+/// and those counting days), if the number is not one the first epoch
+/// accepts (*e.g.*, a negative Palm OS time), or if the result is not
+/// one the second epoch accepts or does not fit in an i64.
+///
+/// ```
+/// use epochs::{rescale, Epoch};
+/// const FILETIME: i64 = 128_790_414_900_000_000;
+/// const CHROME: Option<i64> = rescale(FILETIME, Epoch::WindowsFile, Epoch::Chrome);
+/// assert_eq!(CHROME, Some(12_879_041_490_000_000));
+/// ```
+pub const fn rescale(num: i64, from: Epoch, to: Epoch) -> Option<i64> {
+    let (from_units, from_shift) = match from.linear() {
+        Some(epoch) => (epoch.unit().per_second(), epoch.offset_seconds()),
+        None => return None,
+    };
+    let (to_units, to_shift) = match to.linear() {
+        Some(epoch) => (epoch.unit().per_second(), epoch.offset_seconds()),
+        None => return None,
+    };
+    // Work in i128 so that neither the scaling nor the shift can
+    // overflow before the final narrowing.
+    let scaled = (num as i128 * to_units as i128).div_euclid(from_units as i128);
+    let shifted = scaled + (from_shift as i128 - to_shift as i128) * to_units as i128;
+    let (from_range, to_range) = (from.range(), to.range());
+    if num < *from_range.start()
+        || num > *from_range.end()
+        || shifted < *to_range.start() as i128
+        || shifted > *to_range.end() as i128
+    {
+        None
+    } else {
+        Some(shifted as i64)
+    }
+}
+
+/// The result of converting a number to a NaiveDateTime and back
+/// again, from [verify_roundtrip](fn.verify_roundtrip.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundTrip {
+    /// Whether the number came back unchanged.
+    pub exact: bool,
+    /// The NaiveDateTime the number converted to.
+    pub datetime: NaiveDateTime,
+    /// The number the NaiveDateTime converted back to.
+    pub recovered: i64,
+    /// How far the recovered number is from the original, in the
+    /// epoch's own units (saturating at the limits of i64).
+    pub delta: i64,
+}
+
+/// Convert the given number in the given epoch to a NaiveDateTime and
+/// back, and report any discrepancy. Returns None if the number
+/// doesn't convert at all.
+///
+/// ```
+/// use epochs::{verify_roundtrip, Epoch};
+/// let rt = verify_roundtrip(Epoch::WindowsFile, 128_790_414_900_000_000).unwrap();
+/// assert!(rt.exact);
+/// assert_eq!(rt.datetime.to_string(), "2009-02-13 23:31:30");
+/// assert!(verify_roundtrip(Epoch::Filemaker, -1).is_none());
+/// ```
+pub fn verify_roundtrip(epoch: Epoch, num: i64) -> Option<RoundTrip> {
+    let datetime = epoch.to_datetime(num)?;
+    let recovered = epoch.from_datetime(datetime);
+    let delta = recovered.saturating_sub(num);
+    Some(RoundTrip {
+        exact: delta == 0,
+        datetime,
+        recovered,
+        delta,
+    })
+}
+
+/// The last number from the given one that converts in the given
+/// epoch, heading toward the given limit. The numbers which convert
+/// make up a single range (for all but Google Calendar, and the Excel
+/// 1900 date system's phantom leap day, well inside its range), so
+/// this finds its end by bisection.
+fn edge(epoch: Epoch, mut good: i64, limit: i64) -> i64 {
+    if epoch.to_datetime(limit).is_some() {
+        return limit;
+    }
+    let mut bad = limit;
+    while good.abs_diff(bad) > 1 {
+        let mid = ((good as i128 + bad as i128) / 2) as i64;
+        if epoch.to_datetime(mid).is_some() {
+            good = mid;
+        } else {
+            bad = mid;
+        }
+    }
+    good
+}
+
+/// How long after the first time (a number in one epoch) the second
+/// time (a number in another, or the same, epoch) is; negative if it's
+/// before. Returns None if either number can't be converted to a
+/// NaiveDateTime.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::Duration;
+/// use epochs::{duration_between, Epoch};
+/// // A Chrome history visit and a later NTFS modification time.
+/// let visit = 12_879_041_490_000_000;
+/// let modified = 128_790_414_960_000_000;
+/// let d = duration_between(Epoch::Chrome, visit, Epoch::WindowsFile, modified);
+/// assert_eq!(d, Some(Duration::seconds(6)));
+/// ```
+pub fn duration_between(from: Epoch, from_num: i64, to: Epoch, to_num: i64) -> Option<Duration> {
+    let from = from.to_datetime(from_num)?;
+    let to = to.to_datetime(to_num)?;
+    Some(to.signed_duration_since(from))
+}
+
+/// Like [duration_between](fn.duration_between.html), but never fails.
+/// Numbers beyond the range of NaiveDateTime are still compared by
+/// their counts (or, for Google Calendar, taken to be the far past or
+/// future), numbers an epoch rejects (such as a negative Palm time) are
+/// taken to be its nearest bound, and the result is clamped to the
+/// range of Duration.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::Duration;
+/// use epochs::{saturating_duration_between, Epoch};
+/// let d = saturating_duration_between(Epoch::Unix, 0, Epoch::Unix, i64::MAX);
+/// assert_eq!(d, Duration::MAX);
+/// let d = saturating_duration_between(Epoch::Unix, i64::MAX, Epoch::Unix, 0);
+/// assert_eq!(d, Duration::MIN);
+/// ```
+pub fn saturating_duration_between(from: Epoch, from_num: i64, to: Epoch, to_num: i64) -> Duration {
+    let nanos = unix_nanos(to, to_num) - unix_nanos(from, from_num);
+    let seconds = nanos.div_euclid(1_000_000_000);
+    let subsec = nanos.rem_euclid(1_000_000_000) as u32;
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| Duration::new(seconds, subsec))
+        .unwrap_or(if nanos < 0 {
+            Duration::MIN
+        } else {
+            Duration::MAX
+        })
+}
+
+/// The nanoseconds since the Unix epoch of the given number in the
+/// given epoch, even beyond the range of NaiveDateTime. Numbers outside
+/// the epoch's range count as its nearest bound.
+fn unix_nanos(epoch: Epoch, num: i64) -> i128 {
+    if let Some(ndt) = epoch.to_datetime(num) {
+        let dt = ndt.and_utc();
+        return dt.timestamp() as i128 * 1_000_000_000 + dt.timestamp_subsec_nanos() as i128;
+    }
+    match epoch.linear() {
+        Some(linear) => {
+            let range = epoch.range();
+            let num = num.clamp(*range.start(), *range.end());
+            let units = linear.unit().per_second() as i128;
+            (num as i128 * 1_000_000_000).div_euclid(units)
+                + linear.offset_seconds() as i128 * 1_000_000_000
+        }
+        // Far enough out that the difference still saturates, without
+        // overflowing the subtraction.
+        None if num < 0 => i128::MIN / 2,
+        None => i128::MAX / 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::{NaiveDate, Timelike};
+
+    #[test]
+    fn to_datetime_run() {
+        let ndt = Epoch::WindowsFile.to_datetime(128790414900000000).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn from_datetime_run() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        assert_eq!(Epoch::Symbian.from_datetime(ndt), 63401787090000000);
+    }
+    /// The time an epoch can hold for `ndt`: midnight for the epochs
+    /// which count days.
+    fn in_resolution(epoch: Epoch, ndt: NaiveDateTime) -> NaiveDateTime {
+        if epoch.info().resolution == "days" {
+            ndt.date().and_time(NaiveTime::MIN)
+        } else {
+            ndt
+        }
+    }
+    #[test]
+    fn all_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        for epoch in Epoch::ALL {
+            let num = epoch.from_datetime(ndt);
+            let expected = in_resolution(epoch, ndt);
+            assert_eq!(epoch.to_datetime(num), Some(expected), "{:?}", epoch);
+        }
+    }
+    #[test]
+    fn display_parse_round_trip() {
+        for epoch in Epoch::ALL {
+            assert_eq!(epoch.to_string().parse::<Epoch>(), Ok(epoch));
+        }
+    }
+    #[test]
+    fn range_matches_converter() {
+        for epoch in Epoch::ALL {
+            let range = epoch.range();
+            if let Some(below) = range.start().checked_sub(1) {
+                assert_eq!(epoch.to_datetime(below), None, "{epoch}");
+            }
+            if let Some(above) = range.end().checked_add(1) {
+                assert_eq!(epoch.to_datetime(above), None, "{epoch}");
+            }
+            assert!(range.contains(&epoch.min_value()), "{epoch}");
+            assert!(range.contains(&epoch.max_value()), "{epoch}");
+        }
+    }
+    #[test]
+    fn parse_aliases() {
+        assert_eq!("WebKit".parse::<Epoch>(), Ok(Epoch::Chrome));
+        assert_eq!(" uuid ".parse::<Epoch>(), Ok(Epoch::UuidV1));
+        assert_eq!("windows date".parse::<Epoch>(), Ok(Epoch::WindowsDate));
+        assert_eq!(".NET".parse::<Epoch>(), Ok(Epoch::WindowsDate));
+    }
+    #[test]
+    fn parse_unknown() {
+        let err = "icq".parse::<Epoch>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown epoch: \"icq\"");
+    }
+
+    #[test]
+    fn linear_matches_functions() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        for epoch in Epoch::ALL {
+            if let Some(linear) = epoch.linear() {
+                let (units, shift) = (linear.unit().per_second(), linear.offset_seconds());
+                assert_eq!(
+                    epoch.from_datetime(ndt),
+                    (1234567890 - shift) * units,
+                    "{:?}",
+                    epoch
+                );
+            }
+        }
+    }
+    #[test]
+    fn try_conversions() {
+        for epoch in Epoch::ALL {
+            let ndt = epoch.max_datetime();
+            assert_eq!(
+                epoch
+                    .try_from_datetime(ndt)
+                    .map(|num| epoch.to_datetime(num)),
+                Ok(Some(ndt))
+            );
+            if epoch.max_value() < i64::MAX {
+                assert!(epoch.try_to_datetime(epoch.max_value() + 1).is_err());
+            }
+        }
+    }
+    #[cfg(feature = "clock")]
+    #[test]
+    fn roundtrips_are_exact() {
+        for epoch in Epoch::ALL {
+            for num in [epoch.min_value(), epoch.now(), epoch.max_value()] {
+                let rt = verify_roundtrip(epoch, num).unwrap();
+                assert!(rt.exact, "{epoch} {num} {rt:?}");
+                assert_eq!(rt.recovered, num);
+            }
+        }
+    }
+    #[test]
+    fn other_integer_types() {
+        assert_eq!(
+            Epoch::Unix.to_datetime_from(-1_i8),
+            Epoch::Unix.to_datetime(-1)
+        );
+        assert_eq!(
+            Epoch::Unix.to_datetime_from(0xffff_fff0_u32),
+            Epoch::Unix.to_datetime(0xffff_fff0)
+        );
+        assert_eq!(Epoch::Unix.to_datetime_from(1_u128 << 63), None);
+        assert_eq!(
+            Epoch::Java.to_datetime_from(-1_i128),
+            Epoch::Java.to_datetime(-1)
+        );
+        let ndt = Epoch::Unix.to_datetime(-1).unwrap();
+        assert_eq!(Epoch::Unix.from_datetime_into::<i32>(ndt), Some(-1));
+        assert_eq!(Epoch::Unix.from_datetime_into::<u64>(ndt), None);
+        // Out of range of i64 itself is not saturated either.
+        let ndt = NaiveDateTime::MAX;
+        assert_eq!(Epoch::Apfs.from_datetime_into::<i128>(ndt), None);
+    }
+    #[test]
+    fn extremes_round_trip() {
+        for epoch in Epoch::ALL {
+            for num in [epoch.min_value(), epoch.max_value()] {
+                let ndt = epoch.to_datetime(num).unwrap();
+                assert_eq!(epoch.try_from_datetime(ndt), Ok(num), "{epoch} {ndt}");
+            }
+            for ndt in [NaiveDateTime::MIN, NaiveDateTime::MAX] {
+                // Sub-unit precision is truncated, but the count is not.
+                let num = epoch.try_from_datetime(ndt);
+                if let Some(back) = num.ok().and_then(|num| epoch.to_datetime(num)) {
+                    assert_eq!(epoch.try_from_datetime(back), num, "{epoch} {ndt}");
+                }
+            }
+        }
+    }
+    #[test]
+    fn extremes_of_i64() {
+        // These count fine units from far enough back that i64::MIN is
+        // a representable time, but i64::MIN whole seconds is not.
+        for epoch in [
+            Epoch::Apfs,
+            Epoch::UuidV1,
+            Epoch::WindowsDate,
+            Epoch::WindowsFile,
+        ] {
+            assert_eq!(epoch.min_value(), i64::MIN, "{epoch}");
+            let ndt = epoch.to_datetime(i64::MIN).unwrap();
+            assert_eq!(epoch.try_from_datetime(ndt), Ok(i64::MIN), "{epoch}");
+            let ndt = epoch.to_datetime(i64::MIN + 1).unwrap();
+            assert_eq!(epoch.try_from_datetime(ndt), Ok(i64::MIN + 1), "{epoch}");
+            let before = ndt - Duration::nanoseconds(200);
+            assert_eq!(
+                epoch.try_from_datetime(before),
+                Err(ConversionError::Scale),
+                "{epoch}"
+            );
+        }
+    }
+    #[cfg(feature = "clock")]
+    #[test]
+    fn to_local_is_the_same_instant() {
+        // Midnight, so that the day epochs have it too, and late enough
+        // for ZigBee.
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_time(NaiveTime::MIN);
+        for epoch in Epoch::ALL {
+            let num = epoch.from_datetime(ndt);
+            let local = epoch.to_local(num).unwrap();
+            assert_eq!(local.naive_utc(), ndt, "{epoch}");
+        }
+        assert_eq!(Epoch::Unix.to_local(i64::MAX), None);
+        let local = UnixSeconds(1234567890).to_local().unwrap();
+        assert_eq!(local.timestamp(), 1234567890);
+    }
+    #[cfg(feature = "clock")]
+    #[test]
+    fn now_is_now() {
+        for epoch in Epoch::ALL {
+            let before = in_resolution(epoch, Utc::now().naive_utc());
+            let now = epoch.to_datetime(epoch.now()).unwrap();
+            // Allow for the seconds of Google Calendar time, or a day
+            // epoch ticking over midnight.
+            let since = now - before;
+            assert!(since.num_seconds().abs() <= 1, "{epoch} {since}");
+        }
+    }
+    #[test]
+    fn value_ranges() {
+        for epoch in Epoch::ALL {
+            let (min, max) = (epoch.min_value(), epoch.max_value());
+            assert!(epoch.to_datetime(min).is_some(), "{epoch}");
+            assert!(epoch.to_datetime(max).is_some(), "{epoch}");
+            if min > i64::MIN {
+                assert!(epoch.to_datetime(min - 1).is_none(), "{epoch}");
+            }
+            if max < i64::MAX {
+                assert!(epoch.to_datetime(max + 1).is_none(), "{epoch}");
+            }
+            assert!(epoch.min_datetime() < epoch.max_datetime());
+            for ndt in [NaiveDateTime::MIN, NaiveDateTime::MAX] {
+                let num = epoch.from_datetime_saturating(ndt);
+                assert!(epoch.to_datetime(num).is_some(), "{epoch}");
+            }
+        }
+        assert_eq!(
+            Epoch::Filemaker.max_datetime().to_string(),
+            "4000-12-31 23:59:59"
+        );
+        assert_eq!(
+            Epoch::Unix.max_datetime(),
+            NaiveDateTime::MAX.with_nanosecond(0).unwrap()
+        );
+        assert_eq!(Epoch::Unix.min_datetime(), NaiveDateTime::MIN);
+    }
+    #[test]
+    fn duration_between_run() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        for from in Epoch::ALL {
+            for to in Epoch::ALL {
+                let d = duration_between(from, from.from_datetime(ndt), to, to.from_datetime(ndt));
+                let expected = in_resolution(to, ndt) - in_resolution(from, ndt);
+                assert_eq!(d, Some(expected), "{from} {to}");
+            }
+        }
+        let d = duration_between(Epoch::Unix, 1_234_567_890, Epoch::Java, 1_234_567_889_999);
+        assert_eq!(d, Some(Duration::milliseconds(-1)));
+        assert_eq!(
+            duration_between(Epoch::Unix, i64::MAX, Epoch::Unix, 0),
+            None
+        );
+    }
+    #[test]
+    fn saturating_duration_between_run() {
+        let d = saturating_duration_between(Epoch::Unix, 0, Epoch::Java, -1);
+        assert_eq!(d, Duration::milliseconds(-1));
+        // Beyond chrono, but still a difference Duration can hold.
+        let d = saturating_duration_between(Epoch::Unix, i64::MAX - 1, Epoch::Unix, i64::MAX);
+        assert_eq!(d, Duration::seconds(1));
+        let d = saturating_duration_between(Epoch::GoogleCalendar, i64::MIN, Epoch::Unix, 0);
+        assert_eq!(d, Duration::MAX);
+        // Numbers the epoch rejects are taken to be its bounds.
+        let d = saturating_duration_between(Epoch::Palm, -1, Epoch::Palm, 0);
+        assert_eq!(d, Duration::zero());
+        let max = Epoch::Zigbee.max_value();
+        let d = saturating_duration_between(Epoch::Zigbee, u32::MAX as i64, Epoch::Zigbee, max);
+        assert_eq!(d, Duration::zero());
+        let d = saturating_duration_between(Epoch::Filemaker, -1, Epoch::Unix, 0);
+        assert_eq!(
+            Some(d),
+            duration_between(Epoch::Filemaker, 0, Epoch::Unix, 0)
+        );
+    }
+    #[test]
+    fn rescale_run() {
+        assert_eq!(
+            rescale(1234567890, Epoch::Unix, Epoch::Symbian),
+            Some(63401787090000000)
+        );
+        assert_eq!(
+            rescale(63401787090000000, Epoch::Symbian, Epoch::Cocoa),
+            Some(256260690)
+        );
+    }
+    #[test]
+    fn rescale_rounds_down() {
+        assert_eq!(rescale(1999, Epoch::Java, Epoch::Unix), Some(1));
+        assert_eq!(rescale(-1, Epoch::Java, Epoch::Unix), Some(-1));
+    }
+    #[test]
+    fn rescale_google_calendar() {
+        assert_eq!(
+            rescale(1297899090, Epoch::GoogleCalendar, Epoch::Unix),
+            None
+        );
+        assert_eq!(
+            rescale(1234567890, Epoch::Unix, Epoch::GoogleCalendar),
+            None
+        );
+    }
+    #[test]
+    fn rescale_outside_range() {
+        // Out of range going in.
+        assert_eq!(rescale(-1, Epoch::Palm, Epoch::Unix), None);
+        assert_eq!(rescale(1 << 32, Epoch::Zigbee, Epoch::Unix), None);
+        // Out of range coming out.
+        assert_eq!(rescale(-3_000_000_000, Epoch::Unix, Epoch::Palm), None);
+        assert_eq!(rescale(0, Epoch::Unix, Epoch::Zigbee), None);
+        assert_eq!(rescale(946_684_800, Epoch::Unix, Epoch::Zigbee), Some(0));
+    }
+    #[test]
+    fn rescale_too_big() {
+        assert_eq!(rescale(i64::MAX, Epoch::Unix, Epoch::Apfs), None);
+    }
+}
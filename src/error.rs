@@ -0,0 +1,217 @@
+//! One error type covering everything that can go wrong, for code
+//! which would rather use `?` than handle each kind on its own.
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "csv")]
+use crate::stream::{CellErrorKind, RewriteError};
+use crate::{
+    ConversionError, Epoch, FormatError, ParseEpochError, ParseUuidError, ParseValueError,
+};
+
+/// Any of the errors this crate returns. Each converts into it with
+/// `From`, so functions calling several fallible ones can return this
+/// and use `?` throughout. Each variant says only what failed; the
+/// error it wraps (which says why) is its
+/// [source](https://doc.rust-lang.org/std/error/trait.Error.html#method.source).
+///
+/// The functions themselves still return the narrower types (*e.g.*,
+/// [ConversionError](enum.ConversionError.html) from `try_to_datetime`,
+/// [ParseUuidError](struct.ParseUuidError.html) from `parse_uuid_v1`),
+/// so that matching on the result covers only what that function can
+/// actually fail with, and so that those which are `Copy` and `Eq` can
+/// be compared in tests, which this (holding a Vec, and a CSV error in
+/// the `csv` feature) cannot. Only [detect](fn.detect.html), whose
+/// failures are not any one of those, returns this directly.
+///
+/// ```
+/// use epochs::{parse_value, Epoch, EpochError};
+///
+/// fn convert(epoch: &str, num: &str) -> Result<String, EpochError> {
+///     let epoch: Epoch = epoch.parse()?;
+///     let num = parse_value(num)?.as_i64().unwrap_or_default();
+///     Ok(epoch.try_to_datetime(num)?.to_string())
+/// }
+///
+/// assert_eq!(convert("unix", "1234567890").unwrap(), "2009-02-13 23:31:30");
+/// assert!(matches!(convert("mayan", "0"), Err(EpochError::UnknownEpoch(_))));
+/// assert!(matches!(convert("unix", "soon"), Err(EpochError::Parse(_))));
+/// assert!(matches!(convert("filemaker", "-1"), Err(EpochError::Conversion(_))));
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EpochError {
+    /// A number could not be converted to a time in the epoch, or a
+    /// time to a number.
+    Conversion(ConversionError),
+    /// A string is not a number.
+    Parse(ParseValueError),
+    /// A string is not the name of an epoch.
+    UnknownEpoch(ParseEpochError),
+    /// A string is not a version 1 UUID.
+    Uuid(ParseUuidError),
+    /// A format string is invalid, or a string does not match it.
+    Format(FormatError),
+    /// Numbers are plausible in more than one of the given epochs, and
+    /// there is nothing to choose between them.
+    Ambiguous(Vec<Epoch>),
+    /// Numbers are plausible in none of the epochs.
+    Undetected,
+    /// A CSV could not be rewritten.
+    #[cfg(feature = "csv")]
+    Rewrite(RewriteError),
+}
+
+impl fmt::Display for EpochError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EpochError::Conversion(_) => f.write_str("conversion failed"),
+            EpochError::Parse(_) => f.write_str("could not parse a number"),
+            EpochError::UnknownEpoch(_) => f.write_str("could not parse an epoch name"),
+            EpochError::Uuid(_) => f.write_str("could not parse a UUID"),
+            EpochError::Format(_) => f.write_str("could not format or parse a time"),
+            EpochError::Ambiguous(epochs) => {
+                f.write_str("ambiguous: could be any of")?;
+                for (i, epoch) in epochs.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    write!(f, "{sep}{epoch}")?;
+                }
+                Ok(())
+            }
+            EpochError::Undetected => f.write_str("no epoch puts the numbers in a plausible range"),
+            #[cfg(feature = "csv")]
+            EpochError::Rewrite(_) => f.write_str("could not rewrite the CSV"),
+        }
+    }
+}
+
+impl Error for EpochError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EpochError::Conversion(e) => Some(e),
+            EpochError::Parse(e) => Some(e),
+            EpochError::UnknownEpoch(e) => Some(e),
+            EpochError::Uuid(e) => Some(e),
+            EpochError::Format(e) => Some(e),
+            EpochError::Ambiguous(_) | EpochError::Undetected => None,
+            #[cfg(feature = "csv")]
+            EpochError::Rewrite(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConversionError> for EpochError {
+    fn from(e: ConversionError) -> Self {
+        EpochError::Conversion(e)
+    }
+}
+
+impl From<ParseValueError> for EpochError {
+    fn from(e: ParseValueError) -> Self {
+        EpochError::Parse(e)
+    }
+}
+
+impl From<ParseEpochError> for EpochError {
+    fn from(e: ParseEpochError) -> Self {
+        EpochError::UnknownEpoch(e)
+    }
+}
+
+impl From<ParseUuidError> for EpochError {
+    fn from(e: ParseUuidError) -> Self {
+        EpochError::Uuid(e)
+    }
+}
+
+impl From<FormatError> for EpochError {
+    fn from(e: FormatError) -> Self {
+        EpochError::Format(e)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<CellErrorKind> for EpochError {
+    fn from(e: CellErrorKind) -> Self {
+        match e {
+            CellErrorKind::Parse(e) => EpochError::Parse(e),
+            CellErrorKind::Conversion(e) => EpochError::Conversion(e),
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<RewriteError> for EpochError {
+    fn from(e: RewriteError) -> Self {
+        EpochError::Rewrite(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{format, parse_hex};
+
+    /// Every message in the chain of the given error, outermost first.
+    fn chain(e: &dyn Error) -> Vec<String> {
+        let mut messages = vec![e.to_string()];
+        let mut source = e.source();
+        while let Some(e) = source {
+            messages.push(e.to_string());
+            source = e.source();
+        }
+        messages
+    }
+
+    #[test]
+    fn display_is_not_repeated() {
+        let e = EpochError::from(ConversionError::Range);
+        assert_eq!(
+            chain(&e),
+            ["conversion failed", &ConversionError::Range.to_string()]
+        );
+        let e: EpochError = parse_hex("xyz").unwrap_err().into();
+        let messages = chain(&e);
+        assert_eq!(messages[0], "could not parse a number");
+        assert!(messages[1].starts_with("invalid number"), "{e}");
+        let e: EpochError = "mayan".parse::<Epoch>().unwrap_err().into();
+        assert_eq!(
+            chain(&e),
+            ["could not parse an epoch name", "unknown epoch: \"mayan\""]
+        );
+        let e: EpochError = format(Epoch::Filemaker, -1, "%F").unwrap_err().into();
+        let messages = chain(&e);
+        for (i, message) in messages.iter().enumerate() {
+            for other in &messages[i + 1..] {
+                assert!(!message.contains(other.as_str()), "{messages:?}");
+            }
+        }
+    }
+    #[test]
+    fn source_chain() {
+        let e: EpochError = format(Epoch::Filemaker, -1, "%F").unwrap_err().into();
+        let format = e.source().unwrap();
+        assert!(format.is::<FormatError>());
+        let conversion = format.source().unwrap();
+        assert_eq!(
+            conversion.downcast_ref::<ConversionError>(),
+            Some(&ConversionError::Range)
+        );
+        assert!(conversion.source().is_none());
+    }
+    #[test]
+    fn ambiguous() {
+        let e = EpochError::Ambiguous(vec![Epoch::Unix, Epoch::Cocoa]);
+        assert_eq!(e.to_string(), "ambiguous: could be any of unix, cocoa");
+        assert!(e.source().is_none());
+        assert!(EpochError::Undetected.source().is_none());
+    }
+    #[cfg(feature = "csv")]
+    #[test]
+    fn cell_errors() {
+        let e = EpochError::from(CellErrorKind::Conversion(ConversionError::Scale));
+        assert!(matches!(e, EpochError::Conversion(ConversionError::Scale)));
+    }
+}
@@ -0,0 +1,287 @@
+//! Excel serial dates, in both the 1900 and the 1904 date systems.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime};
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// 1899-12-30, the day before serial 1 once the phantom leap day is
+/// accounted for, is 25,569 days before the Unix epoch.
+const EXCEL_1900_DAYS: i64 = 25_569;
+
+/// 1904-01-01, serial 0 of the 1904 date system, is 24,107 days before
+/// the Unix epoch.
+const EXCEL_1904_DAYS: i64 = 24_107;
+
+/// The first serial after 9999-12-31 in the 1900 date system.
+const EXCEL_1900_END: f64 = 2_958_466.0;
+
+/// The first serial after 9999-12-31 in the 1904 date system.
+const EXCEL_1904_END: f64 = EXCEL_1900_END - (EXCEL_1900_DAYS - EXCEL_1904_DAYS) as f64;
+
+/// The last whole serial in the 1900 date system, 9999-12-31.
+pub(crate) const EXCEL_1900_LAST: i64 = EXCEL_1900_END as i64 - 1;
+
+/// The last whole serial in the 1904 date system, 9999-12-31.
+pub(crate) const EXCEL_1904_LAST: i64 = EXCEL_1904_END as i64 - 1;
+
+/// Days from 0001-01-01, where chrono counts from, to 1970-01-01.
+const UNIX_EPOCH_DAY_CE: i64 = 719_163;
+
+/// Excel's 1900 date system (the default on Windows) counts days from
+/// 1900-01-01, which is serial 1. Days can have a fractional part,
+/// which is rounded to the millisecond. For compatibility with Lotus
+/// 1-2-3, Excel treats 1900 as a leap year, so serial 60 is the
+/// non-existent 1900-02-29 (for which this returns None) and serials
+/// from 61 on agree with [ICQ](fn.icq.html) time. Serial 0 is
+/// 1899-12-31, which Excel shows as 1900-01-00. Returns None for
+/// negative serials or ones past 9999-12-31, which Excel doesn't
+/// allow.
+///
+/// ```
+/// use epochs::excel_1900;
+/// let ndt = excel_1900(39857.980208333334).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// let ndt = excel_1900(59.0).unwrap();
+/// assert_eq!(ndt.to_string(), "1900-02-28 00:00:00");
+/// assert!(excel_1900(60.0).is_none());
+/// ```
+pub fn excel_1900(serial: f64) -> Option<NaiveDateTime> {
+    if !(0.0..EXCEL_1900_END).contains(&serial) || (60.0..61.0).contains(&serial) {
+        return None;
+    }
+    let mut millis = (serial * MILLIS_PER_DAY as f64).round() as i64;
+    if serial < 60.0 {
+        // Before the phantom leap day, serials are one day later.
+        millis += MILLIS_PER_DAY;
+    }
+    from_millis(millis, EXCEL_1900_DAYS)
+}
+
+/// Convert the given NaiveDateTime to an [Excel 1900](fn.excel_1900.html)
+/// serial date. Returns None for times before 1899-12-31 or after
+/// 9999-12-31, which Excel can't show.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_excel_1900;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_excel_1900(ndt), Some(39857.980208333334));
+/// let ndt = NaiveDateTime::parse_from_str("1900-03-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_excel_1900(ndt), Some(61.0));
+/// ```
+pub fn to_excel_1900(ndt: NaiveDateTime) -> Option<f64> {
+    let mut millis = to_millis(ndt, EXCEL_1900_DAYS)?;
+    if millis < MILLIS_PER_DAY {
+        return None;
+    }
+    if millis < 61 * MILLIS_PER_DAY {
+        millis -= MILLIS_PER_DAY;
+    }
+    to_serial(millis, EXCEL_1900_END)
+}
+
+/// Excel's 1904 date system (the old default on the Mac) counts days
+/// from 1904-01-01, which is serial 0, and so avoids the 1900 leap
+/// year bug. Days can have a fractional part, which is rounded to the
+/// millisecond. Returns None for negative serials or ones past
+/// 9999-12-31, which Excel doesn't allow.
+///
+/// ```
+/// use epochs::excel_1904;
+/// let ndt = excel_1904(38395.980208333334).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn excel_1904(serial: f64) -> Option<NaiveDateTime> {
+    if !(0.0..EXCEL_1904_END).contains(&serial) {
+        return None;
+    }
+    let millis = (serial * MILLIS_PER_DAY as f64).round() as i64;
+    from_millis(millis, EXCEL_1904_DAYS)
+}
+
+/// Convert the given NaiveDateTime to an [Excel 1904](fn.excel_1904.html)
+/// serial date. Returns None for times before 1904-01-01 or after
+/// 9999-12-31, which Excel can't show.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_excel_1904;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_excel_1904(ndt), Some(38395.980208333334));
+/// ```
+pub fn to_excel_1904(ndt: NaiveDateTime) -> Option<f64> {
+    let millis = to_millis(ndt, EXCEL_1904_DAYS)?;
+    if millis < 0 {
+        return None;
+    }
+    to_serial(millis, EXCEL_1904_END)
+}
+
+/// The date of the given whole [Excel 1900](fn.excel_1900.html)
+/// serial date, for cells formatted as dates only. Returns None for
+/// the phantom 1900-02-29 (serial 60) and anything out of range.
+///
+/// ```
+/// use epochs::excel_1900_date;
+/// let nd = excel_1900_date(39857).unwrap();
+/// assert_eq!(nd.to_string(), "2009-02-13");
+/// ```
+pub fn excel_1900_date(serial: i64) -> Option<NaiveDate> {
+    let serial = u32::try_from(serial).ok()?;
+    excel_1900(serial.into()).map(|ndt| ndt.date())
+}
+
+/// Convert the given date (or NaiveDateTime, whose time of day is
+/// dropped) to a whole [Excel 1900](fn.excel_1900.html) serial date.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDate;
+/// use epochs::to_excel_1900_date;
+/// let nd = NaiveDate::from_ymd_opt(2009, 2, 13).unwrap();
+/// assert_eq!(to_excel_1900_date(nd), Some(39857));
+/// ```
+pub fn to_excel_1900_date(date: impl Datelike) -> Option<i64> {
+    let date = NaiveDate::from_num_days_from_ce_opt(date.num_days_from_ce())?;
+    to_excel_1900(date.and_hms_opt(0, 0, 0)?).map(|serial| serial as i64)
+}
+
+/// The date of the given whole [Excel 1904](fn.excel_1904.html)
+/// serial date, for cells formatted as dates only.
+///
+/// ```
+/// use epochs::excel_1904_date;
+/// let nd = excel_1904_date(38395).unwrap();
+/// assert_eq!(nd.to_string(), "2009-02-13");
+/// ```
+pub fn excel_1904_date(serial: i64) -> Option<NaiveDate> {
+    let serial = u32::try_from(serial).ok()?;
+    excel_1904(serial.into()).map(|ndt| ndt.date())
+}
+
+/// Convert the given date (or NaiveDateTime, whose time of day is
+/// dropped) to a whole [Excel 1904](fn.excel_1904.html) serial date.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDate;
+/// use epochs::to_excel_1904_date;
+/// let nd = NaiveDate::from_ymd_opt(2009, 2, 13).unwrap();
+/// assert_eq!(to_excel_1904_date(nd), Some(38395));
+/// ```
+pub fn to_excel_1904_date(date: impl Datelike) -> Option<i64> {
+    let date = NaiveDate::from_num_days_from_ce_opt(date.num_days_from_ce())?;
+    to_excel_1904(date.and_hms_opt(0, 0, 0)?).map(|serial| serial as i64)
+}
+
+/// The whole [Excel 1900](fn.excel_1900.html) serial of the given
+/// date, even one Excel can't show (which is then out of range).
+pub(crate) fn excel_1900_serial(date: impl Datelike) -> i64 {
+    let days = i64::from(date.num_days_from_ce()) - UNIX_EPOCH_DAY_CE + EXCEL_1900_DAYS;
+    // Before the phantom leap day, serials are one day earlier.
+    if days < 61 {
+        days - 1
+    } else {
+        days
+    }
+}
+
+/// The whole [Excel 1904](fn.excel_1904.html) serial of the given
+/// date, even one Excel can't show.
+pub(crate) fn excel_1904_serial(date: impl Datelike) -> i64 {
+    i64::from(date.num_days_from_ce()) - UNIX_EPOCH_DAY_CE + EXCEL_1904_DAYS
+}
+
+/// The time the given number of milliseconds after the day zero the
+/// given number of days before the Unix epoch.
+fn from_millis(millis: i64, days: i64) -> Option<NaiveDateTime> {
+    let t = millis.checked_sub(days * MILLIS_PER_DAY)?;
+    DateTime::from_timestamp_millis(t).map(|dt| dt.naive_utc())
+}
+
+/// The number of milliseconds from the day zero the given number of
+/// days before the Unix epoch to the given time.
+fn to_millis(ndt: NaiveDateTime, days: i64) -> Option<i64> {
+    ndt.and_utc()
+        .timestamp_millis()
+        .checked_add(days * MILLIS_PER_DAY)
+}
+
+/// The serial for the given number of milliseconds, if it's before
+/// the given end.
+fn to_serial(millis: i64, end: f64) -> Option<f64> {
+    let serial = millis as f64 / MILLIS_PER_DAY as f64;
+    (serial < end).then_some(serial)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn midnight(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn excel_1900_early_serials() {
+        assert_eq!(excel_1900(0.0), Some(midnight(1899, 12, 31)));
+        assert_eq!(excel_1900(1.0), Some(midnight(1900, 1, 1)));
+        assert_eq!(excel_1900(59.5).unwrap().to_string(), "1900-02-28 12:00:00");
+        assert!(excel_1900(60.5).is_none());
+        assert_eq!(excel_1900(61.0), Some(midnight(1900, 3, 1)));
+    }
+    #[test]
+    fn excel_1900_differs_from_icq_before_march() {
+        assert_eq!(crate::icq(1.0), Some(midnight(1899, 12, 31)));
+        assert_eq!(excel_1900(1.0), Some(midnight(1900, 1, 1)));
+        assert_eq!(crate::icq(61.0), excel_1900(61.0));
+    }
+    #[test]
+    fn excel_1900_out_of_range() {
+        assert!(excel_1900(-1.0).is_none());
+        assert!(excel_1900(f64::NAN).is_none());
+        assert!(excel_1900(EXCEL_1900_END).is_none());
+        assert_eq!(
+            excel_1900(EXCEL_1900_END - 1.0),
+            Some(midnight(9999, 12, 31))
+        );
+        assert!(to_excel_1900(midnight(1899, 12, 30)).is_none());
+        assert!(to_excel_1900(midnight(10000, 1, 1)).is_none());
+    }
+    #[test]
+    fn excel_1900_round_trip() {
+        for serial in [0.0, 1.25, 59.0, 61.0, 39857.980208333334, 2_958_465.5] {
+            let ndt = excel_1900(serial).unwrap();
+            assert_eq!(to_excel_1900(ndt), Some(serial), "{serial}");
+        }
+    }
+    #[test]
+    fn excel_dates() {
+        assert_eq!(excel_1900_date(59), NaiveDate::from_ymd_opt(1900, 2, 28));
+        assert_eq!(excel_1900_date(60), None);
+        assert_eq!(excel_1900_date(-1), None);
+        let ndt = midnight(1900, 1, 1) + chrono::Duration::hours(18);
+        assert_eq!(to_excel_1900_date(ndt), Some(1));
+        assert_eq!(excel_1904_date(0), NaiveDate::from_ymd_opt(1904, 1, 1));
+        assert_eq!(to_excel_1904_date(midnight(1903, 12, 31)), None);
+    }
+    #[test]
+    fn excel_1904_epoch() {
+        assert_eq!(excel_1904(0.0), Some(midnight(1904, 1, 1)));
+        assert_eq!(to_excel_1904(midnight(1904, 1, 1)), Some(0.0));
+        assert!(excel_1904(-0.5).is_none());
+        assert!(to_excel_1904(midnight(1903, 12, 31)).is_none());
+    }
+    #[test]
+    fn excel_systems_differ_by_1462_days() {
+        let ndt = midnight(2024, 2, 29);
+        let serial = to_excel_1900(ndt).unwrap();
+        assert_eq!(to_excel_1904(ndt), Some(serial - 1462.0));
+    }
+}
@@ -0,0 +1,96 @@
+//! ext4 inode timestamps.
+
+use chrono::{DateTime, NaiveDateTime};
+
+/// The low two bits of an `*_extra` field extend the seconds.
+const EPOCH_BITS: u32 = 2;
+
+const EPOCH_MASK: u32 = (1 << EPOCH_BITS) - 1;
+
+/// ext4 inodes store each timestamp as a signed 32-bit count of
+/// seconds since the Unix epoch (*e.g.*, `i_mtime`) and, in large
+/// inodes, an `*_extra` field (*e.g.*, `i_mtime_extra`). The low two
+/// bits of the extra field are added to the seconds as bits 32 and 33,
+/// and the remaining 30 bits are the nanoseconds. This covers
+/// 1901-12-13 to 2446-05-10.
+///
+/// ```
+/// use epochs::ext4;
+/// let ndt = ext4(1_234_567_890, 654_321_000 << 2).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.654321");
+/// let ndt = ext4(-1, 1).unwrap();
+/// assert_eq!(ndt.to_string(), "2106-02-07 06:28:15");
+/// ```
+pub fn ext4(seconds: i32, extra: u32) -> Option<NaiveDateTime> {
+    let t = i64::from(seconds) + (i64::from(extra & EPOCH_MASK) << 32);
+    DateTime::from_timestamp(t, extra >> EPOCH_BITS).map(|dt| dt.naive_utc())
+}
+
+/// Convert the given NaiveDateTime to an [ext4](fn.ext4.html) time,
+/// returning the seconds and the extra field. Returns None if it is
+/// outside the range ext4 can store.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_ext4;
+/// let ndt = NaiveDateTime::parse_from_str("2040-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_ext4(ndt), Some((-2_085_978_496, 1)));
+/// ```
+pub fn to_ext4(ndt: NaiveDateTime) -> Option<(i32, u32)> {
+    let t = ndt.and_utc().timestamp();
+    let seconds = t as i32;
+    let epoch = (t - i64::from(seconds)) >> 32;
+    if !(0..=i64::from(EPOCH_MASK)).contains(&epoch) {
+        return None;
+    }
+    let nanos = ndt.and_utc().timestamp_subsec_nanos();
+    Some((seconds, epoch as u32 | nanos << EPOCH_BITS))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn ext4_limits() {
+        let ndt = ext4(i32::MIN, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1901-12-13 20:45:52");
+        let ndt = ext4(i32::MAX, 3).unwrap();
+        assert_eq!(ndt.to_string(), "2446-05-10 22:38:55");
+    }
+    #[test]
+    fn ext4_without_extra() {
+        let ndt = ext4(i32::MAX, 0).unwrap();
+        assert_eq!(ndt.to_string(), "2038-01-19 03:14:07");
+        let ndt = ext4(i32::MIN, 1).unwrap();
+        assert_eq!(ndt.to_string(), "2038-01-19 03:14:08");
+    }
+    #[test]
+    fn to_ext4_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2200, 7, 4)
+            .unwrap()
+            .and_hms_nano_opt(12, 0, 0, 999_999_999)
+            .unwrap();
+        let (seconds, extra) = to_ext4(ndt).unwrap();
+        assert_eq!(extra & EPOCH_MASK, 2);
+        assert_eq!(ext4(seconds, extra), Some(ndt));
+    }
+    #[test]
+    fn to_ext4_before_1970() {
+        let ndt = NaiveDate::from_ymd_opt(1969, 12, 31)
+            .unwrap()
+            .and_hms_milli_opt(23, 59, 59, 500)
+            .unwrap();
+        assert_eq!(to_ext4(ndt), Some((-1, 500_000_000 << 2)));
+    }
+    #[test]
+    fn to_ext4_out_of_range() {
+        let ndt = ext4(i32::MIN, 0).unwrap();
+        assert!(to_ext4(ndt - chrono::Duration::seconds(1)).is_none());
+        let ndt = ext4(i32::MAX, 3).unwrap();
+        assert!(to_ext4(ndt + chrono::Duration::seconds(1)).is_none());
+    }
+}
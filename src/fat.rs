@@ -0,0 +1,342 @@
+//! FAT and exFAT directory entry timestamps.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+#[cfg(feature = "clock")]
+use chrono::{Local, LocalResult};
+
+/// In an exFAT UTC offset byte, bit 7 says whether the offset is valid.
+const OFFSET_VALID: u8 = 0x80;
+
+/// FAT time (*e.g.*, MS-DOS and FAT32 directory entries) packs a date
+/// and a time into two 16-bit words. The date has the year since 1980
+/// in bits 15-9, the month in bits 8-5, and the day in bits 4-0. The
+/// time has the hour in bits 15-11, the minute in bits 10-5, and the
+/// seconds divided by two in bits 4-0.
+///
+/// Creation times may also have a third "fine resolution" byte,
+/// counting 10 ms units from 0 to 199 to make up for the two-second
+/// granularity of the time word. FAT times are local times, with no
+/// record of which time zone.
+///
+/// ```
+/// use epochs::fat;
+/// let ndt = fat(0x3a4d, 0xbbef, None).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// let ndt = fat(0x3a4d, 0xbbef, Some(145)).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:31.450");
+/// ```
+pub fn fat(date: u16, time: u16, tenths: Option<u8>) -> Option<NaiveDateTime> {
+    let year = 1980 + (date >> 9) as i32;
+    let month = ((date >> 5) & 0x0f) as u32;
+    let day = (date & 0x1f) as u32;
+
+    let hour = (time >> 11) as u32;
+    let minute = ((time >> 5) & 0x3f) as u32;
+    let second = ((time & 0x1f) * 2) as u32;
+
+    let centis = tenths.unwrap_or(0) as u32;
+    if centis > 199 {
+        return None;
+    }
+
+    let ndt = NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)?;
+    ndt.checked_add_signed(Duration::milliseconds(centis as i64 * 10))
+}
+
+/// Convert the given NaiveDateTime to a [FAT](fn.fat.html) time,
+/// returning the date word, the time word, and the fine resolution
+/// byte. Returns None for years outside 1980 to 2107, which FAT
+/// cannot represent. Anything finer than 10 ms is dropped.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_fat;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:31.45", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_fat(ndt), Some((0x3a4d, 0xbbef, 145)));
+/// ```
+pub fn to_fat(ndt: NaiveDateTime) -> Option<(u16, u16, u8)> {
+    let year = ndt.year() - 1980;
+    if !(0..=127).contains(&year) {
+        return None;
+    }
+    let date = (year as u16) << 9 | (ndt.month() as u16) << 5 | ndt.day() as u16;
+    let time = (ndt.hour() as u16) << 11 | (ndt.minute() as u16) << 5 | (ndt.second() / 2) as u16;
+    // A leap second shows up as an extra second of nanoseconds.
+    let nanos = ndt.nanosecond().min(999_999_999);
+    let tenths = (ndt.second() % 2) * 100 + nanos / 10_000_000;
+    Some((date, time, tenths as u8))
+}
+
+/// Like [fat](fn.fat.html), but for a FAT time known to have been
+/// written at the given offset from UTC, returning it with that
+/// offset.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::FixedOffset;
+/// use epochs::fat_with_offset;
+/// let offset = FixedOffset::east_opt(60 * 60).unwrap();
+/// let dt = fat_with_offset(0x3a4d, 0xbbef, None, offset).unwrap();
+/// assert_eq!(dt.to_rfc3339(), "2009-02-13T23:31:30+01:00");
+/// assert_eq!(dt.naive_utc().to_string(), "2009-02-13 22:31:30");
+/// ```
+pub fn fat_with_offset(
+    date: u16,
+    time: u16,
+    tenths: Option<u8>,
+    offset: FixedOffset,
+) -> Option<DateTime<FixedOffset>> {
+    fat(date, time, tenths)?.and_local_timezone(offset).single()
+}
+
+/// Convert the given DateTime to a [FAT](fn.fat.html) time in its own
+/// offset from UTC. See [to_fat](fn.to_fat.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::DateTime;
+/// use epochs::to_fat_with_offset;
+/// let dt = DateTime::parse_from_rfc3339("2009-02-13T23:31:31.45+01:00").unwrap();
+/// assert_eq!(to_fat_with_offset(dt), Some((0x3a4d, 0xbbef, 145)));
+/// ```
+pub fn to_fat_with_offset(dt: DateTime<FixedOffset>) -> Option<(u16, u16, u8)> {
+    to_fat(dt.naive_local())
+}
+
+/// Like [fat](fn.fat.html), but for a FAT time written in the local
+/// time zone of this machine. The resolution says whether that time
+/// was ambiguous (in the hour repeated when the clocks go back) or
+/// never happened (in the hour skipped when they go forward). Returns
+/// None if any of the fields are invalid.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::Local;
+/// use epochs::{fat, fat_local};
+/// let local = fat_local(0x3a4d, 0xbbef, None).unwrap();
+/// let ndt = fat(0x3a4d, 0xbbef, None).unwrap();
+/// assert_eq!(local, ndt.and_local_timezone(Local));
+/// ```
+#[cfg(feature = "clock")]
+pub fn fat_local(date: u16, time: u16, tenths: Option<u8>) -> Option<LocalResult<DateTime<Local>>> {
+    Some(fat(date, time, tenths)?.and_local_timezone(Local))
+}
+
+/// exFAT time packs the [FAT](fn.fat.html) date (in the high 16 bits)
+/// and time (in the low 16 bits) into one 32-bit timestamp, with a 10 ms
+/// increment byte from 0 to 199 and a UTC offset byte. The offset byte
+/// has the offset in 15-minute units as a 7-bit signed number in bits
+/// 6-0, and bit 7 set if it is valid. Returns the local time as
+/// written, along with its offset from UTC if known. Returns None if
+/// any of the fields are invalid.
+///
+/// ```
+/// use epochs::exfat;
+/// let (ndt, offset) = exfat(0x3a4d_bbef, 145, 0x84u8 as i8).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:31.450");
+/// assert_eq!(offset.unwrap().to_string(), "+01:00");
+/// let (_, offset) = exfat(0x3a4d_bbef, 145, 0).unwrap();
+/// assert_eq!(offset, None);
+/// ```
+pub fn exfat(
+    timestamp: u32,
+    ten_ms: u8,
+    utc_offset: i8,
+) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
+    let ndt = fat((timestamp >> 16) as u16, timestamp as u16, Some(ten_ms))?;
+    let byte = utc_offset as u8;
+    let offset = if byte & OFFSET_VALID != 0 {
+        // Sign-extend the low seven bits.
+        let quarters = i32::from((byte << 1) as i8 >> 1);
+        Some(FixedOffset::east_opt(quarters * 15 * 60)?)
+    } else {
+        None
+    };
+    Some((ndt, offset))
+}
+
+/// Convert the given local NaiveDateTime and its offset from UTC (if
+/// known) to an [exFAT](fn.exfat.html) time, returning the timestamp,
+/// the 10 ms increment, and the UTC offset byte. Returns None for years
+/// outside 1980 to 2107, or an offset that is not a whole number of
+/// quarter hours within -16:00 to +15:45.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{FixedOffset, NaiveDateTime};
+/// use epochs::to_exfat;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:31.45", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// let offset = FixedOffset::west_opt(5 * 60 * 60).unwrap();
+/// assert_eq!(to_exfat(ndt, Some(offset)), Some((0x3a4d_bbef, 145, 0xecu8 as i8)));
+/// assert_eq!(to_exfat(ndt, None), Some((0x3a4d_bbef, 145, 0)));
+/// ```
+pub fn to_exfat(ndt: NaiveDateTime, offset: Option<FixedOffset>) -> Option<(u32, u8, i8)> {
+    let (date, time, ten_ms) = to_fat(ndt)?;
+    let byte = match offset {
+        Some(offset) => {
+            let seconds = offset.local_minus_utc();
+            if seconds % (15 * 60) != 0 {
+                return None;
+            }
+            let quarters = seconds / (15 * 60);
+            if !(-64..=63).contains(&quarters) {
+                return None;
+            }
+            OFFSET_VALID | (quarters as u8 & 0x7f)
+        }
+        None => 0,
+    };
+    Some((u32::from(date) << 16 | u32::from(time), ten_ms, byte as i8))
+}
+
+/// Like [exfat](fn.exfat.html), but returning a DateTime, in the
+/// offset it was written with if that is valid, or else in the given
+/// fallback offset (*e.g.*, that of the machine that wrote it).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::FixedOffset;
+/// use epochs::exfat_with_offset;
+/// let utc = FixedOffset::east_opt(0).unwrap();
+/// let dt = exfat_with_offset(0x3a4d_bbef, 145, 0x84u8 as i8, utc).unwrap();
+/// assert_eq!(dt.to_rfc3339(), "2009-02-13T23:31:31.450+01:00");
+/// let dt = exfat_with_offset(0x3a4d_bbef, 145, 0, utc).unwrap();
+/// assert_eq!(dt.to_rfc3339(), "2009-02-13T23:31:31.450+00:00");
+/// ```
+pub fn exfat_with_offset(
+    timestamp: u32,
+    ten_ms: u8,
+    utc_offset: i8,
+    fallback: FixedOffset,
+) -> Option<DateTime<FixedOffset>> {
+    let (ndt, offset) = exfat(timestamp, ten_ms, utc_offset)?;
+    ndt.and_local_timezone(offset.unwrap_or(fallback)).single()
+}
+
+/// Like [exfat](fn.exfat.html), but in the offset it was written with
+/// if that is valid, or else in the local time zone of this machine,
+/// which may be ambiguous (see [fat_local](fn.fat_local.html)). Returns
+/// None if any of the fields are invalid.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{LocalResult, Utc};
+/// use epochs::exfat_local;
+/// let LocalResult::Single(dt) = exfat_local(0x3a4d_bbef, 145, 0x84u8 as i8).unwrap() else {
+///     panic!("a recorded offset is never ambiguous");
+/// };
+/// assert_eq!(dt.with_timezone(&Utc).to_rfc3339(), "2009-02-13T22:31:31.450+00:00");
+/// ```
+#[cfg(feature = "clock")]
+pub fn exfat_local(
+    timestamp: u32,
+    ten_ms: u8,
+    utc_offset: i8,
+) -> Option<LocalResult<DateTime<Local>>> {
+    let (ndt, offset) = exfat(timestamp, ten_ms, utc_offset)?;
+    Some(match offset {
+        Some(offset) => ndt
+            .and_local_timezone(offset)
+            .map(|dt| dt.with_timezone(&Local)),
+        None => ndt.and_local_timezone(Local),
+    })
+}
+
+/// Convert the given DateTime to an [exFAT](fn.exfat.html) time,
+/// recording its offset from UTC. See [to_exfat](fn.to_exfat.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::DateTime;
+/// use epochs::to_exfat_with_offset;
+/// let dt = DateTime::parse_from_rfc3339("2009-02-13T23:31:31.45-05:00").unwrap();
+/// assert_eq!(to_exfat_with_offset(dt), Some((0x3a4d_bbef, 145, 0xecu8 as i8)));
+/// ```
+pub fn to_exfat_with_offset(dt: DateTime<FixedOffset>) -> Option<(u32, u8, i8)> {
+    to_exfat(dt.naive_local(), Some(*dt.offset()))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn fat_run() {
+        let ndt = fat(0x3a4d, 0xbbef, None).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn fat_epoch() {
+        let ndt = fat(0x0021, 0, Some(0)).unwrap();
+        assert_eq!(ndt.to_string(), "1980-01-01 00:00:00");
+    }
+    #[test]
+    fn fat_invalid() {
+        // Day zero, month 13, hour 24, and too much fine resolution.
+        assert!(fat(0x3a40, 0, None).is_none());
+        assert!(fat(0x3bad, 0, None).is_none());
+        assert!(fat(0x3a4d, 0xc000, None).is_none());
+        assert!(fat(0x3a4d, 0xbbef, Some(200)).is_none());
+    }
+    #[test]
+    fn to_fat_run() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        assert_eq!(to_fat(ndt), Some((0x3a4d, 0xbbef, 0)));
+    }
+    #[test]
+    fn to_fat_out_of_range() {
+        let ndt = NaiveDate::from_ymd_opt(1979, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert_eq!(to_fat(ndt), None);
+        let ndt = NaiveDate::from_ymd_opt(2108, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(to_fat(ndt), None);
+    }
+    #[test]
+    fn exfat_offsets() {
+        for quarters in [-64, -20, -1, 0, 22, 63] {
+            let offset = FixedOffset::east_opt(quarters * 15 * 60).unwrap();
+            let ndt = fat(0x3a4d, 0xbbef, Some(145)).unwrap();
+            let (timestamp, ten_ms, byte) = to_exfat(ndt, Some(offset)).unwrap();
+            assert_eq!(exfat(timestamp, ten_ms, byte), Some((ndt, Some(offset))));
+        }
+    }
+    #[test]
+    fn exfat_invalid() {
+        assert!(exfat(0x3a4d_bbef, 200, 0).is_none());
+        assert!(exfat(0x3a40_0000, 0, 0).is_none());
+        let ndt = fat(0x3a4d, 0xbbef, None).unwrap();
+        let offset = FixedOffset::east_opt(16 * 60 * 60).unwrap();
+        assert!(to_exfat(ndt, Some(offset)).is_none());
+        let offset = FixedOffset::east_opt(10 * 60).unwrap();
+        assert!(to_exfat(ndt, Some(offset)).is_none());
+    }
+    #[test]
+    fn round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2107, 12, 31)
+            .unwrap()
+            .and_hms_milli_opt(23, 59, 59, 990)
+            .unwrap();
+        let (date, time, tenths) = to_fat(ndt).unwrap();
+        assert_eq!(fat(date, time, Some(tenths)), Some(ndt));
+    }
+    #[test]
+    fn with_offset_round_trip() {
+        let offset = FixedOffset::west_opt(8 * 60 * 60).unwrap();
+        let dt = fat_with_offset(0x3a4d, 0xbbef, Some(145), offset).unwrap();
+        assert_eq!(dt.naive_utc().to_string(), "2009-02-14 07:31:31.450");
+        assert_eq!(to_fat_with_offset(dt), Some((0x3a4d, 0xbbef, 145)));
+        let (timestamp, ten_ms, byte) = to_exfat_with_offset(dt).unwrap();
+        let utc = FixedOffset::east_opt(0).unwrap();
+        assert_eq!(exfat_with_offset(timestamp, ten_ms, byte, utc), Some(dt));
+    }
+}
@@ -0,0 +1,160 @@
+//! Converting and formatting in one step.
+
+use std::error::Error;
+use std::fmt::{self, Write};
+
+use chrono::format::{Item, ParseErrorKind, StrftimeItems};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, ParseError};
+
+use crate::{ConversionError, Epoch};
+
+/// The error returned by [format](fn.format.html) and
+/// [parse_as](fn.parse_as.html). The conversion or parse error
+/// behind it, if any, is its source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatError {
+    /// The number could not be converted to a NaiveDateTime, or the
+    /// parsed time could not be converted to a number.
+    Conversion(ConversionError),
+    /// The format string has an invalid specifier, or one (like a time
+    /// zone) that a NaiveDateTime has nothing to fill in with.
+    InvalidFormat,
+    /// The string does not match the format.
+    Parse(ParseError),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Conversion(_) => f.write_str("conversion failed"),
+            FormatError::InvalidFormat => f.write_str("invalid format string"),
+            FormatError::Parse(_) => f.write_str("string does not match the format"),
+        }
+    }
+}
+
+impl Error for FormatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FormatError::Conversion(e) => Some(e),
+            FormatError::InvalidFormat => None,
+            FormatError::Parse(e) => Some(e),
+        }
+    }
+}
+
+/// Convert the given number in the given epoch and format it with the
+/// given [strftime](https://docs.rs/chrono/latest/chrono/format/strftime/index.html)
+/// format string.
+///
+/// ```
+/// use epochs::{format, Epoch};
+/// let s = format(Epoch::Chrome, 12_879_041_490_654_321, "%Y-%m-%d %H:%M:%S%.3f").unwrap();
+/// assert_eq!(s, "2009-02-13 23:31:30.654");
+/// assert!(format(Epoch::Unix, i64::MAX, "%F").is_err());
+/// assert!(format(Epoch::Unix, 0, "%Q").is_err());
+/// ```
+pub fn format(epoch: Epoch, num: i64, fmt: &str) -> Result<String, FormatError> {
+    let ndt = epoch
+        .try_to_datetime(num)
+        .map_err(FormatError::Conversion)?;
+    let items: Vec<Item> = StrftimeItems::new(fmt).collect();
+    if items.contains(&Item::Error) {
+        return Err(FormatError::InvalidFormat);
+    }
+    let mut s = String::new();
+    write!(s, "{}", ndt.format_with_items(items.iter())).map_err(|_| FormatError::InvalidFormat)?;
+    Ok(s)
+}
+
+/// Parse the given string with the given strftime format string and
+/// convert it to a number in the given epoch, the reverse of
+/// [format](fn.format.html). If the format includes an offset from
+/// UTC, the time is converted to UTC; if it has no time at all, it is
+/// taken to be midnight.
+///
+/// ```
+/// use epochs::{parse_as, Epoch};
+/// let num = parse_as(Epoch::Java, "13/02/2009 23:31:30", "%d/%m/%Y %H:%M:%S").unwrap();
+/// assert_eq!(num, 1_234_567_890_000);
+/// let num = parse_as(Epoch::Unix, "2009-02-14 00:31:30 +0100", "%Y-%m-%d %H:%M:%S %z").unwrap();
+/// assert_eq!(num, 1_234_567_890);
+/// assert!(parse_as(Epoch::Unix, "yesterday", "%F").is_err());
+/// ```
+pub fn parse_as(epoch: Epoch, s: &str, fmt: &str) -> Result<i64, FormatError> {
+    let ndt = match DateTime::parse_from_str(s, fmt) {
+        Ok(dt) => dt.naive_utc(),
+        Err(_) => match NaiveDateTime::parse_from_str(s, fmt) {
+            Ok(ndt) => ndt,
+            Err(e) if e.kind() == ParseErrorKind::NotEnough => NaiveDate::parse_from_str(s, fmt)
+                .map_err(|_| FormatError::Parse(e))?
+                .and_hms_opt(0, 0, 0)
+                .ok_or(FormatError::Parse(e))?,
+            Err(e) => return Err(FormatError::Parse(e)),
+        },
+    };
+    epoch
+        .try_from_datetime(ndt)
+        .map_err(FormatError::Conversion)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn format_every_epoch() {
+        for epoch in Epoch::ALL {
+            let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+                .unwrap()
+                .and_hms_opt(23, 31, 30)
+                .unwrap();
+            let s = format(epoch, epoch.from_datetime(ndt), "%FT%T").unwrap();
+            let expected = if epoch.info().resolution == "days" {
+                "2009-02-13T00:00:00"
+            } else {
+                "2009-02-13T23:31:30"
+            };
+            assert_eq!(s, expected, "{epoch}");
+        }
+    }
+    #[test]
+    fn format_invalid() {
+        assert_eq!(
+            format(Epoch::Filemaker, -1, "%F"),
+            Err(FormatError::Conversion(ConversionError::Range))
+        );
+        assert_eq!(format(Epoch::Unix, 0, "%"), Err(FormatError::InvalidFormat));
+        // A NaiveDateTime has no offset to write.
+        assert_eq!(
+            format(Epoch::Unix, 0, "%F %z"),
+            Err(FormatError::InvalidFormat)
+        );
+    }
+    #[test]
+    fn parse_as_date_only() {
+        assert_eq!(
+            parse_as(Epoch::Unix, "2009-02-13", "%Y-%m-%d"),
+            Ok(1_234_483_200)
+        );
+    }
+    #[test]
+    fn parse_as_invalid() {
+        let err = parse_as(Epoch::Unix, "2009-02-13", "%Y-%m-%d %H:%M").unwrap_err();
+        assert!(matches!(err, FormatError::Parse(_)), "{err:?}");
+        let err = parse_as(Epoch::Unix, "2009-02-13x", "%Y-%m-%d").unwrap_err();
+        assert!(matches!(err, FormatError::Parse(_)), "{err:?}");
+        assert_eq!(
+            parse_as(Epoch::Apfs, "2300-01-01", "%F"),
+            Err(FormatError::Conversion(ConversionError::Scale))
+        );
+    }
+    #[test]
+    fn round_trip() {
+        let fmt = "%Y%m%d%H%M%S%.6f";
+        let s = format(Epoch::Mozilla, 1_234_567_890_654_321, fmt).unwrap();
+        assert_eq!(s, "20090213233130.654321");
+        assert_eq!(parse_as(Epoch::Mozilla, &s, fmt), Ok(1_234_567_890_654_321));
+    }
+}
@@ -0,0 +1,391 @@
+//! Satellite navigation (GNSS) system times.
+
+use chrono::{Datelike, Days, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+use crate::leap::{from_tai_nanos, to_tai_nanos, NANOS};
+
+const SECONDS_PER_WEEK: i64 = 7 * 24 * 60 * 60;
+
+/// GPS time started at 1980-01-06 00:00:00 UTC, when it was 19
+/// seconds behind TAI (*i.e.*, this is 1980-01-06 00:00:19 TAI).
+const GPS_TAI: i64 = 315_964_819;
+
+/// Galileo System Time is steered to GPS time, but its weeks count
+/// from the first GPS week rollover, 1999-08-22.
+const GALILEO_TAI: i64 = GPS_TAI + 1024 * SECONDS_PER_WEEK;
+
+/// BeiDou Time started at 2006-01-01 00:00:00 UTC, when TAI was 33
+/// seconds ahead.
+const BEIDOU_TAI: i64 = 1_136_073_633;
+
+/// GLONASS time is Moscow time, three hours ahead of UTC.
+const MOSCOW_OFFSET: i64 = 3 * 60 * 60;
+
+/// CDMA frames (power control groups) are 1.25 ms long.
+const FRAMES_PER_SECOND: i128 = 800;
+
+/// Legacy navigation messages only carry the week modulo 1024.
+const WEEK_ROLLOVER: i32 = 1024;
+
+/// GPS time is given as a week number (counting from 1980-01-06) and
+/// the seconds into the week, or time of week (TOW). GPS time does not
+/// have leap seconds, so this uses the leap second table to return
+/// the UTC time (18 seconds behind GPS time since 2017). Returns None
+/// if the time of week is not within a week.
+///
+/// The week numbers in legacy (*e.g.*, NMEA and older receiver)
+/// messages are only ten bits, and so have rolled over to zero twice,
+/// in 1999 and 2019; see [gps_week_tow_near](fn.gps_week_tow_near.html).
+///
+/// ```
+/// use epochs::gps_week_tow;
+/// let ndt = gps_week_tow(1518, 516_705.25).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.250");
+/// ```
+pub fn gps_week_tow(week: i32, tow: f64) -> Option<NaiveDateTime> {
+    week_tow(GPS_TAI, week, tow)
+}
+
+/// Convert a week and time of week from the given start (in TAI
+/// seconds) to UTC.
+fn week_tow(start: i64, week: i32, tow: f64) -> Option<NaiveDateTime> {
+    if !(0.0..SECONDS_PER_WEEK as f64).contains(&tow) {
+        return None;
+    }
+    let seconds = i128::from(week) * i128::from(SECONDS_PER_WEEK) + i128::from(start);
+    from_tai_nanos(seconds * NANOS + (tow * 1e9).round() as i128)
+}
+
+/// Convert UTC to a week and time of week from the given start.
+fn to_week_tow(start: i64, ndt: NaiveDateTime) -> (i32, f64) {
+    let nanos = to_tai_nanos(ndt) - i128::from(start) * NANOS;
+    let per_week = i128::from(SECONDS_PER_WEEK) * NANOS;
+    (
+        nanos.div_euclid(per_week) as i32,
+        nanos.rem_euclid(per_week) as f64 / 1e9,
+    )
+}
+
+/// Like [gps_week_tow](fn.gps_week_tow.html), but for a week number
+/// that may have rolled over: the week taken is the one (of those that
+/// agree modulo 1024) closest to the given reference time, such as a
+/// file's modification time or the present.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::gps_week_tow_near;
+/// let near = NaiveDateTime::parse_from_str("2020-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let ndt = gps_week_tow_near(0, 18.0, near).unwrap();
+/// assert_eq!(ndt.to_string(), "2019-04-07 00:00:00");
+/// ```
+pub fn gps_week_tow_near(week: u16, tow: f64, near: NaiveDateTime) -> Option<NaiveDateTime> {
+    let week = i32::from(week) % WEEK_ROLLOVER;
+    let (near_week, _) = to_gps_week_tow(near);
+    let rollovers = (near_week - week + WEEK_ROLLOVER / 2).div_euclid(WEEK_ROLLOVER);
+    gps_week_tow(week + rollovers * WEEK_ROLLOVER, tow)
+}
+
+/// Convert the given UTC NaiveDateTime to a [GPS](fn.gps_week_tow.html)
+/// week number and time of week.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_gps_week_tow;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.25", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_gps_week_tow(ndt), (1518, 516_705.25));
+/// ```
+pub fn to_gps_week_tow(ndt: NaiveDateTime) -> (i32, f64) {
+    to_week_tow(GPS_TAI, ndt)
+}
+
+/// CDMA (IS-95 and CDMA2000) system time is the number of seconds
+/// since 1980-01-06 00:00:00 UTC. Like GPS time, which it is
+/// synchronized to, it has no leap seconds, so this uses the leap
+/// second table to return the UTC time.
+///
+/// ```
+/// use epochs::cdma;
+/// let ndt = cdma(918_603_105).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn cdma(num: i64) -> Option<NaiveDateTime> {
+    from_tai_nanos((i128::from(num) + i128::from(GPS_TAI)) * NANOS)
+}
+
+/// Convert the given UTC NaiveDateTime to [CDMA](fn.cdma.html) system
+/// time. Fractions of a second are dropped.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_cdma;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_cdma(ndt), 918_603_105);
+/// ```
+pub fn to_cdma(ndt: NaiveDateTime) -> i64 {
+    (to_tai_nanos(ndt).div_euclid(NANOS) - i128::from(GPS_TAI)) as i64
+}
+
+/// Like [cdma](fn.cdma.html), but counting 1.25 ms frames (800 a
+/// second) rather than seconds.
+///
+/// ```
+/// use epochs::cdma_frames;
+/// let ndt = cdma_frames(734_882_484_001).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.001250");
+/// ```
+pub fn cdma_frames(num: i64) -> Option<NaiveDateTime> {
+    let nanos = i128::from(num) * (NANOS / FRAMES_PER_SECOND);
+    from_tai_nanos(nanos + i128::from(GPS_TAI) * NANOS)
+}
+
+/// Convert the given UTC NaiveDateTime to [CDMA
+/// frames](fn.cdma_frames.html). Fractions of a frame are dropped.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_cdma_frames;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.002", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_cdma_frames(ndt), 734_882_484_001);
+/// ```
+pub fn to_cdma_frames(ndt: NaiveDateTime) -> i64 {
+    let nanos = to_tai_nanos(ndt) - i128::from(GPS_TAI) * NANOS;
+    nanos.div_euclid(NANOS / FRAMES_PER_SECOND) as i64
+}
+
+/// Galileo System Time (GST) is given as a week number and time of
+/// week, like [GPS](fn.gps_week_tow.html) time, but its weeks count
+/// from 1999-08-22 (GPS week 1024). Returns the UTC time, or None if
+/// the time of week is not within a week.
+///
+/// ```
+/// use epochs::galileo_st;
+/// let ndt = galileo_st(494, 516_705.25).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.250");
+/// ```
+pub fn galileo_st(week: i32, tow: f64) -> Option<NaiveDateTime> {
+    week_tow(GALILEO_TAI, week, tow)
+}
+
+/// Convert the given UTC NaiveDateTime to a [Galileo System
+/// Time](fn.galileo_st.html) week number and time of week.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_galileo_st;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.25", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_galileo_st(ndt), (494, 516_705.25));
+/// ```
+pub fn to_galileo_st(ndt: NaiveDateTime) -> (i32, f64) {
+    to_week_tow(GALILEO_TAI, ndt)
+}
+
+/// BeiDou Time (BDT) is given as a week number counting from
+/// 2006-01-01 and the seconds of the week. It has no leap seconds, so
+/// it is 14 seconds behind GPS time. Returns the UTC time, or None if
+/// the seconds are not within a week.
+///
+/// ```
+/// use epochs::beidou_t;
+/// let ndt = beidou_t(162, 516_691.25).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.250");
+/// ```
+pub fn beidou_t(week: i32, sow: f64) -> Option<NaiveDateTime> {
+    week_tow(BEIDOU_TAI, week, sow)
+}
+
+/// Convert the given UTC NaiveDateTime to a [BeiDou
+/// Time](fn.beidou_t.html) week number and seconds of the week.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_beidou_t;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.25", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_beidou_t(ndt), (162, 516_691.25));
+/// ```
+pub fn to_beidou_t(ndt: NaiveDateTime) -> (i32, f64) {
+    to_week_tow(BEIDOU_TAI, ndt)
+}
+
+/// GLONASS time is Moscow time (UTC + 3 hours, with the same leap
+/// seconds), given as the number of the four-year interval since 1996
+/// (N4, starting at 1), the day within that interval (NT, from 1 to
+/// 1461), and the seconds of the day. Returns the UTC time, or None if
+/// any of those are out of range.
+///
+/// ```
+/// use epochs::glonass;
+/// let ndt = glonass(4, 411, 9_090.25).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.250");
+/// ```
+pub fn glonass(n4: u32, nt: u32, tod: f64) -> Option<NaiveDateTime> {
+    if n4 < 1 || !(1..=1461).contains(&nt) || !(0.0..86_400.0).contains(&tod) {
+        return None;
+    }
+    let year = 1996 + 4 * (i32::try_from(n4).ok()? - 1);
+    let day =
+        NaiveDate::from_ymd_opt(year, 1, 1)?.checked_add_days(Days::new(u64::from(nt) - 1))?;
+    let nanos = (tod * 1e9).round() as i64;
+    day.and_hms_opt(0, 0, 0)?
+        .checked_add_signed(Duration::nanoseconds(nanos))?
+        .checked_sub_signed(Duration::seconds(MOSCOW_OFFSET))
+}
+
+/// Convert the given UTC NaiveDateTime to [GLONASS](fn.glonass.html)
+/// time, returning the four-year interval (N4), the day within it
+/// (NT), and the seconds of the day. Returns None for times before
+/// 1996-01-01 Moscow time.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_glonass;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.25", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_glonass(ndt), Some((4, 411, 9_090.25)));
+/// ```
+pub fn to_glonass(ndt: NaiveDateTime) -> Option<(u32, u32, f64)> {
+    let moscow = ndt.checked_add_signed(Duration::seconds(MOSCOW_OFFSET))?;
+    let interval = (moscow.year() - 1996).checked_div_euclid(4)?;
+    if interval < 0 {
+        return None;
+    }
+    let start = NaiveDate::from_ymd_opt(1996 + 4 * interval, 1, 1)?;
+    let nt = (moscow.date() - start).num_days() + 1;
+    let tod = moscow.num_seconds_from_midnight() as f64 + moscow.nanosecond() as f64 / 1e9;
+    Some((interval as u32 + 1, nt as u32, tod))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn midnight(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn gps_week_zero() {
+        assert_eq!(gps_week_tow(0, 0.0), Some(midnight(1980, 1, 6)));
+    }
+    #[test]
+    fn gps_leap_seconds() {
+        // GPS was 18 seconds ahead of UTC after the 2016 leap second...
+        let ndt = gps_week_tow(1930, 18.0).unwrap();
+        assert_eq!(ndt, midnight(2017, 1, 1));
+        // ...and the leap second itself was 2016-12-31 23:59:60.
+        let ndt = gps_week_tow(1930, 17.0).unwrap();
+        assert_eq!(ndt.to_string(), "2016-12-31 23:59:60");
+    }
+    #[test]
+    fn gps_bad_tow() {
+        assert!(gps_week_tow(1518, -0.5).is_none());
+        assert!(gps_week_tow(1518, 604_800.0).is_none());
+        assert!(gps_week_tow(1518, f64::NAN).is_none());
+    }
+    #[test]
+    fn gps_rollovers() {
+        // The first rollover, 1999-08-22 (with 13 leap seconds).
+        let ndt = gps_week_tow_near(0, 13.0, midnight(2000, 1, 1)).unwrap();
+        assert_eq!(ndt, midnight(1999, 8, 22));
+        let ndt = gps_week_tow_near(0, 13.0, midnight(1985, 1, 1)).unwrap();
+        assert_eq!(ndt.to_string(), "1980-01-06 00:00:13");
+        let ndt = gps_week_tow_near(1023, 0.0, midnight(2019, 5, 1)).unwrap();
+        assert_eq!(to_gps_week_tow(ndt).0, 2047);
+    }
+    #[test]
+    fn to_gps_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_milli_opt(12, 34, 56, 789)
+            .unwrap();
+        let (week, tow) = to_gps_week_tow(ndt);
+        assert_eq!(week, 2303);
+        assert_eq!(gps_week_tow(week, tow), Some(ndt));
+    }
+    #[test]
+    fn galileo_epoch() {
+        // GST week 0 started at GPS 1999-08-22 00:00:00, 13 seconds
+        // ahead of UTC.
+        let ndt = galileo_st(0, 13.0).unwrap();
+        assert_eq!(ndt, midnight(1999, 8, 22));
+        assert_eq!(to_galileo_st(ndt), (0, 13.0));
+    }
+    #[test]
+    fn beidou_epoch() {
+        assert_eq!(beidou_t(0, 0.0), Some(midnight(2006, 1, 1)));
+        // The 2016 leap second makes UTC another second behind.
+        let ndt = beidou_t(574, 4.0).unwrap();
+        assert_eq!(ndt, midnight(2017, 1, 1));
+    }
+    #[test]
+    fn gnss_agree() {
+        let ndt = midnight(2024, 2, 29);
+        let (gps_week, gps_tow) = to_gps_week_tow(ndt);
+        let (gst_week, gst_tow) = to_galileo_st(ndt);
+        let (bdt_week, bdt_sow) = to_beidou_t(ndt);
+        assert_eq!(gps_week - gst_week, 1024);
+        assert_eq!(gps_tow, gst_tow);
+        assert_eq!(gps_week - bdt_week, 1356);
+        assert_eq!(gps_tow - bdt_sow, 14.0);
+    }
+    #[test]
+    fn glonass_fields() {
+        // 1996-01-01 00:00:00 Moscow time.
+        let ndt = glonass(1, 1, 0.0).unwrap();
+        assert_eq!(ndt.to_string(), "1995-12-31 21:00:00");
+        // The last day of a four-year interval, which includes a leap
+        // day.
+        let ndt = glonass(8, 1461, 0.0).unwrap();
+        assert_eq!(ndt.to_string(), "2027-12-30 21:00:00");
+        assert!(glonass(0, 1, 0.0).is_none());
+        assert!(glonass(1, 0, 0.0).is_none());
+        assert!(glonass(1, 1462, 0.0).is_none());
+        assert!(glonass(1, 1, 86_400.0).is_none());
+    }
+    #[test]
+    fn to_glonass_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_milli_opt(22, 34, 56, 789)
+            .unwrap();
+        let (n4, nt, tod) = to_glonass(ndt).unwrap();
+        assert_eq!((n4, nt), (8, 61));
+        assert_eq!(glonass(n4, nt, tod), Some(ndt));
+        assert!(to_glonass(midnight(1995, 12, 31)).is_none());
+    }
+    #[test]
+    fn cdma_matches_gps() {
+        let ndt = midnight(2024, 2, 29);
+        let (week, tow) = to_gps_week_tow(ndt);
+        assert_eq!(
+            to_cdma(ndt),
+            i64::from(week) * SECONDS_PER_WEEK + tow as i64
+        );
+        assert_eq!(cdma(0), Some(midnight(1980, 1, 6)));
+        assert_eq!(cdma(to_cdma(ndt)), Some(ndt));
+    }
+    #[test]
+    fn cdma_frames_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(1979, 12, 31)
+            .unwrap()
+            .and_hms_micro_opt(23, 59, 59, 998_750)
+            .unwrap();
+        // Five days, a frame, and the leap second at the end of 1979.
+        assert_eq!(to_cdma_frames(ndt), -5 * 24 * 60 * 60 * 800 - 1 - 800);
+        assert_eq!(cdma_frames(to_cdma_frames(ndt)), Some(ndt));
+    }
+    #[test]
+    fn cdma_too_big() {
+        assert!(cdma(i64::MAX).is_none());
+        assert!(cdma_frames(i64::MIN).is_none());
+    }
+}
@@ -0,0 +1,142 @@
+//! Go `time.Time` binary marshaling.
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+
+use crate::WindowsDateTicks;
+
+/// Go counts seconds from 0001-01-01, like `windows_date`.
+const GO_OFFSET: i64 = -WindowsDateTicks::EPOCH.offset_seconds();
+
+/// The zone offset Go writes for `time.UTC`.
+const UTC_MINUTES: i16 = -1;
+
+/// Go's `time.Time.MarshalBinary` (also used by gob) writes a version
+/// byte, the seconds since 0001-01-01 UTC as a big-endian `int64`, the
+/// nanoseconds as a big-endian `int32`, and the zone offset in minutes
+/// as a big-endian `int16` (-1 for UTC). Version 2 adds a byte of
+/// offset seconds. Returns the UTC time and its offset, or None if
+/// the bytes are not a valid encoding.
+///
+/// ```
+/// use epochs::go_binary;
+/// let bytes = [1, 0, 0, 0, 14, 193, 39, 249, 210, 0, 0, 0, 0, 255, 255];
+/// let (ndt, offset) = go_binary(&bytes).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(offset.to_string(), "+00:00");
+/// ```
+pub fn go_binary(bytes: &[u8]) -> Option<(NaiveDateTime, FixedOffset)> {
+    let extra_seconds = match (bytes.first()?, bytes.len()) {
+        (1, 15) => 0,
+        (2, 16) => i32::from(bytes[15]),
+        _ => return None,
+    };
+    let seconds = i64::from_be_bytes(bytes[1..9].try_into().ok()?);
+    let nanos = i32::from_be_bytes(bytes[9..13].try_into().ok()?);
+    let minutes = i16::from_be_bytes(bytes[13..15].try_into().ok()?);
+
+    let offset = if minutes == UTC_MINUTES {
+        if extra_seconds != 0 {
+            return None;
+        }
+        FixedOffset::east_opt(0)?
+    } else {
+        FixedOffset::east_opt(i32::from(minutes) * 60 + extra_seconds)?
+    };
+    let nanos = u32::try_from(nanos).ok().filter(|&n| n < 1_000_000_000)?;
+    let ndt = DateTime::from_timestamp(seconds.checked_sub(GO_OFFSET)?, nanos)?.naive_utc();
+    Some((ndt, offset))
+}
+
+/// Encode the given UTC time and offset the way Go's
+/// [MarshalBinary](fn.go_binary.html) does. A zero offset is written as
+/// UTC, and offsets with leftover seconds use version 2.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{FixedOffset, NaiveDateTime};
+/// use epochs::to_go_binary;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let bytes = to_go_binary(ndt, FixedOffset::east_opt(0).unwrap());
+/// assert_eq!(bytes, [1, 0, 0, 0, 14, 193, 39, 249, 210, 0, 0, 0, 0, 255, 255]);
+/// ```
+pub fn to_go_binary(ndt: NaiveDateTime, offset: FixedOffset) -> Vec<u8> {
+    let seconds = ndt.and_utc().timestamp() + GO_OFFSET;
+    // A leap second is written as the end of second 59.
+    let nanos = ndt.and_utc().timestamp_subsec_nanos().min(999_999_999) as i32;
+    let offset_seconds = offset.local_minus_utc();
+    let minutes = if offset_seconds == 0 {
+        UTC_MINUTES
+    } else {
+        (offset_seconds / 60) as i16
+    };
+    let extra_seconds = offset_seconds % 60;
+
+    let mut bytes = Vec::with_capacity(16);
+    bytes.push(if extra_seconds == 0 { 1 } else { 2 });
+    bytes.extend_from_slice(&seconds.to_be_bytes());
+    bytes.extend_from_slice(&nanos.to_be_bytes());
+    bytes.extend_from_slice(&minutes.to_be_bytes());
+    if extra_seconds != 0 {
+        bytes.push(extra_seconds as u8);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn go_binary_zero_time() {
+        // time.Time{} is 0001-01-01 00:00:00 UTC.
+        let bytes = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 255];
+        let (ndt, _) = go_binary(&bytes).unwrap();
+        assert_eq!(ndt.to_string(), "0001-01-01 00:00:00");
+    }
+    #[test]
+    fn go_binary_offset() {
+        // 2009-02-13 18:31:30.5 EST
+        let mut bytes = to_go_binary(
+            NaiveDate::from_ymd_opt(2009, 2, 13)
+                .unwrap()
+                .and_hms_milli_opt(23, 31, 30, 500)
+                .unwrap(),
+            FixedOffset::west_opt(5 * 60 * 60).unwrap(),
+        );
+        assert_eq!(&bytes[13..], &(-300_i16).to_be_bytes());
+        let (ndt, offset) = go_binary(&bytes).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.500");
+        assert_eq!(offset.to_string(), "-05:00");
+        bytes.push(0);
+        assert!(go_binary(&bytes).is_none());
+    }
+    #[test]
+    fn go_binary_version_2() {
+        // Amsterdam's mean time was +00:19:32 until 1937.
+        let ndt = NaiveDate::from_ymd_opt(1930, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let offset = FixedOffset::east_opt(19 * 60 + 32).unwrap();
+        let bytes = to_go_binary(ndt, offset);
+        assert_eq!(bytes.len(), 16);
+        assert_eq!((bytes[0], bytes[15]), (2, 32));
+        assert_eq!(go_binary(&bytes), Some((ndt, offset)));
+    }
+    #[test]
+    fn go_binary_invalid() {
+        assert!(go_binary(&[]).is_none());
+        assert!(go_binary(&[1, 0, 0]).is_none());
+        let mut bytes = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 255];
+        bytes[0] = 3;
+        assert!(go_binary(&bytes).is_none());
+        bytes[0] = 1;
+        bytes[9] = 0x40;
+        assert!(go_binary(&bytes).is_none());
+        bytes[9] = 0;
+        bytes[1] = 0x7f;
+        assert!(go_binary(&bytes).is_none());
+    }
+}
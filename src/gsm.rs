@@ -0,0 +1,145 @@
+//! GSM SMS service centre time stamps (SCTS).
+
+use chrono::{Datelike, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+
+/// Decode one semi-octet (nibble-swapped BCD) byte.
+fn from_semi_octet(b: u8) -> Option<u32> {
+    let (tens, units) = (b & 0x0f, b >> 4);
+    if tens > 9 || units > 9 {
+        return None;
+    }
+    Some(u32::from(tens) * 10 + u32::from(units))
+}
+
+/// Encode a number below 100 as a semi-octet byte.
+fn to_semi_octet(n: u32) -> u8 {
+    (((n % 10) << 4) | (n / 10)) as u8
+}
+
+/// The service centre time stamp (TP-SCTS, 3GPP TS 23.040) of an SMS
+/// PDU is seven semi-octets: year (in the century), month, day, hour,
+/// minute, second, and the time zone in quarter hours, each a pair of
+/// BCD digits with the nibbles swapped. The sign of the time zone is
+/// bit 3 of the last byte. Returns the local time as written, along
+/// with its offset from UTC; the years are taken to be 2000 to 2099.
+/// Returns None if any of the digits or fields are invalid.
+///
+/// ```
+/// use epochs::gsm_scts;
+/// let (ndt, offset) = gsm_scts(&[0x90, 0x20, 0x31, 0x32, 0x13, 0x03, 0x40]).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(offset.to_string(), "+01:00");
+/// ```
+pub fn gsm_scts(bytes: &[u8; 7]) -> Option<(NaiveDateTime, FixedOffset)> {
+    let mut fields = [0; 6];
+    for (field, &b) in fields.iter_mut().zip(bytes) {
+        *field = from_semi_octet(b)?;
+    }
+    let [year, month, day, hour, minute, second] = fields;
+    let ndt = NaiveDate::from_ymd_opt(2000 + year as i32, month, day)?
+        .and_hms_opt(hour, minute, second)?;
+
+    let quarters = from_semi_octet(bytes[6] & !0x08)? as i32;
+    let seconds = quarters * 15 * 60;
+    let offset = if bytes[6] & 0x08 != 0 {
+        FixedOffset::west_opt(seconds)?
+    } else {
+        FixedOffset::east_opt(seconds)?
+    };
+    Some((ndt, offset))
+}
+
+/// Encode the given local time and offset from UTC as a [GSM
+/// SCTS](fn.gsm_scts.html). Fractions of a second are dropped. Returns
+/// None if the year is not 2000 to 2099 or the offset is not a whole
+/// number of quarter hours within ±19:45.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{FixedOffset, NaiveDateTime};
+/// use epochs::to_gsm_scts;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let offset = FixedOffset::west_opt(5 * 60 * 60).unwrap();
+/// assert_eq!(
+///     to_gsm_scts(ndt, offset),
+///     Some([0x90, 0x20, 0x31, 0x32, 0x13, 0x03, 0x0a]),
+/// );
+/// ```
+pub fn to_gsm_scts(ndt: NaiveDateTime, offset: FixedOffset) -> Option<[u8; 7]> {
+    if !(2000..=2099).contains(&ndt.year()) {
+        return None;
+    }
+    let seconds = offset.local_minus_utc();
+    if seconds % (15 * 60) != 0 {
+        return None;
+    }
+    let quarters = (seconds / (15 * 60)).unsigned_abs();
+    if quarters > 79 {
+        return None;
+    }
+    let sign = if seconds < 0 { 0x08 } else { 0 };
+    Some([
+        to_semi_octet(ndt.year() as u32 - 2000),
+        to_semi_octet(ndt.month()),
+        to_semi_octet(ndt.day()),
+        to_semi_octet(ndt.hour()),
+        to_semi_octet(ndt.minute()),
+        // A leap second is written as second 59.
+        to_semi_octet(ndt.second().min(59)),
+        to_semi_octet(quarters) | sign,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn gsm_scts_negative_offset() {
+        let (ndt, offset) = gsm_scts(&[0x90, 0x20, 0x31, 0x32, 0x13, 0x03, 0x0a]).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+        assert_eq!(offset.to_string(), "-05:00");
+    }
+    #[test]
+    fn gsm_scts_quarter_hours() {
+        // India, +05:30, is 22 quarter hours.
+        let (_, offset) = gsm_scts(&[0x00, 0x10, 0x10, 0x00, 0x00, 0x00, 0x22]).unwrap();
+        assert_eq!(offset.to_string(), "+05:30");
+    }
+    #[test]
+    fn gsm_scts_bad_digits() {
+        assert!(gsm_scts(&[0x9a, 0x20, 0x31, 0x32, 0x13, 0x03, 0x40]).is_none());
+        assert!(gsm_scts(&[0x90, 0x20, 0x31, 0x32, 0x13, 0x03, 0xa0]).is_none());
+    }
+    #[test]
+    fn gsm_scts_bad_fields() {
+        // Month 13.
+        assert!(gsm_scts(&[0x90, 0x31, 0x31, 0x32, 0x13, 0x03, 0x40]).is_none());
+        // February 30th.
+        assert!(gsm_scts(&[0x90, 0x20, 0x03, 0x32, 0x13, 0x03, 0x40]).is_none());
+        // Hour 24.
+        assert!(gsm_scts(&[0x90, 0x20, 0x31, 0x42, 0x13, 0x03, 0x40]).is_none());
+    }
+    #[test]
+    fn to_gsm_scts_round_trip() {
+        let bytes = [0x99, 0x21, 0x13, 0x32, 0x95, 0x95, 0x9f];
+        let (ndt, offset) = gsm_scts(&bytes).unwrap();
+        assert_eq!(ndt.to_string(), "2099-12-31 23:59:59");
+        assert_eq!(offset.to_string(), "-19:45");
+        assert_eq!(to_gsm_scts(ndt, offset), Some(bytes));
+    }
+    #[test]
+    fn to_gsm_scts_out_of_range() {
+        let ndt = NaiveDate::from_ymd_opt(1999, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        let utc = FixedOffset::east_opt(0).unwrap();
+        assert!(to_gsm_scts(ndt, utc).is_none());
+        let ndt = ndt + chrono::Duration::seconds(1);
+        assert!(to_gsm_scts(ndt, utc).is_some());
+        assert!(to_gsm_scts(ndt, FixedOffset::east_opt(60).unwrap()).is_none());
+        assert!(to_gsm_scts(ndt, FixedOffset::east_opt(80 * 15 * 60).unwrap()).is_none());
+    }
+}
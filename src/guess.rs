@@ -0,0 +1,595 @@
+//! Work out which epoch a number might be in.
+
+use std::cmp::Reverse;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::{parse_hex, parse_value, Epoch, EpochError, ParseValueError, Value};
+
+/// The earliest date [guess](fn.guess.html) considers plausible.
+pub const GUESS_FROM: NaiveDateTime = midnight(1990, 1, 1);
+
+/// The latest date [guess](fn.guess.html) considers plausible.
+pub const GUESS_TO: NaiveDateTime = midnight(2040, 1, 1);
+
+const fn midnight(year: i32, month: u32, day: u32) -> NaiveDateTime {
+    match NaiveDate::from_ymd_opt(year, month, day) {
+        Some(date) => date.and_time(chrono::NaiveTime::MIN),
+        None => panic!("invalid date"),
+    }
+}
+
+/// Guess which epochs the given number might be in, returning the
+/// epochs under which it falls between [GUESS_FROM](constant.GUESS_FROM.html)
+/// and [GUESS_TO](constant.GUESS_TO.html), along with the
+/// interpretation under each.
+///
+/// ```
+/// use epochs::{guess, Epoch};
+/// let guesses = guess(1234567890);
+/// let epochs: Vec<_> = guesses.iter().map(|(epoch, _)| *epoch).collect();
+/// assert!(epochs.contains(&Epoch::Unix));
+/// assert!(epochs.contains(&Epoch::GoogleCalendar));
+/// assert!(!epochs.contains(&Epoch::Java));
+/// ```
+pub fn guess(num: i64) -> Vec<(Epoch, NaiveDateTime)> {
+    guess_in_range(num, GUESS_FROM, GUESS_TO)
+}
+
+/// Like [guess](fn.guess.html), but only keep the epochs under which
+/// the given number falls between `from` and `to` (inclusive).
+///
+/// Narrowing the window is the best way to tell apart epochs that
+/// differ only slightly. For example, this Windows file time is also a
+/// believable UUID version 1 time, but only one of them is recent.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use epochs::{guess, guess_in_range, Epoch};
+/// let num = 128_790_414_900_000_000;
+/// assert_eq!(guess(num).len(), 2);
+///
+/// let from = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+/// let to = NaiveDate::from_ymd_opt(2020, 12, 31).unwrap().and_hms_opt(23, 59, 59).unwrap();
+/// let guesses = guess_in_range(num, from, to);
+/// assert_eq!(guesses.len(), 1);
+/// assert_eq!(guesses[0].0, Epoch::WindowsFile);
+/// ```
+pub fn guess_in_range(
+    num: i64,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Vec<(Epoch, NaiveDateTime)> {
+    let guesses: Vec<_> = view_all(num)
+        .into_iter()
+        .filter_map(|(epoch, ndt)| Some((epoch, ndt?)))
+        .filter(|&(_, ndt)| from <= ndt && ndt <= to)
+        .collect();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        value = num,
+        %from,
+        %to,
+        candidates = guesses.len(),
+        epochs = ?guesses.iter().map(|(epoch, _)| epoch.name()).collect::<Vec<_>>(),
+        "guessed epochs"
+    );
+    guesses
+}
+
+/// A [guess](fn.guess.html) along with how plausible it is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RankedGuess {
+    /// The epoch the number might be in.
+    pub epoch: Epoch,
+    /// The number's time under that epoch.
+    pub datetime: NaiveDateTime,
+    /// How plausible this guess is, from 0 (not at all) to 1.
+    pub score: f64,
+}
+
+/// Like [guess](fn.guess.html), but score each guess and put the most
+/// plausible first. See [guess_ranked_at](fn.guess_ranked_at.html) for
+/// how the scores are worked out; this takes "now" to be the current
+/// time.
+///
+/// ```
+/// use epochs::{guess_ranked, Epoch};
+/// let ranked = guess_ranked(128_790_414_900_000_000);
+/// assert_eq!(ranked[0].epoch, Epoch::WindowsFile);
+/// ```
+#[cfg(feature = "clock")]
+pub fn guess_ranked(num: i64) -> Vec<RankedGuess> {
+    guess_ranked_at(num, chrono::Utc::now().naive_utc())
+}
+
+/// Like [guess](fn.guess.html), but score each guess and put the most
+/// plausible first. The score is the product of three things, each
+/// from 0 to 1:
+///
+/// - how close the time is to `now`: 1 at `now`, ½ five years away, ⅓
+///   ten years away, and so on;
+/// - how typical the number of digits is for the epoch (see
+///   [EpochInfo](struct.EpochInfo.html)): 1 if it's the same, ½ if it's
+///   one more or fewer, ⅓ if two, and so on; and
+/// - for an epoch which counts fractions of a second, whether the
+///   sub-second digits are all zero: 1 if so (since a number with more
+///   resolution than it uses was probably scaled up from a coarser one
+///   in that very unit), and ¾ otherwise. For any other epoch, this is
+///   always 1.
+///
+/// Guesses with equal scores stay in the order of [guess](fn.guess.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDate;
+/// use epochs::{guess, guess_ranked_at, Epoch};
+/// let now = NaiveDate::from_ymd_opt(2009, 3, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+/// // A whole second of Windows file time, which is also sometime in
+/// // 2010 as a UUID.
+/// let ranked = guess_ranked_at(128_790_414_900_000_000, now);
+/// assert_eq!(ranked.len(), guess(128_790_414_900_000_000).len());
+/// assert_eq!(ranked[0].epoch, Epoch::WindowsFile);
+/// assert!(ranked[0].score > ranked[1].score);
+/// ```
+pub fn guess_ranked_at(num: i64, now: NaiveDateTime) -> Vec<RankedGuess> {
+    let mut ranked: Vec<_> = guess(num)
+        .into_iter()
+        .map(|(epoch, datetime)| RankedGuess {
+            epoch,
+            datetime,
+            score: score(epoch, num, datetime, now),
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked
+}
+
+/// The plausibility score described at
+/// [guess_ranked_at](fn.guess_ranked_at.html).
+fn score(epoch: Epoch, num: i64, ndt: NaiveDateTime, now: NaiveDateTime) -> f64 {
+    let days = (ndt - now).num_days().unsigned_abs() as f64;
+    let recency = 1.0 / (1.0 + days / 365.25 / 5.0);
+
+    let digits = num.unsigned_abs().checked_ilog10().map_or(1, |n| n + 1) as usize;
+    let typicality = 1.0 / (1.0 + digits.abs_diff(epoch.info().digits) as f64);
+
+    let per_second = epoch
+        .linear()
+        .map_or(1, |linear| linear.unit().per_second());
+    let roundness = if per_second > 1 && num % per_second != 0 {
+        0.75
+    } else {
+        1.0
+    };
+
+    recency * typicality * roundness
+}
+
+/// How many values of a column each epoch explains, from
+/// [guess_many](fn.guess_many.html).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuessReport {
+    /// How many values there were.
+    pub values: usize,
+    /// How many values fell in the window under no epoch at all.
+    pub unexplained: usize,
+    /// Each epoch under which at least one value fell in the window,
+    /// with how many did, most first (and in the order of
+    /// [Epoch::ALL](enum.Epoch.html#associatedconstant.ALL) for ties).
+    pub counts: Vec<(Epoch, usize)>,
+}
+
+impl GuessReport {
+    /// The epoch which explains the most values, or None if none
+    /// explains any or two explain the same number.
+    ///
+    /// ```
+    /// use epochs::{guess_many, Epoch};
+    /// assert_eq!(guess_many(&[1234567890]).best(), None);
+    /// let report = guess_many(&[1_234_567_890_000, 1_700_000_000_000]);
+    /// assert_eq!(report.best(), Some(Epoch::Java));
+    /// ```
+    pub fn best(&self) -> Option<Epoch> {
+        match self.counts.as_slice() {
+            [(epoch, _)] => Some(*epoch),
+            [(epoch, first), (_, second), ..] if first > second => Some(*epoch),
+            _ => None,
+        }
+    }
+}
+
+/// Guess at a whole column of values at once, counting how many of
+/// them each epoch puts between [GUESS_FROM](constant.GUESS_FROM.html)
+/// and [GUESS_TO](constant.GUESS_TO.html). One value can be plausible
+/// in several epochs, but a column of them rarely is.
+///
+/// ```
+/// use epochs::{guess_many, Epoch};
+/// // Visit times from a Chrome history, with one stray value.
+/// let column = [
+///     12_879_041_490_000_000,
+///     13_100_000_000_000_000,
+///     13_250_000_000_000_000,
+///     -1_000_000_000_000,
+/// ];
+/// let report = guess_many(&column);
+/// assert_eq!(report.best(), Some(Epoch::Chrome));
+/// assert_eq!(report.counts[0], (Epoch::Chrome, 3));
+/// assert_eq!(report.unexplained, 1);
+/// ```
+pub fn guess_many(nums: &[i64]) -> GuessReport {
+    let mut counts = [0; Epoch::ALL.len()];
+    let mut unexplained = 0;
+    for &num in nums {
+        let mut explained = false;
+        for (count, epoch) in counts.iter_mut().zip(Epoch::ALL) {
+            if epoch
+                .to_datetime(num)
+                .is_some_and(|ndt| (GUESS_FROM..=GUESS_TO).contains(&ndt))
+            {
+                *count += 1;
+                explained = true;
+            }
+        }
+        if !explained {
+            unexplained += 1;
+        }
+    }
+    let mut counts: Vec<_> = Epoch::ALL
+        .into_iter()
+        .zip(counts)
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    counts.sort_by_key(|&(_, count)| Reverse(count));
+    GuessReport {
+        values: nums.len(),
+        unexplained,
+        counts,
+    }
+}
+
+/// Like [guess_many](fn.guess_many.html), but for when only one answer
+/// will do: the epoch which explains the most of the given values, or
+/// an error saying which epochs tie for it, if any explain them at all.
+///
+/// ```
+/// use epochs::{detect, Epoch, EpochError};
+/// let column = [1_234_567_890_000, 1_700_000_000_000];
+/// assert_eq!(detect(&column).unwrap(), Epoch::Java);
+/// let err = detect(&[128_790_414_900_000_000]).unwrap_err();
+/// assert!(matches!(err, EpochError::Ambiguous(epochs) if epochs.len() == 2));
+/// assert!(matches!(detect(&[-1_000_000_000_000]), Err(EpochError::Undetected)));
+/// ```
+pub fn detect(nums: &[i64]) -> Result<Epoch, EpochError> {
+    let report = guess_many(nums);
+    if let Some(epoch) = report.best() {
+        return Ok(epoch);
+    }
+    match report.counts.first() {
+        Some(&(_, most)) => Err(EpochError::Ambiguous(
+            report
+                .counts
+                .iter()
+                .filter(|&&(_, count)| count == most)
+                .map(|&(epoch, _)| epoch)
+                .collect(),
+        )),
+        None => Err(EpochError::Undetected),
+    }
+}
+
+/// What kind of number [guess_str](fn.guess_str.html) found in a
+/// string.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Detected {
+    /// An integer, in the given radix (2, 8, 10, or 16).
+    Integer { value: i64, radix: u32 },
+    /// A number with a decimal point or an exponent.
+    Float(f64),
+}
+
+/// A guess named by its conversion function.
+type Named = (&'static str, NaiveDateTime);
+
+/// A conversion from a float.
+type FromFloat = fn(f64) -> Option<NaiveDateTime>;
+
+/// The conversions from a float worth trying when guessing, by name.
+const FLOAT_GUESSES: [(&str, FromFloat); 4] = [
+    ("cocoa_f64", crate::cocoa_f64),
+    ("excel_1900", crate::excel_1900),
+    ("excel_1904", crate::excel_1904),
+    ("icq", crate::icq),
+];
+
+/// Like [guess](fn.guess.html), but for a number as copied and pasted,
+/// in any form [parse_value](fn.parse_value.html) understands, or as
+/// bare hex from a hex dump. Digits alone are taken to be decimal; with
+/// any of the letters a to f, hex.
+///
+/// Integers are guessed at in every [Epoch](enum.Epoch.html), each
+/// named as by [name](enum.Epoch.html#method.name). Floats are guessed
+/// at as the day counts of Excel and ICQ time and as Cocoa time with a
+/// fraction of a second (named, like the functions, `excel_1900`,
+/// `excel_1904`, `icq`, and `cocoa_f64`), as well as in every epoch if
+/// they have no fractional part.
+///
+/// ```
+/// use epochs::{guess_str, Detected};
+/// let (detected, guesses) = guess_str("1c98e333296f500").unwrap();
+/// assert_eq!(detected, Detected::Integer { value: 0x1c98e333296f500, radix: 16 });
+/// assert!(guesses.iter().any(|&(name, _)| name == "windows_file"));
+///
+/// let (detected, guesses) = guess_str("39857.980208333334").unwrap();
+/// assert_eq!(detected, Detected::Float(39857.980208333334));
+/// let (name, ndt) = guesses[1];
+/// assert_eq!((name, ndt.to_string().as_str()), ("excel_1900", "2009-02-13 23:31:30"));
+///
+/// assert!(guess_str("yesterday").is_err());
+/// ```
+pub fn guess_str(s: &str) -> Result<(Detected, Vec<Named>), ParseValueError> {
+    let detected = detect_number(s)?;
+    let in_window = |ndt: &NaiveDateTime| (GUESS_FROM..=GUESS_TO).contains(ndt);
+    let mut guesses = Vec::new();
+    let integer = match detected {
+        Detected::Integer { value, .. } => Some(value),
+        Detected::Float(x) => {
+            guesses.extend(
+                FLOAT_GUESSES
+                    .iter()
+                    .filter_map(|&(name, f)| Some((name, f(x)?)))
+                    .filter(|(_, ndt)| in_window(ndt)),
+            );
+            Value::Float(x).as_i64()
+        }
+    };
+    if let Some(num) = integer {
+        guesses.extend(
+            guess(num)
+                .into_iter()
+                .map(|(epoch, ndt)| (epoch.name(), ndt)),
+        );
+    }
+    Ok((detected, guesses))
+}
+
+/// Work out what kind of number the given string holds.
+fn detect_number(s: &str) -> Result<Detected, ParseValueError> {
+    match parse_value(s) {
+        Ok(Value::Int(value)) => {
+            let digits = s.trim().trim_start_matches(['-', '+']);
+            let radix = match digits.get(..2) {
+                Some("0x" | "0X") => 16,
+                Some("0o" | "0O") => 8,
+                Some("0b" | "0B") => 2,
+                _ => 10,
+            };
+            Ok(Detected::Integer { value, radix })
+        }
+        Ok(Value::Float(x)) => Ok(Detected::Float(x)),
+        Err(e) => {
+            // Letters which are hex digits, and not just an exponent.
+            let bare = s.trim().trim_start_matches(['-', '+']);
+            if bare.chars().any(|c| c.is_ascii_alphabetic()) {
+                if let Ok(value) = parse_hex(s) {
+                    return Ok(Detected::Integer { value, radix: 16 });
+                }
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Interpret the given number under every supported epoch, however
+/// implausible the result. Epochs under which the number cannot be
+/// converted at all are included with None.
+///
+/// ```
+/// use epochs::{view_all, Epoch};
+/// let views = view_all(1234567890);
+/// assert_eq!(views.len(), Epoch::ALL.len());
+/// assert!(views.contains(&(Epoch::Unix, epochs::unix(1234567890))));
+/// ```
+pub fn view_all(num: i64) -> Vec<(Epoch, Option<NaiveDateTime>)> {
+    Epoch::ALL
+        .iter()
+        .map(|&epoch| (epoch, epoch.to_datetime(num)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn guess_filetime() {
+        let guesses = guess(128790414900000000);
+        let epochs: Vec<_> = guesses.iter().map(|(epoch, _)| *epoch).collect();
+        assert_eq!(epochs, [Epoch::UuidV1, Epoch::WindowsFile]);
+    }
+    #[test]
+    fn guess_nothing() {
+        assert!(guess(-1_000_000_000_000).is_empty());
+    }
+    #[test]
+    fn guess_in_range_inclusive() {
+        let ndt = crate::unix(1234567890).unwrap();
+        let guesses = guess_in_range(1234567890, ndt, ndt);
+        assert_eq!(guesses, [(Epoch::Unix, ndt)]);
+    }
+    #[test]
+    fn guess_in_range_backwards() {
+        assert!(guess_in_range(1234567890, GUESS_TO, GUESS_FROM).is_empty());
+    }
+    #[test]
+    fn ranked_by_digits() {
+        // Unix and Google Calendar times are both ten digits now, but
+        // the Google Calendar time of this is in 2010. TAI is only
+        // seconds away, so it ties with Unix.
+        let now = crate::unix(1_234_567_890).unwrap();
+        let ranked = guess_ranked_at(1_234_567_890, now);
+        let score = |epoch| ranked.iter().find(|r| r.epoch == epoch).unwrap().score;
+        assert_eq!(score(Epoch::Unix), 1.0);
+        assert_eq!(score(Epoch::Tai), 1.0);
+        assert!(score(Epoch::GoogleCalendar) < 1.0);
+    }
+    #[test]
+    fn ranked_fine_units() {
+        // A whole second of Chrome time, also a plausible Mozilla time
+        // in 2378 if the window allowed it, but not APFS nanoseconds.
+        let now = crate::unix(1_234_567_890).unwrap();
+        let ranked = guess_ranked_at(12_879_041_490_000_000, now);
+        assert_eq!(ranked[0].epoch, Epoch::Chrome);
+        assert_eq!(ranked[0].score, 1.0);
+        // Java time with milliseconds isn't round.
+        let ranked = guess_ranked_at(1_234_567_890_123, now);
+        assert_eq!(ranked[0].epoch, Epoch::Java);
+        assert_eq!(ranked[0].score, 0.75);
+    }
+    #[test]
+    fn ranked_scores_in_range() {
+        let now = crate::unix(1_234_567_890).unwrap();
+        for num in [1, 1_234_567_890, 128_790_414_906_543_210, -1, i64::MAX] {
+            for guess in guess_ranked_at(num, now) {
+                assert!(guess.score > 0.0 && guess.score <= 1.0, "{guess:?}");
+            }
+        }
+        assert!(guess_ranked_at(-1_000_000_000_000, now).is_empty());
+    }
+    #[test]
+    fn guess_many_column() {
+        // FILETIMEs from 1995 to 2035, some of which are also UUID
+        // times after 1990.
+        let column: Vec<i64> = (0..100)
+            .map(|i| 124_370_000_000_000_000 + i * 126_240_000_000_000)
+            .collect();
+        let report = guess_many(&column);
+        assert_eq!(report.values, 100);
+        assert_eq!(report.best(), Some(Epoch::WindowsFile));
+        let uuid = report
+            .counts
+            .iter()
+            .find(|(e, _)| *e == Epoch::UuidV1)
+            .unwrap();
+        assert!(uuid.1 < report.counts[0].1);
+    }
+    #[test]
+    fn guess_many_empty() {
+        let report = guess_many(&[]);
+        assert_eq!(report, GuessReport::default());
+        assert_eq!(report.best(), None);
+        let report = guess_many(&[-1_000_000_000_000]);
+        assert_eq!(report.unexplained, 1);
+        assert!(report.counts.is_empty());
+    }
+    #[test]
+    fn guess_many_tie() {
+        let report = guess_many(&[128_790_414_900_000_000]);
+        assert_eq!(report.counts, [(Epoch::UuidV1, 1), (Epoch::WindowsFile, 1)]);
+        assert_eq!(report.best(), None);
+    }
+    #[test]
+    fn detect_ties() {
+        let column = [1_234_567_890_000_000, 1_300_000_000_000_000];
+        assert_eq!(detect(&column).unwrap(), Epoch::Mozilla);
+        match detect(&[1_234_567_890, 1_300_000_000]) {
+            Err(EpochError::Ambiguous(epochs)) => {
+                assert!(epochs.contains(&Epoch::Unix), "{epochs:?}");
+                assert!(!epochs.contains(&Epoch::Java), "{epochs:?}");
+            }
+            other => panic!("{other:?}"),
+        }
+        assert!(matches!(detect(&[]), Err(EpochError::Undetected)));
+    }
+    #[test]
+    fn guess_str_decimal() {
+        let (detected, guesses) = guess_str(" 1,234,567,890 ").unwrap();
+        assert_eq!(
+            detected,
+            Detected::Integer {
+                value: 1_234_567_890,
+                radix: 10
+            }
+        );
+        let names: Vec<_> = guesses.iter().map(|&(name, _)| name).collect();
+        assert_eq!(
+            names,
+            [
+                "cdma",
+                "garmin_fit",
+                "google_calendar",
+                "tai",
+                "unix",
+                "unix_right",
+                "zigbee"
+            ]
+        );
+    }
+    #[test]
+    fn guess_str_hex() {
+        let (detected, _) = guess_str("0x499602d2").unwrap();
+        assert_eq!(
+            detected,
+            Detected::Integer {
+                value: 1_234_567_890,
+                radix: 16
+            }
+        );
+        // Bare digits are decimal, even though they could be hex.
+        let (detected, _) = guess_str("12345678").unwrap();
+        assert_eq!(
+            detected,
+            Detected::Integer {
+                value: 12_345_678,
+                radix: 10
+            }
+        );
+        // An e makes this a float, not hex.
+        let (detected, _) = guess_str("1e9").unwrap();
+        assert_eq!(detected, Detected::Float(1e9));
+        let (detected, _) = guess_str("1ea4f7dca4892ce").unwrap();
+        assert_eq!(
+            detected,
+            Detected::Integer {
+                value: 0x1ea4f7dca4892ce,
+                radix: 16
+            }
+        );
+    }
+    #[test]
+    fn guess_str_float() {
+        let (_, guesses) = guess_str("256260690.123456").unwrap();
+        assert_eq!(guesses.len(), 1);
+        assert_eq!(guesses[0].0, "cocoa_f64");
+        assert_eq!(guesses[0].1.to_string(), "2009-02-13 23:31:30.123456");
+        // Whole floats are tried as integers too.
+        let (_, guesses) = guess_str("1.23456789e9").unwrap();
+        assert!(guesses.iter().any(|&(name, _)| name == "unix"));
+    }
+    #[test]
+    fn guess_str_garbage() {
+        assert!(guess_str("").is_err());
+        assert!(guess_str("0xfg").is_err());
+        assert_eq!(guess_str("12 monkeys").unwrap_err().input(), "12 monkeys");
+    }
+
+    #[test]
+    fn view_all_order() {
+        let epochs: Vec<_> = view_all(0).into_iter().map(|(epoch, _)| epoch).collect();
+        assert_eq!(epochs, Epoch::ALL);
+    }
+    #[test]
+    fn view_all_chrome() {
+        let views = view_all(12879041490000000);
+        let (_, ndt) = views.iter().find(|(e, _)| *e == Epoch::Chrome).unwrap();
+        assert_eq!(ndt.unwrap().to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn view_all_unconvertible() {
+        let views = view_all(i64::MAX);
+        assert!(views.contains(&(Epoch::Unix, None)));
+        assert!(views.iter().any(|(_, ndt)| ndt.is_some()));
+    }
+}
@@ -0,0 +1,84 @@
+//! MUMPS `$HOROLOG` times.
+
+use chrono::{DateTime, NaiveDateTime};
+
+/// Day zero of `$HOROLOG`, 1840-12-31, is 47,117 days before the Unix
+/// epoch.
+const HOROLOG_DAYS: i64 = 47_117;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// MUMPS (*e.g.*, InterSystems Caché, VistA) `$HOROLOG` time is a
+/// pair of numbers: the days since 1840-12-31 and the seconds since
+/// midnight. Returns None if the seconds are not within a day.
+///
+/// ```
+/// use epochs::horolog;
+/// let ndt = horolog(61405, 84690).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn horolog(days: i64, seconds: i64) -> Option<NaiveDateTime> {
+    if !(0..SECONDS_PER_DAY).contains(&seconds) {
+        return None;
+    }
+    let t = days
+        .checked_sub(HOROLOG_DAYS)?
+        .checked_mul(SECONDS_PER_DAY)?
+        .checked_add(seconds)?;
+    DateTime::from_timestamp(t, 0).map(|dt| dt.naive_utc())
+}
+
+/// Convert the given NaiveDateTime to a [`$HOROLOG`](fn.horolog.html)
+/// time, returning the days and the seconds. Fractions of a second
+/// are dropped.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_horolog;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_horolog(ndt), (61405, 84690));
+/// ```
+pub fn to_horolog(ndt: NaiveDateTime) -> (i64, i64) {
+    let t = ndt.and_utc().timestamp();
+    (
+        t.div_euclid(SECONDS_PER_DAY) + HOROLOG_DAYS,
+        t.rem_euclid(SECONDS_PER_DAY),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn horolog_day_zero() {
+        let ndt = horolog(0, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1840-12-31 00:00:00");
+    }
+    #[test]
+    fn horolog_unix_epoch() {
+        let ndt = horolog(47117, 1).unwrap();
+        assert_eq!(ndt.to_string(), "1970-01-01 00:00:01");
+    }
+    #[test]
+    fn horolog_bad_seconds() {
+        assert!(horolog(61405, -1).is_none());
+        assert!(horolog(61405, 86400).is_none());
+    }
+    #[test]
+    fn horolog_too_big() {
+        assert!(horolog(i64::MAX, 0).is_none());
+        assert!(horolog(i64::MIN, 0).is_none());
+    }
+    #[test]
+    fn to_horolog_before_1970() {
+        let ndt = NaiveDate::from_ymd_opt(1841, 1, 1)
+            .unwrap()
+            .and_hms_milli_opt(0, 0, 1, 500)
+            .unwrap();
+        assert_eq!(to_horolog(ndt), (1, 1));
+    }
+}
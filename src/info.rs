@@ -0,0 +1,754 @@
+//! Descriptions of the supported epochs, for tools that need to list
+//! them.
+
+use chrono::NaiveDate;
+
+use crate::Epoch;
+
+/// A description of one of the supported epochs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EpochInfo {
+    /// The name of the conversion function (*e.g.*, "chrome").
+    pub name: &'static str,
+    /// A one-line description of the epoch.
+    pub description: &'static str,
+    /// The unit being counted (*e.g.*, "microseconds").
+    pub resolution: &'static str,
+    /// The date the count starts from or, for formats which store
+    /// calendar fields, the earliest date they hold.
+    pub origin: NaiveDate,
+    /// The number of decimal digits in a present-day value, or 0 for
+    /// formats which are not a single number.
+    pub digits: usize,
+    /// Whether negative values (times before the origin) are used.
+    pub signed: bool,
+}
+
+const fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+static ALL_EPOCHS: [EpochInfo; 70] = [
+    EpochInfo {
+        name: "amiga",
+        description: "AmigaOS DateStamp, days since 1978-01-01, minutes, and ticks",
+        resolution: "fiftieths of a second",
+        origin: date(1978, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "apfs",
+        description: "APFS time, nanoseconds since the Unix epoch",
+        resolution: "nanoseconds",
+        origin: date(1970, 1, 1),
+        digits: 19,
+        signed: true,
+    },
+    EpochInfo {
+        name: "as400_dts",
+        description: "IBM i standard time format, laid out like the TOD clock, from 1928-08-23",
+        resolution: "microseconds",
+        origin: date(1928, 8, 23),
+        digits: 20,
+        signed: false,
+    },
+    EpochInfo {
+        name: "bcd_rtc",
+        description: "Real-time clock registers, packed-BCD calendar fields",
+        resolution: "seconds",
+        origin: date(2000, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "beidou_t",
+        description: "BeiDou Time, weeks since 2006-01-01 and seconds of the week",
+        resolution: "seconds",
+        origin: date(2006, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "besselian_year",
+        description: "Besselian years of 365.242198781 days, from B1900.0",
+        resolution: "years",
+        origin: date(1899, 12, 31),
+        digits: 4,
+        signed: false,
+    },
+    EpochInfo {
+        name: "bplist_date",
+        description: "Apple binary property list date, a big-endian double of Cocoa time",
+        resolution: "seconds",
+        origin: date(2001, 1, 1),
+        digits: 0,
+        signed: true,
+    },
+    EpochInfo {
+        name: "cdma",
+        description: "CDMA system time, seconds since 1980-01-06 without leap seconds",
+        resolution: "seconds",
+        origin: date(1980, 1, 6),
+        digits: 9,
+        signed: false,
+    },
+    EpochInfo {
+        name: "cdma_frames",
+        description: "CDMA system time, 1.25 ms frames since 1980-01-06 without leap seconds",
+        resolution: "1.25 milliseconds",
+        origin: date(1980, 1, 6),
+        digits: 12,
+        signed: false,
+    },
+    EpochInfo {
+        name: "chrome",
+        description: "Chrome time, microseconds since 1601-01-01",
+        resolution: "microseconds",
+        origin: date(1601, 1, 1),
+        digits: 17,
+        signed: false,
+    },
+    EpochInfo {
+        name: "cics",
+        description: "CICS ABSTIME, milliseconds since 1900-01-01",
+        resolution: "milliseconds",
+        origin: date(1900, 1, 1),
+        digits: 13,
+        signed: false,
+    },
+    EpochInfo {
+        name: "cocoa",
+        description: "Cocoa time, seconds since 2001-01-01",
+        resolution: "seconds",
+        origin: date(2001, 1, 1),
+        digits: 9,
+        signed: true,
+    },
+    EpochInfo {
+        name: "cocoa_f64",
+        description: "Cocoa time as a double, fractional seconds since 2001-01-01",
+        resolution: "seconds",
+        origin: date(2001, 1, 1),
+        digits: 9,
+        signed: true,
+    },
+    EpochInfo {
+        name: "dotnet_binary",
+        description: ".NET DateTime.ToBinary, a Kind and ticks since 0001-01-01",
+        resolution: "hectonanoseconds",
+        origin: date(1, 1, 1),
+        digits: 19,
+        signed: false,
+    },
+    EpochInfo {
+        name: "excel_1900",
+        description: "Excel 1900 date system, fractional days since 1899-12-31 (with 1900-02-29)",
+        resolution: "days",
+        origin: date(1899, 12, 31),
+        digits: 5,
+        signed: false,
+    },
+    EpochInfo {
+        name: "excel_1900_date",
+        description: "Excel 1900 date system, whole days since 1899-12-31 (with 1900-02-29)",
+        resolution: "days",
+        origin: date(1899, 12, 31),
+        digits: 5,
+        signed: false,
+    },
+    EpochInfo {
+        name: "excel_1904",
+        description: "Excel 1904 date system, fractional days since 1904-01-01",
+        resolution: "days",
+        origin: date(1904, 1, 1),
+        digits: 5,
+        signed: false,
+    },
+    EpochInfo {
+        name: "excel_1904_date",
+        description: "Excel 1904 date system, whole days since 1904-01-01",
+        resolution: "days",
+        origin: date(1904, 1, 1),
+        digits: 5,
+        signed: false,
+    },
+    EpochInfo {
+        name: "exfat",
+        description: "exFAT timestamp, FAT date and time with 10 ms and a UTC offset",
+        resolution: "10 milliseconds",
+        origin: date(1980, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "ext4",
+        description: "ext4 inode time, Unix seconds and an extra field with nanoseconds",
+        resolution: "nanoseconds",
+        origin: date(1970, 1, 1),
+        digits: 0,
+        signed: true,
+    },
+    EpochInfo {
+        name: "fat",
+        description: "FAT (MS-DOS) date and time words, local time",
+        resolution: "two seconds",
+        origin: date(1980, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "filemaker",
+        description: "FileMaker time, seconds since 0001-01-01",
+        resolution: "seconds",
+        origin: date(1, 1, 1),
+        digits: 11,
+        signed: false,
+    },
+    EpochInfo {
+        name: "galileo_st",
+        description: "Galileo System Time, weeks since 1999-08-22 and time of week",
+        resolution: "seconds",
+        origin: date(1999, 8, 22),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "garmin_fit",
+        description: "Garmin FIT time, seconds since 1989-12-31",
+        resolution: "seconds",
+        origin: date(1989, 12, 31),
+        digits: 9,
+        signed: false,
+    },
+    EpochInfo {
+        name: "glonass",
+        description: "GLONASS time, four-year intervals since 1996, day, and seconds, Moscow time",
+        resolution: "seconds",
+        origin: date(1996, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "go_binary",
+        description: "Go time.Time.MarshalBinary, seconds since 0001-01-01, nanoseconds, and zone",
+        resolution: "nanoseconds",
+        origin: date(1, 1, 1),
+        digits: 0,
+        signed: true,
+    },
+    EpochInfo {
+        name: "google_calendar",
+        description: "Google Calendar time, seconds in 32-day months since 1969-12-31",
+        resolution: "seconds",
+        origin: date(1969, 12, 31),
+        digits: 10,
+        signed: false,
+    },
+    EpochInfo {
+        name: "gps_week_tow",
+        description: "GPS time, weeks since 1980-01-06 and time of week",
+        resolution: "seconds",
+        origin: date(1980, 1, 6),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "gsm_scts",
+        description: "SMS service centre time stamp, swapped-BCD calendar fields from 2000",
+        resolution: "seconds",
+        origin: date(2000, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "horolog",
+        description: "MUMPS $HOROLOG, days since 1840-12-31 and seconds since midnight",
+        resolution: "seconds",
+        origin: date(1840, 12, 31),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "icq",
+        description: "ICQ time, fractional days since 1899-12-30",
+        resolution: "days",
+        origin: date(1899, 12, 30),
+        digits: 5,
+        signed: true,
+    },
+    EpochInfo {
+        name: "iso9660_dec_datetime",
+        description: "ISO 9660 volume descriptor date, ASCII digits and a GMT offset",
+        resolution: "hundredths of a second",
+        origin: date(1, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "iso9660_dir_datetime",
+        description: "ISO 9660 directory record date, years since 1900 and a GMT offset",
+        resolution: "seconds",
+        origin: date(1900, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "j2000_seconds",
+        description: "SI seconds since J2000.0 (noon TT)",
+        resolution: "seconds",
+        origin: date(2000, 1, 1),
+        digits: 9,
+        signed: true,
+    },
+    EpochInfo {
+        name: "java",
+        description: "Java time, milliseconds since the Unix epoch",
+        resolution: "milliseconds",
+        origin: date(1970, 1, 1),
+        digits: 13,
+        signed: true,
+    },
+    EpochInfo {
+        name: "java_instant",
+        description: "Java Instant, Unix seconds and nanoseconds",
+        resolution: "nanoseconds",
+        origin: date(1970, 1, 1),
+        digits: 0,
+        signed: true,
+    },
+    EpochInfo {
+        name: "julian_epoch_year",
+        description: "Julian epoch years of 365.25 days, from J2000.0",
+        resolution: "years",
+        origin: date(2000, 1, 1),
+        digits: 4,
+        signed: false,
+    },
+    EpochInfo {
+        name: "labview",
+        description: "LabVIEW time, seconds since 1904-01-01 and a 64-bit binary fraction",
+        resolution: "seconds",
+        origin: date(1904, 1, 1),
+        digits: 0,
+        signed: true,
+    },
+    EpochInfo {
+        name: "mjd_days",
+        description: "Modified Julian Day, whole days since 1858-11-17",
+        resolution: "days",
+        origin: date(1858, 11, 17),
+        digits: 5,
+        signed: true,
+    },
+    EpochInfo {
+        name: "mozilla",
+        description: "Mozilla time, microseconds since the Unix epoch",
+        resolution: "microseconds",
+        origin: date(1970, 1, 1),
+        digits: 16,
+        signed: true,
+    },
+    EpochInfo {
+        name: "mssql_datetime",
+        description: "SQL Server datetime, days since 1900-01-01 and 1/300 second ticks",
+        resolution: "1/300 of a second",
+        origin: date(1900, 1, 1),
+        digits: 0,
+        signed: true,
+    },
+    EpochInfo {
+        name: "mssql_datetime2",
+        description: "SQL Server datetime2, time of day and days since 0001-01-01",
+        resolution: "hectonanoseconds",
+        origin: date(1, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "mssql_datetimeoffset",
+        description: "SQL Server datetimeoffset, a UTC datetime2 and an offset",
+        resolution: "hectonanoseconds",
+        origin: date(1, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "mssql_smalldatetime",
+        description: "SQL Server smalldatetime, days since 1900-01-01 and minutes",
+        resolution: "minutes",
+        origin: date(1900, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "ntp",
+        description: "NTP timestamp, 32.32 fixed-point seconds since 1900-01-01",
+        resolution: "seconds",
+        origin: date(1900, 1, 1),
+        digits: 20,
+        signed: false,
+    },
+    EpochInfo {
+        name: "ntp_date",
+        description: "NTP date format, era, seconds, and a 64-bit fraction from 1900-01-01",
+        resolution: "seconds",
+        origin: date(1900, 1, 1),
+        digits: 29,
+        signed: false,
+    },
+    EpochInfo {
+        name: "oracle_date",
+        description: "Oracle DATE, excess-100 calendar fields",
+        resolution: "seconds",
+        origin: date(-4711, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "oracle_timestamp",
+        description: "Oracle TIMESTAMP, a DATE and nanoseconds",
+        resolution: "nanoseconds",
+        origin: date(-4711, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "palm",
+        description: "Palm OS time, seconds since 1904-01-01",
+        resolution: "seconds",
+        origin: date(1904, 1, 1),
+        digits: 10,
+        signed: false,
+    },
+    EpochInfo {
+        name: "parquet_int96",
+        description: "Parquet INT96, nanoseconds of the day and a Julian day number",
+        resolution: "nanoseconds",
+        origin: date(-4713, 11, 24),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "ptp",
+        description: "PTP time, seconds and nanoseconds since 1970-01-01 TAI",
+        resolution: "nanoseconds",
+        origin: date(1970, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "quicktime",
+        description: "QuickTime/MP4 time, seconds since 1904-01-01",
+        resolution: "seconds",
+        origin: date(1904, 1, 1),
+        digits: 10,
+        signed: false,
+    },
+    EpochInfo {
+        name: "rata_die",
+        description: "Rata Die, whole days since 0000-12-31 (0001-01-01 is day 1)",
+        resolution: "days",
+        origin: date(0, 12, 31),
+        digits: 6,
+        signed: true,
+    },
+    EpochInfo {
+        name: "sas_date",
+        description: "SAS date, whole days since 1960-01-01",
+        resolution: "days",
+        origin: date(1960, 1, 1),
+        digits: 5,
+        signed: true,
+    },
+    EpochInfo {
+        name: "stata_date",
+        description: "Stata %td date, whole days since 1960-01-01",
+        resolution: "days",
+        origin: date(1960, 1, 1),
+        digits: 5,
+        signed: true,
+    },
+    EpochInfo {
+        name: "symbian",
+        description: "Symbian time, microseconds since the year 0",
+        resolution: "microseconds",
+        origin: date(0, 1, 1),
+        digits: 17,
+        signed: true,
+    },
+    EpochInfo {
+        name: "systemtime_from_le_bytes",
+        description: "Windows SYSTEMTIME, 16-bit calendar fields",
+        resolution: "milliseconds",
+        origin: date(1601, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "tai",
+        description: "TAI, seconds since 1970-01-01 TAI, counting leap seconds",
+        resolution: "seconds",
+        origin: date(1970, 1, 1),
+        digits: 10,
+        signed: true,
+    },
+    EpochInfo {
+        name: "tandem",
+        description: "HP NonStop JULIANTIMESTAMP, microseconds since Julian day zero (noon)",
+        resolution: "microseconds",
+        origin: date(-4713, 11, 24),
+        digits: 18,
+        signed: false,
+    },
+    EpochInfo {
+        name: "tod_clock",
+        description: "z/Architecture TOD clock, bit 51 a microsecond from 1900-01-01",
+        resolution: "microseconds",
+        origin: date(1900, 1, 1),
+        digits: 20,
+        signed: false,
+    },
+    EpochInfo {
+        name: "tod_clock_extended",
+        description: "z/Architecture extended TOD clock, with an epoch index",
+        resolution: "microseconds",
+        origin: date(1900, 1, 1),
+        digits: 37,
+        signed: false,
+    },
+    EpochInfo {
+        name: "unix",
+        description: "Unix time, seconds since 1970-01-01",
+        resolution: "seconds",
+        origin: date(1970, 1, 1),
+        digits: 10,
+        signed: true,
+    },
+    EpochInfo {
+        name: "unix_i32",
+        description: "32-bit signed Unix time, seconds since 1970-01-01",
+        resolution: "seconds",
+        origin: date(1970, 1, 1),
+        digits: 10,
+        signed: true,
+    },
+    EpochInfo {
+        name: "unix_right",
+        description: "Unix time counting leap seconds, as with the right/ zoneinfo files",
+        resolution: "seconds",
+        origin: date(1970, 1, 1),
+        digits: 10,
+        signed: true,
+    },
+    EpochInfo {
+        name: "unix_u32",
+        description: "32-bit unsigned Unix time, seconds since 1970-01-01",
+        resolution: "seconds",
+        origin: date(1970, 1, 1),
+        digits: 10,
+        signed: false,
+    },
+    EpochInfo {
+        name: "uuid_v1",
+        description: "UUID version 1 time, hectonanoseconds since 1582-10-15",
+        resolution: "hectonanoseconds",
+        origin: date(1582, 10, 15),
+        digits: 18,
+        signed: false,
+    },
+    EpochInfo {
+        name: "windows_date",
+        description: "Windows date time, hectonanoseconds since 0001-01-01",
+        resolution: "hectonanoseconds",
+        origin: date(1, 1, 1),
+        digits: 18,
+        signed: false,
+    },
+    EpochInfo {
+        name: "windows_file",
+        description: "Windows file time, hectonanoseconds since 1601-01-01",
+        resolution: "hectonanoseconds",
+        origin: date(1601, 1, 1),
+        digits: 18,
+        signed: false,
+    },
+    EpochInfo {
+        name: "windows_file_parts",
+        description: "Windows FILETIME structure, low and high halves",
+        resolution: "hectonanoseconds",
+        origin: date(1601, 1, 1),
+        digits: 0,
+        signed: false,
+    },
+    EpochInfo {
+        name: "zigbee",
+        description: "ZigBee UTCTime, seconds since 2000-01-01",
+        resolution: "seconds",
+        origin: date(2000, 1, 1),
+        digits: 9,
+        signed: false,
+    },
+];
+
+/// Descriptions of all the supported epochs, in alphabetical order.
+/// Each is named for the function which converts from it; variations
+/// on the same format (*e.g.*, [ptp_utc](fn.ptp_utc.html) or
+/// [fat_local](fn.fat_local.html)) are not listed separately.
+///
+/// ```
+/// let names: Vec<_> = epochs::all_epochs().iter().map(|info| info.name).collect();
+/// assert!(names.contains(&"chrome"));
+/// assert!(names.contains(&"icq"));
+/// ```
+pub fn all_epochs() -> &'static [EpochInfo] {
+    &ALL_EPOCHS
+}
+
+impl Epoch {
+    /// The description of this epoch.
+    ///
+    /// ```
+    /// use epochs::Epoch;
+    /// let info = Epoch::WindowsFile.info();
+    /// assert_eq!(info.resolution, "hectonanoseconds");
+    /// assert_eq!(info.origin.to_string(), "1601-01-01");
+    /// ```
+    pub fn info(self) -> &'static EpochInfo {
+        ALL_EPOCHS
+            .iter()
+            .find(|info| info.name == self.name())
+            .expect("every Epoch has an EpochInfo")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn sorted_by_name() {
+        let names: Vec<_> = all_epochs().iter().map(|info| info.name).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+    #[test]
+    fn origin_is_zero() {
+        let midnight =
+            |info: &EpochInfo| -> NaiveDateTime { info.origin.and_hms_opt(0, 0, 0).unwrap() };
+        for epoch in Epoch::ALL {
+            let info = epoch.info();
+            // Julian day zero starts at noon, and at the Unix epoch,
+            // TAI was already ten seconds ahead of UTC.
+            if epoch == Epoch::Tandem {
+                assert_eq!(epoch.from_datetime(midnight(info)), -43_200_000_000);
+            } else if epoch == Epoch::Tai {
+                assert_eq!(epoch.from_datetime(midnight(info)), 10);
+            } else if epoch != Epoch::GoogleCalendar {
+                assert_eq!(epoch.from_datetime(midnight(info)), 0, "{}", info.name);
+            }
+        }
+    }
+    /// Convert the earliest (or zero) value of the format with the
+    /// given name, so that a name with no conversion function fails.
+    fn at_origin(name: &str) -> Option<NaiveDateTime> {
+        match name {
+            "amiga" => crate::amiga(0, 0, 0),
+            "as400_dts" => crate::as400_dts(0),
+            "bcd_rtc" => crate::bcd_rtc(&[0, 0, 0, 1, 1, 0]),
+            "beidou_t" => crate::beidou_t(0, 0.0),
+            "besselian_year" => crate::besselian_year(1900.0),
+            "bplist_date" => crate::bplist_date(&[0; 8]),
+            "cocoa_f64" => crate::cocoa_f64(0.0),
+            "dotnet_binary" => crate::dotnet_binary(0).map(|(ndt, _)| ndt),
+            "excel_1900" => crate::excel_1900(0.0),
+            "excel_1904" => crate::excel_1904(0.0),
+            "exfat" => crate::exfat(0x0021_0000, 0, 0).map(|(ndt, _)| ndt),
+            "ext4" => crate::ext4(0, 0),
+            "fat" => crate::fat(0x0021, 0, None),
+            "galileo_st" => crate::galileo_st(0, 0.0),
+            "glonass" => crate::glonass(1, 1, 0.0),
+            "go_binary" => {
+                let mut bytes = [0; 15];
+                bytes[0] = 1;
+                bytes[13..].copy_from_slice(&(-1i16).to_be_bytes());
+                crate::go_binary(&bytes).map(|(ndt, _)| ndt)
+            }
+            "google_calendar" => {
+                crate::google_calendar(crate::to_google_calendar(NaiveDateTime::default()))
+            }
+            "gps_week_tow" => crate::gps_week_tow(0, 0.0),
+            "gsm_scts" => crate::gsm_scts(&[0x00, 0x10, 0x10, 0, 0, 0, 0]).map(|(ndt, _)| ndt),
+            "horolog" => crate::horolog(0, 0),
+            "icq" => crate::icq(0.0),
+            "iso9660_dec_datetime" => {
+                let mut bytes = [b'0'; 17];
+                bytes[..8].copy_from_slice(b"00010101");
+                bytes[16] = 0;
+                crate::iso9660_dec_datetime(&bytes).map(|(ndt, _)| ndt)
+            }
+            "iso9660_dir_datetime" => {
+                crate::iso9660_dir_datetime(&[0, 1, 1, 0, 0, 0, 0]).map(|(ndt, _)| ndt)
+            }
+            "j2000_seconds" => crate::j2000_seconds(0.0),
+            "java_instant" => crate::java_instant(0, 0),
+            "julian_epoch_year" => crate::julian_epoch_year(2000.0),
+            "labview" => crate::labview(0, 0),
+            "mssql_datetime" => crate::mssql_datetime(0, 0),
+            "mssql_datetime2" => crate::mssql_datetime2(&[0; 8], 7),
+            "mssql_datetimeoffset" => crate::mssql_datetimeoffset(&[0; 10], 7).map(|(ndt, _)| ndt),
+            "mssql_smalldatetime" => crate::mssql_smalldatetime(0, 0),
+            "ntp" => crate::ntp(0),
+            "ntp_date" => crate::ntp_date(0),
+            "oracle_date" => crate::oracle_date(&[53, 88, 1, 1, 1, 1, 1]),
+            "oracle_timestamp" => crate::oracle_timestamp(&[53, 88, 1, 1, 1, 1, 1, 0, 0, 0, 0]),
+            "parquet_int96" => crate::parquet_int96(&[0; 12]),
+            "ptp" => crate::ptp(0, 0),
+            "systemtime_from_le_bytes" => {
+                let mut bytes = [0; 16];
+                bytes[..2].copy_from_slice(&1601u16.to_le_bytes());
+                bytes[2] = 1;
+                bytes[6] = 1;
+                crate::systemtime_from_le_bytes(&bytes)
+            }
+            "tod_clock" => crate::tod_clock(0),
+            "tod_clock_extended" => crate::tod_clock_extended(0),
+            "unix_i32" => crate::unix_i32(0),
+            "unix_u32" => crate::unix_u32(0),
+            "windows_file_parts" => crate::windows_file_parts(0, 0),
+            _ => {
+                let epoch: Epoch = name.parse().expect("a conversion function");
+                epoch.to_datetime(0)
+            }
+        }
+    }
+    #[test]
+    fn names_are_converters() {
+        for info in all_epochs() {
+            let ndt = at_origin(info.name).unwrap_or_else(|| panic!("{}", info.name));
+            let origin = info.origin.and_hms_opt(0, 0, 0).unwrap();
+            // Within a day, for those which start at noon or in
+            // another time zone.
+            assert!(
+                (ndt - origin).num_hours().abs() <= 24,
+                "{} {ndt}",
+                info.name
+            );
+        }
+    }
+    #[test]
+    fn digits_are_typical() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        for epoch in Epoch::ALL {
+            let info = epoch.info();
+            let num = epoch.from_datetime(ndt);
+            assert_eq!(num.to_string().len(), info.digits, "{}", info.name);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! ISO 9660 (CD-ROM file system) dates.
+
+use chrono::{Datelike, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+
+/// Offsets from GMT are stored in 15-minute intervals, from -48 (west)
+/// to +52 (east).
+const QUARTER_HOURS: std::ops::RangeInclusive<i8> = -48..=52;
+
+fn offset_from_quarters(quarters: i8) -> Option<FixedOffset> {
+    if !QUARTER_HOURS.contains(&quarters) {
+        return None;
+    }
+    FixedOffset::east_opt(i32::from(quarters) * 15 * 60)
+}
+
+fn offset_to_quarters(offset: FixedOffset) -> Option<i8> {
+    let seconds = offset.local_minus_utc();
+    if seconds % (15 * 60) != 0 {
+        return None;
+    }
+    let quarters = i8::try_from(seconds / (15 * 60)).ok()?;
+    QUARTER_HOURS.contains(&quarters).then_some(quarters)
+}
+
+/// Parse a run of ASCII digits.
+fn digits(bytes: &[u8]) -> Option<u32> {
+    bytes.iter().try_fold(0, |n, &b| {
+        b.is_ascii_digit().then(|| n * 10 + u32::from(b - b'0'))
+    })
+}
+
+/// The dates in an ISO 9660 volume descriptor (creation, modification,
+/// expiration, and effective dates) are 17 bytes: 16 ASCII digits
+/// giving the year, month, day, hour, minute, second, and hundredths
+/// of a second, then the offset from GMT as a signed byte counting
+/// 15-minute intervals. Returns the local time as written, along with
+/// its offset. Returns None for the all-zero "not specified" date or
+/// if any of the fields are invalid.
+///
+/// ```
+/// use epochs::iso9660_dec_datetime;
+/// let (ndt, offset) = iso9660_dec_datetime(b"2009021323313065\x04").unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.650");
+/// assert_eq!(offset.to_string(), "+01:00");
+/// ```
+pub fn iso9660_dec_datetime(bytes: &[u8; 17]) -> Option<(NaiveDateTime, FixedOffset)> {
+    let year = digits(&bytes[0..4])?;
+    if year == 0 {
+        return None;
+    }
+    let ndt = NaiveDate::from_ymd_opt(year as i32, digits(&bytes[4..6])?, digits(&bytes[6..8])?)?
+        .and_hms_milli_opt(
+        digits(&bytes[8..10])?,
+        digits(&bytes[10..12])?,
+        digits(&bytes[12..14])?,
+        digits(&bytes[14..16])? * 10,
+    )?;
+    Some((ndt, offset_from_quarters(bytes[16] as i8)?))
+}
+
+/// Encode the given local time and offset as an [ISO 9660 volume
+/// descriptor date](fn.iso9660_dec_datetime.html). Fractions of a
+/// hundredth of a second are dropped. Returns None if the year is not
+/// 1 to 9999 or the offset is not a whole number of quarter hours from
+/// -12:00 to +13:00.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{FixedOffset, NaiveDateTime};
+/// use epochs::to_iso9660_dec_datetime;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let offset = FixedOffset::east_opt(0).unwrap();
+/// assert_eq!(
+///     to_iso9660_dec_datetime(ndt, offset),
+///     Some(*b"2009021323313000\x00"),
+/// );
+/// ```
+pub fn to_iso9660_dec_datetime(ndt: NaiveDateTime, offset: FixedOffset) -> Option<[u8; 17]> {
+    if !(1..=9999).contains(&ndt.year()) {
+        return None;
+    }
+    let quarters = offset_to_quarters(offset)?;
+    let text = format!(
+        "{:04}{:02}{:02}{:02}{:02}{:02}{:02}",
+        ndt.year(),
+        ndt.month(),
+        ndt.day(),
+        ndt.hour(),
+        ndt.minute(),
+        ndt.second().min(59),
+        ndt.nanosecond().min(999_999_999) / 10_000_000,
+    );
+    let mut bytes = [0; 17];
+    bytes[..16].copy_from_slice(text.as_bytes());
+    bytes[16] = quarters as u8;
+    Some(bytes)
+}
+
+/// The dates in ISO 9660 directory records are 7 bytes: the years
+/// since 1900, the month, day, hour, minute, and second, then the
+/// offset from GMT as a signed byte counting 15-minute intervals.
+/// Returns the local time as written, along with its offset. Returns
+/// None for the all-zero "not specified" date or if any of the fields
+/// are invalid.
+///
+/// ```
+/// use epochs::iso9660_dir_datetime;
+/// let (ndt, offset) = iso9660_dir_datetime(&[109, 2, 13, 23, 31, 30, 0xec]).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(offset.to_string(), "-05:00");
+/// ```
+pub fn iso9660_dir_datetime(bytes: &[u8; 7]) -> Option<(NaiveDateTime, FixedOffset)> {
+    if bytes == &[0; 7] {
+        return None;
+    }
+    let [year, month, day, hour, minute, second, quarters] = *bytes;
+    let ndt = NaiveDate::from_ymd_opt(1900 + i32::from(year), month.into(), day.into())?
+        .and_hms_opt(hour.into(), minute.into(), second.into())?;
+    Some((ndt, offset_from_quarters(quarters as i8)?))
+}
+
+/// Encode the given local time and offset as an [ISO 9660 directory
+/// record date](fn.iso9660_dir_datetime.html). Fractions of a second
+/// are dropped. Returns None if the year is not 1900 to 2155 or the
+/// offset is not a whole number of quarter hours from -12:00 to
+/// +13:00.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{FixedOffset, NaiveDateTime};
+/// use epochs::to_iso9660_dir_datetime;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let offset = FixedOffset::east_opt(0).unwrap();
+/// assert_eq!(
+///     to_iso9660_dir_datetime(ndt, offset),
+///     Some([109, 2, 13, 23, 31, 30, 0]),
+/// );
+/// ```
+pub fn to_iso9660_dir_datetime(ndt: NaiveDateTime, offset: FixedOffset) -> Option<[u8; 7]> {
+    let year = u8::try_from(ndt.year() - 1900).ok()?;
+    let quarters = offset_to_quarters(offset)?;
+    Some([
+        year,
+        ndt.month() as u8,
+        ndt.day() as u8,
+        ndt.hour() as u8,
+        ndt.minute() as u8,
+        ndt.second().min(59) as u8,
+        quarters as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    #[test]
+    fn dec_datetime_not_specified() {
+        assert!(iso9660_dec_datetime(b"0000000000000000\x00").is_none());
+    }
+    #[test]
+    fn dec_datetime_bad_fields() {
+        assert!(iso9660_dec_datetime(b"2009021323313x00\x00").is_none());
+        assert!(iso9660_dec_datetime(b"2009133123313000\x00").is_none());
+        assert!(iso9660_dec_datetime(b"2009021323313000\x35").is_none());
+        assert!(iso9660_dec_datetime(b"2009021323313000\xcf").is_none());
+    }
+    #[test]
+    fn dec_datetime_offset_extremes() {
+        let (_, offset) = iso9660_dec_datetime(b"2009021323313000\xd0").unwrap();
+        assert_eq!(offset.to_string(), "-12:00");
+        let (_, offset) = iso9660_dec_datetime(b"2009021323313000\x34").unwrap();
+        assert_eq!(offset.to_string(), "+13:00");
+    }
+    #[test]
+    fn to_dec_datetime_round_trip() {
+        let bytes = *b"1999123123595999\xe2";
+        let (ndt, offset) = iso9660_dec_datetime(&bytes).unwrap();
+        assert_eq!(ndt.to_string(), "1999-12-31 23:59:59.990");
+        assert_eq!(offset.to_string(), "-07:30");
+        assert_eq!(to_iso9660_dec_datetime(ndt, offset), Some(bytes));
+    }
+    #[test]
+    fn to_dec_datetime_bad_offset() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        let offset = FixedOffset::east_opt(14 * 60 * 60).unwrap();
+        assert!(to_iso9660_dec_datetime(ndt, offset).is_none());
+        let offset = FixedOffset::east_opt(10 * 60).unwrap();
+        assert!(to_iso9660_dec_datetime(ndt, offset).is_none());
+    }
+    #[test]
+    fn dir_datetime_not_specified() {
+        assert!(iso9660_dir_datetime(&[0; 7]).is_none());
+    }
+    #[test]
+    fn dir_datetime_bad_fields() {
+        assert!(iso9660_dir_datetime(&[109, 2, 30, 23, 31, 30, 0]).is_none());
+        assert!(iso9660_dir_datetime(&[109, 2, 13, 24, 31, 30, 0]).is_none());
+        assert!(iso9660_dir_datetime(&[109, 2, 13, 23, 31, 30, 53]).is_none());
+    }
+    #[test]
+    fn to_dir_datetime_round_trip() {
+        let bytes = [255, 12, 31, 23, 59, 59, 52];
+        let (ndt, offset) = iso9660_dir_datetime(&bytes).unwrap();
+        assert_eq!(ndt.to_string(), "2155-12-31 23:59:59");
+        assert_eq!(to_iso9660_dir_datetime(ndt, offset), Some(bytes));
+    }
+    #[test]
+    fn to_dir_datetime_out_of_range() {
+        let ndt = NaiveDate::from_ymd_opt(1899, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert!(to_iso9660_dir_datetime(ndt, utc()).is_none());
+        let ndt = NaiveDate::from_ymd_opt(2156, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert!(to_iso9660_dir_datetime(ndt, utc()).is_none());
+    }
+}
@@ -0,0 +1,94 @@
+//! Converting iterators of numbers and datetimes.
+
+use std::iter::Map;
+
+use chrono::NaiveDateTime;
+
+use crate::Epoch;
+
+/// Adds epoch conversions to every iterator, to use in a chain with
+/// the usual adapters.
+///
+/// ```
+/// use epochs::{Epoch, EpochIterExt};
+/// let visits = [12_879_041_490_000_000, 12_879_041_491_000_000];
+/// let times: Vec<String> = visits
+///     .into_iter()
+///     .map_epoch(Epoch::Chrome)
+///     .flatten()
+///     .map(|ndt| ndt.to_string())
+///     .collect();
+/// assert_eq!(times, ["2009-02-13 23:31:30", "2009-02-13 23:31:31"]);
+/// ```
+pub trait EpochIterExt: Iterator + Sized {
+    /// Convert each number from the given epoch, yielding None for any
+    /// that cannot be converted.
+    ///
+    /// ```
+    /// use epochs::{Epoch, EpochIterExt};
+    /// let mut times = [0, i64::MAX].into_iter().map_epoch(Epoch::Unix);
+    /// assert_eq!(times.next().unwrap().unwrap().to_string(), "1970-01-01 00:00:00");
+    /// assert_eq!(times.next(), Some(None));
+    /// ```
+    fn map_epoch(self, epoch: Epoch) -> Map<Self, fn(i64) -> Option<NaiveDateTime>>
+    where
+        Self: Iterator<Item = i64>,
+    {
+        self.map(epoch.converter())
+    }
+
+    /// Convert each NaiveDateTime to a number in the given epoch,
+    /// saturating as [from_datetime](enum.Epoch.html#method.from_datetime)
+    /// does.
+    ///
+    /// ```
+    /// use epochs::{Epoch, EpochIterExt};
+    /// let times = [epochs::unix(1234567890).unwrap()];
+    /// let javas: Vec<i64> = times.into_iter().map_to_epoch(Epoch::Java).collect();
+    /// assert_eq!(javas, [1_234_567_890_000]);
+    /// ```
+    fn map_to_epoch(self, epoch: Epoch) -> Map<Self, fn(NaiveDateTime) -> i64>
+    where
+        Self: Iterator<Item = NaiveDateTime>,
+    {
+        self.map(epoch.inverter())
+    }
+}
+
+impl<I: Iterator> EpochIterExt for I {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let nums = [0, 1, -1, 1_234_567_890_654_321];
+        let back: Vec<i64> = nums
+            .into_iter()
+            .map_epoch(Epoch::Mozilla)
+            .map(Option::unwrap)
+            .map_to_epoch(Epoch::Mozilla)
+            .collect();
+        assert_eq!(back, nums);
+    }
+    #[test]
+    fn rescale_between_epochs() {
+        let out: Vec<i64> = [128_790_414_900_000_000]
+            .into_iter()
+            .map_epoch(Epoch::WindowsFile)
+            .flatten()
+            .map_to_epoch(Epoch::Unix)
+            .collect();
+        assert_eq!(out, [1_234_567_890]);
+    }
+    #[test]
+    fn lazy() {
+        let mut it = (0..).map_epoch(Epoch::Unix);
+        assert_eq!(
+            it.nth(86_400).unwrap().unwrap().to_string(),
+            "1970-01-02 00:00:00"
+        );
+    }
+}
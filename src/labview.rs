@@ -0,0 +1,81 @@
+//! LabVIEW timestamps.
+
+use chrono::{DateTime, NaiveDateTime};
+
+use crate::{fraction_to_nanos, nanos_to_fraction};
+
+/// LabVIEW's epoch, 1904-01-01 UTC, is 2,082,844,800 seconds before
+/// the Unix epoch.
+const LABVIEW_OFFSET: i64 = 2_082_844_800;
+
+/// LabVIEW time is 128 bits: a signed 64-bit count of seconds since
+/// 1904-01-01 UTC and an unsigned 64-bit binary fraction of a second
+/// (*i.e.*, in units of 2<sup>-64</sup> seconds). The fraction is
+/// truncated to nanoseconds.
+///
+/// ```
+/// use epochs::labview;
+/// let ndt = labview(3_317_412_690, 1 << 63).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.500");
+/// ```
+pub fn labview(seconds: i64, fraction: u64) -> Option<NaiveDateTime> {
+    let t = seconds.checked_sub(LABVIEW_OFFSET)?;
+    let n = fraction_to_nanos(fraction as u128, 64);
+    DateTime::from_timestamp(t, n).map(|dt| dt.naive_utc())
+}
+
+/// Convert the given NaiveDateTime to a [LabVIEW](fn.labview.html)
+/// time, returning the seconds and the fraction.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_labview;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_labview(ndt), (3_317_412_690, 1 << 63));
+/// ```
+pub fn to_labview(ndt: NaiveDateTime) -> (i64, u64) {
+    let dt = ndt.and_utc();
+    let nanos = dt.timestamp_subsec_nanos().min(999_999_999);
+    (
+        dt.timestamp() + LABVIEW_OFFSET,
+        nanos_to_fraction(nanos, 64) as u64,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn labview_epoch() {
+        let ndt = labview(0, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1904-01-01 00:00:00");
+    }
+    #[test]
+    fn labview_negative() {
+        let ndt = labview(-1, 1 << 63).unwrap();
+        assert_eq!(ndt.to_string(), "1903-12-31 23:59:59.500");
+    }
+    #[test]
+    fn labview_max_fraction() {
+        let ndt = labview(0, u64::MAX).unwrap();
+        assert_eq!(ndt.to_string(), "1904-01-01 00:00:00.999999999");
+    }
+    #[test]
+    fn labview_too_big() {
+        assert!(labview(i64::MAX, 0).is_none());
+        assert!(labview(i64::MIN, 0).is_none());
+    }
+    #[test]
+    fn round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2012, 5, 27)
+            .unwrap()
+            .and_hms_nano_opt(6, 36, 17, 971_000_001)
+            .unwrap();
+        let (seconds, fraction) = to_labview(ndt);
+        assert_eq!(labview(seconds, fraction), Some(ndt));
+    }
+}
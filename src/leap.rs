@@ -0,0 +1,351 @@
+//! Leap seconds, and International Atomic Time (TAI).
+
+use chrono::{DateTime, Duration, NaiveDateTime};
+
+use crate::to_unix_const;
+
+const fn at(year: i32, month: u32) -> i64 {
+    match to_unix_const(year, month, 1, 0, 0, 0) {
+        Some(t) => t,
+        None => panic!("bad leap second date"),
+    }
+}
+
+/// The Unix times at which each TAI - UTC offset took effect, from
+/// IERS Bulletin C. There has not been a leap second since 2017.
+pub(crate) const LEAP_SECONDS: [(i64, i32); 28] = [
+    (at(1972, 1), 10),
+    (at(1972, 7), 11),
+    (at(1973, 1), 12),
+    (at(1974, 1), 13),
+    (at(1975, 1), 14),
+    (at(1976, 1), 15),
+    (at(1977, 1), 16),
+    (at(1978, 1), 17),
+    (at(1979, 1), 18),
+    (at(1980, 1), 19),
+    (at(1981, 7), 20),
+    (at(1982, 7), 21),
+    (at(1983, 7), 22),
+    (at(1985, 7), 23),
+    (at(1988, 1), 24),
+    (at(1990, 1), 25),
+    (at(1991, 1), 26),
+    (at(1992, 7), 27),
+    (at(1993, 7), 28),
+    (at(1994, 7), 29),
+    (at(1996, 1), 30),
+    (at(1997, 7), 31),
+    (at(1999, 1), 32),
+    (at(2006, 1), 33),
+    (at(2009, 1), 34),
+    (at(2012, 7), 35),
+    (at(2015, 7), 36),
+    (at(2017, 1), 37),
+];
+
+/// The offset before the first entry. UTC was not kept within a whole
+/// number of seconds of TAI until 1972, so earlier times are only
+/// approximate.
+const FIRST_OFFSET: i32 = LEAP_SECONDS[0].1;
+
+/// The offset in effect at the given Unix time.
+fn offset_at(t: i64) -> i32 {
+    match LEAP_SECONDS.iter().rposition(|&(start, _)| t >= start) {
+        Some(i) => LEAP_SECONDS[i].1,
+        None => FIRST_OFFSET,
+    }
+}
+
+/// The number of seconds TAI is ahead of UTC at the given UTC time (37
+/// since 2017). During a leap second, this is still the old offset.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::utc_tai_offset;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(utc_tai_offset(ndt), 34);
+/// ```
+pub fn utc_tai_offset(ndt: NaiveDateTime) -> i32 {
+    offset_at(ndt.and_utc().timestamp())
+}
+
+/// TAI time is the number of SI seconds since 1970-01-01 00:00:00 TAI
+/// (as, *e.g.*, Linux's CLOCK_TAI counts). Unlike Unix time, it
+/// counts leap seconds, so this uses a built-in table of them to
+/// return the UTC time. A leap second comes back as second 59 with an
+/// extra second of nanoseconds, the way chrono represents it.
+///
+/// ```
+/// use epochs::tai;
+/// let ndt = tai(1_234_567_924).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// let ndt = tai(1_230_768_033).unwrap();
+/// assert_eq!(ndt.to_string(), "2008-12-31 23:59:60");
+/// ```
+pub fn tai(num: i64) -> Option<NaiveDateTime> {
+    from_tai_nanos(i128::from(num) * NANOS)
+}
+
+pub(crate) const NANOS: i128 = 1_000_000_000;
+
+/// Convert nanoseconds of TAI to UTC.
+pub(crate) fn from_tai_nanos(num: i128) -> Option<NaiveDateTime> {
+    let seconds = i64::try_from(num.div_euclid(NANOS)).ok()?;
+    let nanos = num.rem_euclid(NANOS) as u32;
+    let i = LEAP_SECONDS
+        .iter()
+        .rposition(|&(start, offset)| seconds >= start + i64::from(offset));
+    let offset = i.map_or(FIRST_OFFSET, |i| LEAP_SECONDS[i].1);
+    let t = seconds.checked_sub(offset.into())?;
+
+    // The TAI second just before a new offset takes effect is the leap
+    // second itself.
+    let next = i.map_or(0, |i| i + 1);
+    if LEAP_SECONDS.get(next).is_some_and(|&(start, _)| t == start) {
+        return DateTime::from_timestamp(t - 1, 1_000_000_000 + nanos).map(|dt| dt.naive_utc());
+    }
+    DateTime::from_timestamp(t, nanos).map(|dt| dt.naive_utc())
+}
+
+/// Convert UTC to nanoseconds of TAI.
+pub(crate) fn to_tai_nanos(ndt: NaiveDateTime) -> i128 {
+    let dt = ndt.and_utc();
+    let t = dt.timestamp();
+    i128::from(t + i64::from(offset_at(t))) * NANOS + i128::from(dt.timestamp_subsec_nanos())
+}
+
+/// Convert the given UTC NaiveDateTime to a [TAI](fn.tai.html) time,
+/// adding however many leap seconds there have been. Fractions of a
+/// second are dropped.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_tai;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_tai(ndt), 1_234_567_924);
+/// ```
+pub fn to_tai(ndt: NaiveDateTime) -> i64 {
+    to_tai_nanos(ndt).div_euclid(NANOS) as i64
+}
+
+/// Unix time as kept by systems using the "right/" zoneinfo files,
+/// which count leap seconds rather than ignore them as POSIX does.
+/// This agrees with [unix](fn.unix.html) time until 1972, but is 27
+/// seconds ahead of it since 2017. Returns the UTC time, with a leap
+/// second coming back as 23:59:60.
+///
+/// ```
+/// use epochs::unix_right;
+/// let ndt = unix_right(1_234_567_914).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn unix_right(num: i64) -> Option<NaiveDateTime> {
+    tai(num.checked_add(FIRST_OFFSET.into())?)
+}
+
+/// Convert the given UTC NaiveDateTime to a ["right/" Unix
+/// time](fn.unix_right.html). Fractions of a second are dropped.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_unix_right;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_unix_right(ndt), 1_234_567_914);
+/// ```
+pub fn to_unix_right(ndt: NaiveDateTime) -> i64 {
+    to_tai(ndt) - i64::from(FIRST_OFFSET)
+}
+
+/// The smear window around each leap second, as (start, end) in Unix
+/// nanoseconds, with the TAI nanoseconds at its start.
+fn smears(window: Duration) -> Option<impl Iterator<Item = (i128, i128, i128)>> {
+    let half = i128::from(window.num_nanoseconds()?) / 2;
+    (half > 0).then(|| {
+        LEAP_SECONDS
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(move |(i, &(start, _))| {
+                let start = i128::from(start) * NANOS;
+                let old_offset = i128::from(LEAP_SECONDS[i - 1].1) * NANOS;
+                (start - half, start + half, start - half + old_offset)
+            })
+    })
+}
+
+/// Convert the given UTC time to smeared time. Rather than insert a
+/// leap second, a smeared clock (*e.g.*, Google's or AWS's) runs
+/// slightly slow over a window centred on it, so it never reads
+/// 23:59:60; Google and AWS use a 24-hour window, from noon to noon.
+/// Outside the windows, smeared and UTC times are the same. Returns
+/// None if the window is not positive (or too long to count in
+/// nanoseconds).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{Duration, NaiveDateTime};
+/// use epochs::utc_to_smeared;
+/// let ndt = NaiveDateTime::parse_from_str("2016-12-31 23:59:60", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let smeared = utc_to_smeared(ndt, Duration::hours(24)).unwrap();
+/// assert_eq!(smeared.to_string(), "2016-12-31 23:59:59.500005786");
+/// ```
+pub fn utc_to_smeared(ndt: NaiveDateTime, window: Duration) -> Option<NaiveDateTime> {
+    let total = i128::from(window.num_nanoseconds()?);
+    let r = to_tai_nanos(ndt);
+    for (start, end, tai_start) in smears(window)? {
+        if (tai_start..tai_start + total + NANOS).contains(&r) {
+            // The smeared clock covers the window in one second less.
+            let t = start + (r - tai_start) * total / (total + NANOS);
+            return from_unix_nanos(t.min(end - 1));
+        }
+    }
+    // Outside a smear, drop any leap second (there are none there).
+    Some(ndt)
+}
+
+/// Convert the given smeared time (see
+/// [utc_to_smeared](fn.utc_to_smeared.html)) back to UTC.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{Duration, NaiveDateTime};
+/// use epochs::smeared_to_utc;
+/// let ndt = NaiveDateTime::parse_from_str("2017-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let utc = smeared_to_utc(ndt, Duration::hours(24)).unwrap();
+/// assert_eq!(utc.to_string(), "2016-12-31 23:59:60.500");
+/// ```
+pub fn smeared_to_utc(ndt: NaiveDateTime, window: Duration) -> Option<NaiveDateTime> {
+    let total = i128::from(window.num_nanoseconds()?);
+    let dt = ndt.and_utc();
+    let t = i128::from(dt.timestamp()) * NANOS + i128::from(dt.timestamp_subsec_nanos());
+    for (start, end, tai_start) in smears(window)? {
+        if (start..end).contains(&t) {
+            // Round up, so that the smallest UTC time that smears to
+            // this one comes back.
+            let elapsed = ((t - start) * (total + NANOS) + total - 1) / total;
+            return from_tai_nanos(tai_start + elapsed);
+        }
+    }
+    Some(ndt)
+}
+
+pub(crate) fn from_unix_nanos(t: i128) -> Option<NaiveDateTime> {
+    let seconds = i64::try_from(t.div_euclid(NANOS)).ok()?;
+    DateTime::from_timestamp(seconds, t.rem_euclid(NANOS) as u32).map(|dt| dt.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::{Duration, NaiveDate};
+
+    fn leap_second() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_milli_opt(23, 59, 59, 1_000)
+            .unwrap()
+    }
+
+    #[test]
+    fn table_is_sorted() {
+        for pair in LEAP_SECONDS.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+            assert_eq!(pair[0].1 + 1, pair[1].1);
+        }
+        assert_eq!(LEAP_SECONDS[27], (1_483_228_800, 37));
+    }
+    #[test]
+    fn offset_around_leap_second() {
+        let before = leap_second() - Duration::seconds(1);
+        assert_eq!(utc_tai_offset(before), 36);
+        assert_eq!(utc_tai_offset(leap_second()), 36);
+        let after = leap_second() + Duration::seconds(1);
+        assert_eq!(after.to_string(), "2017-01-01 00:00:00");
+        assert_eq!(utc_tai_offset(after), 37);
+    }
+    #[test]
+    fn offset_before_1972() {
+        let ndt = NaiveDate::from_ymd_opt(1960, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(utc_tai_offset(ndt), 10);
+    }
+    #[test]
+    fn tai_counts_leap_seconds() {
+        let t = to_tai(leap_second());
+        assert_eq!(tai(t - 1).unwrap().to_string(), "2016-12-31 23:59:59");
+        assert_eq!(tai(t).unwrap(), leap_second());
+        assert_eq!(tai(t + 1).unwrap().to_string(), "2017-01-01 00:00:00");
+    }
+    #[test]
+    fn tai_round_trip() {
+        for &(start, _) in &LEAP_SECONDS {
+            for t in start - 2..start + 2 {
+                let ndt = DateTime::from_timestamp(t, 0).unwrap().naive_utc();
+                assert_eq!(tai(to_tai(ndt)), Some(ndt));
+            }
+        }
+    }
+    #[test]
+    fn tai_too_big() {
+        assert!(tai(i64::MAX).is_none());
+        assert!(tai(i64::MIN).is_none());
+    }
+    #[test]
+    fn unix_right_run() {
+        assert_eq!(unix_right(0).unwrap().to_string(), "1970-01-01 00:00:00");
+        let posix = leap_second().and_utc().timestamp();
+        assert_eq!(to_unix_right(leap_second()), posix + 26 + 1);
+        assert_eq!(unix_right(posix + 27), Some(leap_second()));
+        let after = leap_second() + Duration::seconds(1);
+        assert_eq!(to_unix_right(after), crate::to_unix(after) + 27);
+    }
+    #[test]
+    fn smear_midpoint() {
+        let day = Duration::hours(24);
+        let smeared = utc_to_smeared(leap_second(), day).unwrap();
+        assert_eq!(smeared_to_utc(smeared, day), Some(leap_second()));
+        let noon = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        assert_eq!(utc_to_smeared(noon, day), Some(noon));
+        assert_eq!(smeared_to_utc(noon, day), Some(noon));
+    }
+    #[test]
+    fn smear_ends() {
+        let day = Duration::hours(24);
+        let noon = NaiveDate::from_ymd_opt(2017, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        assert_eq!(utc_to_smeared(noon, day), Some(noon));
+        let before = noon - Duration::nanoseconds(1);
+        assert_eq!(utc_to_smeared(before, day), Some(before));
+        let smeared = smeared_to_utc(before, day).unwrap();
+        assert_eq!(smeared.to_string(), "2017-01-01 11:59:59.999999999");
+    }
+    #[test]
+    fn smear_is_monotonic() {
+        let window = Duration::seconds(10);
+        let mut prev = None;
+        for tenths in -60..60 {
+            let ndt = leap_second() + Duration::milliseconds(tenths * 100);
+            let smeared = utc_to_smeared(ndt, window).unwrap();
+            assert!(prev < Some(smeared));
+            assert!(smeared.and_utc().timestamp_subsec_nanos() < 1_000_000_000);
+            prev = Some(smeared);
+        }
+    }
+    #[test]
+    fn smear_bad_window() {
+        assert!(utc_to_smeared(leap_second(), Duration::zero()).is_none());
+        assert!(smeared_to_utc(leap_second(), Duration::seconds(-1)).is_none());
+    }
+}
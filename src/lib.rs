@@ -1,93 +1,461 @@
 //! Convert various epoch times to [chrono::NaiveDateTime](https://docs.rs/chrono/0.4.10/chrono/naive/struct.NaiveDateTime.html) times.
+//!
+//! The conversion functions do not panic on any input. Values that are
+//! out of range, NaN, or otherwise meaningless give None instead. The
+//! only exceptions are the `+` and `-` operators on the epoch types,
+//! which panic on overflow like the integer operators do (their
+//! `checked_*` counterparts do not).
 
 extern crate chrono;
 
-use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
 
 // The icq function uses time::Duration, which panics if given too big
 // a number. The maximum is i64::MAX milliseconds.
-const MAX_DAYS: i64 = std::i64::MAX / (24 * 60 * 60 * 1000);
+const MAX_DAYS: i64 = i64::MAX / (24 * 60 * 60 * 1000);
 
 const MILLIS_PER_DAY: f64 = 24. * 60. * 60. * 1000.;
 
-/// APFS time is the number of nanoseconds since the Unix epoch
-/// (*cf.*, [APFS filesystem format](https://blog.cugu.eu/post/apfs/)).
+const FILEMAKER: CustomEpoch = CustomEpoch::new(Unit::Seconds, -62_135_596_800);
+
+// FileMaker's last second, 4000-12-31 23:59:59.
+const FILEMAKER_MAX: i64 = 126_227_807_999;
+
+const GARMIN_FIT: CustomEpoch = CustomEpoch::new(Unit::Seconds, 631_065_600);
+
+// 1904-01-01, the classic Mac OS epoch shared by QuickTime and Palm OS.
+const MAC_1904: CustomEpoch = CustomEpoch::new(Unit::Seconds, -2_082_844_800);
+
+const ZIGBEE: CustomEpoch = CustomEpoch::new(Unit::Seconds, 946_684_800);
+
+// Julian day zero, noon on 4713-01-01 BC in the Julian calendar
+// (-4713-11-24 in the proleptic Gregorian calendar).
+const TANDEM: CustomEpoch = CustomEpoch::new(Unit::Microseconds, -210_866_760_000);
+
+// 1858-11-17, day zero of the Modified Julian Day, counted the way
+// chrono counts days from the common era (0001-01-01 is day 1).
+const MJD_DAYS_FROM_CE: i64 = 678_576;
+
+// 1960-01-01, day zero of SAS and Stata dates, counted the same way.
+const SAS_DAYS_FROM_CE: i64 = 715_510;
+
+#[macro_use]
+mod macros;
+
+mod amiga;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+mod astro;
+mod batch;
+#[cfg(test)]
+mod boundary;
+pub mod bytes;
+mod custom;
+mod dotnet;
+mod epoch;
+mod error;
+mod excel;
+mod ext4;
+mod fat;
+mod format;
+mod gnss;
+mod go;
+mod gsm;
+mod guess;
+mod horolog;
+mod info;
+mod iso9660;
+mod iter;
+mod labview;
+mod leap;
+mod mssql;
+mod ntp;
+mod oracle;
+mod parquet;
+mod parse;
+#[cfg(feature = "polars")]
+pub mod polars;
+#[cfg(feature = "prost")]
+pub mod prost;
+mod ptp;
+#[cfg(feature = "python")]
+mod python;
+mod rtc;
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "diesel")]
+mod sql;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(feature = "csv")]
+pub mod stream;
+mod systemtime;
+pub mod test_vectors;
+mod tod;
+mod uuid;
+mod y2038;
+
+pub use amiga::{amiga, to_amiga};
+pub use astro::{
+    besselian_year, j2000_seconds, julian_epoch_year, tdb_to_tt, to_besselian_year,
+    to_j2000_seconds, to_julian_epoch_year, tt_to_tdb, tt_to_utc, utc_to_tt,
+};
+pub use batch::{convert_slice, convert_vec};
+#[cfg(feature = "rayon")]
+pub use batch::{par_convert_slice, par_convert_vec};
+pub use custom::{ConversionError, CustomEpoch, Unit};
+pub use dotnet::{dotnet_binary, to_dotnet_binary, DotnetKind};
+pub use epoch::{
+    duration_between, rescale, saturating_duration_between, verify_roundtrip, Epoch,
+    ParseEpochError, RoundTrip,
+};
+pub use error::EpochError;
+pub use excel::{
+    excel_1900, excel_1900_date, excel_1904, excel_1904_date, to_excel_1900, to_excel_1900_date,
+    to_excel_1904, to_excel_1904_date,
+};
+pub use ext4::{ext4, to_ext4};
+pub use fat::{
+    exfat, exfat_with_offset, fat, fat_with_offset, to_exfat, to_exfat_with_offset, to_fat,
+    to_fat_with_offset,
+};
+#[cfg(feature = "clock")]
+pub use fat::{exfat_local, fat_local};
+pub use format::{format, parse_as, FormatError};
+pub use gnss::{
+    beidou_t, cdma, cdma_frames, galileo_st, glonass, gps_week_tow, gps_week_tow_near, to_beidou_t,
+    to_cdma, to_cdma_frames, to_galileo_st, to_glonass, to_gps_week_tow,
+};
+pub use go::{go_binary, to_go_binary};
+pub use gsm::{gsm_scts, to_gsm_scts};
+#[cfg(feature = "clock")]
+pub use guess::guess_ranked;
+pub use guess::{
+    detect, guess, guess_in_range, guess_many, guess_ranked_at, guess_str, view_all, Detected,
+    GuessReport, RankedGuess, GUESS_FROM, GUESS_TO,
+};
+pub use horolog::{horolog, to_horolog};
+pub use info::{all_epochs, EpochInfo};
+pub use iso9660::{
+    iso9660_dec_datetime, iso9660_dir_datetime, to_iso9660_dec_datetime, to_iso9660_dir_datetime,
+};
+pub use iter::EpochIterExt;
+pub use labview::{labview, to_labview};
+pub use leap::{
+    smeared_to_utc, tai, to_tai, to_unix_right, unix_right, utc_tai_offset, utc_to_smeared,
+};
+pub use mssql::{
+    mssql_datetime, mssql_datetime2, mssql_datetimeoffset, mssql_smalldatetime, to_mssql_datetime,
+    to_mssql_datetime2, to_mssql_datetimeoffset, to_mssql_smalldatetime,
+};
+pub use ntp::{ntp, ntp_date, ntp_short, to_ntp, to_ntp_date, to_ntp_short};
+pub use oracle::{oracle_date, oracle_timestamp, to_oracle_date, to_oracle_timestamp};
+pub use parquet::{parquet_int96, to_parquet_int96};
+pub use parse::{parse_datetime, parse_hex, parse_value, ParseValueError, Value};
+pub use ptp::{ptp, ptp_utc, to_ptp, to_ptp_utc};
+#[cfg(feature = "clock")]
+pub use rtc::bcd_rtc_local;
+pub use rtc::{bcd_rtc, bcd_rtc_with_offset, to_bcd_rtc, to_bcd_rtc_with_offset};
+#[cfg(feature = "clock")]
+pub use systemtime::systemtime_from_le_bytes_local;
+pub use systemtime::{
+    datetime_to_systemtime, datetime_to_systemtime_with_offset, systemtime_from_le_bytes,
+    systemtime_from_le_bytes_with_offset, systemtime_to_datetime, Systemtime,
+};
+pub use tod::{
+    as400_dts, to_as400_dts, to_tod_clock, to_tod_clock_extended, tod_clock, tod_clock_extended,
+};
+pub use uuid::{parse_uuid_v1, uuid_v1_fields, ParseUuidError, Uuid1Fields};
+pub use y2038::{y2038_at_risk, y2038_at_risk_datetime, y2038_headroom, y2038_unwrap};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "clock")]
+    pub use chrono::{DateTime, Local, Utc};
+    pub use chrono::{Duration, NaiveDateTime};
+}
+
+define_own_epoch! {
+    /// APFS time is the number of nanoseconds since the Unix epoch
+    /// (*cf.*, [APFS filesystem format](https://blog.cugu.eu/post/apfs/)).
+    ///
+    /// ```
+    /// use epochs::apfs;
+    /// let ndt = apfs(1_234_567_890_000_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn apfs;
+
+    /// Convert the given NaiveDateTime to an [APFS](fn.apfs.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_apfs;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_apfs(ndt), 1_234_567_890_000_000_000);
+    /// ```
+    pub fn to_apfs;
+
+    /// An [APFS](fn.apfs.html) time, as a count of nanoseconds.
+    pub struct ApfsNanos;
+
+    unit: Nanoseconds,
+    offset: 0,
+}
+
+define_own_epoch! {
+    /// Chrome time is the number of microseconds since 1601-01-01, which
+    /// is 11,644,473,600 seconds before the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::chrome;
+    /// let ndt = chrome(12_879_041_490_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn chrome;
+
+    /// Convert the given NaiveDateTime to a [Chrome](fn.chrome.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_chrome;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_chrome(ndt), 12_879_041_490_000_000);
+    /// ```
+    pub fn to_chrome;
+
+    /// A [Chrome](fn.chrome.html) time, as a count of microseconds.
+    pub struct ChromeMicros;
+
+    unit: Microseconds,
+    offset: -11_644_473_600,
+}
+
+define_own_epoch! {
+    /// CICS time (the ABSTIME returned by EXEC CICS ASKTIME) is the
+    /// number of milliseconds since 1900-01-01, which is 2,208,988,800
+    /// seconds before the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::cics;
+    /// let ndt = cics(3_443_556_690_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn cics;
+
+    /// Convert the given NaiveDateTime to a [CICS](fn.cics.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_cics;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_cics(ndt), 3_443_556_690_000);
+    /// ```
+    pub fn to_cics;
+
+    /// A [CICS](fn.cics.html) time, as a count of milliseconds.
+    pub struct CicsMillis;
+
+    unit: Milliseconds,
+    offset: -2_208_988_800,
+}
+
+define_own_epoch! {
+    /// Cocoa time is the number of seconds since 2001-01-01, which is
+    /// 978,307,200 seconds after the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::cocoa;
+    /// let ndt = cocoa(256260690).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn cocoa;
+
+    /// Convert the given NaiveDateTime to a [Cocoa](fn.cocoa.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_cocoa;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_cocoa(ndt), 256260690);
+    /// ```
+    pub fn to_cocoa;
+
+    /// A [Cocoa](fn.cocoa.html) time, as a count of seconds.
+    pub struct CocoaSeconds;
+
+    unit: Seconds,
+    offset: 978_307_200,
+}
+
+/// Core Data and `CFAbsoluteTime` store [Cocoa](fn.cocoa.html) time as
+/// a double, keeping the fraction of a second. A double holds present-day
+/// values to about a tenth of a microsecond, so this rounds to the
+/// nearest microsecond. Returns None for NaN or infinite values.
 ///
 /// ```
-/// use epochs::apfs;
-/// let ndt = apfs(1_234_567_890_000_000_000).unwrap();
-/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// use epochs::cocoa_f64;
+/// let ndt = cocoa_f64(256260690.123456).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.123456");
 /// ```
-pub fn apfs(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 1_000_000_000, 0)
+pub fn cocoa_f64(num: f64) -> Option<NaiveDateTime> {
+    if !num.is_finite() {
+        return None;
+    }
+    let offset = CocoaSeconds::EPOCH.offset_seconds() * 1_000_000;
+    // The cast saturates, and anything that far out is rejected below.
+    let micros = ((num * 1e6).round() as i64).checked_add(offset)?;
+    DateTime::from_timestamp_micros(micros).map(|dt| dt.naive_utc())
 }
 
-/// Convert the given NaiveDateTime to an [APFS](fn.apfs.html) time.
+/// Convert the given NaiveDateTime to a [fractional
+/// Cocoa](fn.cocoa_f64.html) time.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_apfs;
-/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_apfs(ndt), 1_234_567_890_000_000_000);
+/// use epochs::to_cocoa_f64;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_cocoa_f64(ndt), 256260690.5);
+/// ```
+pub fn to_cocoa_f64(ndt: NaiveDateTime) -> f64 {
+    let dt = ndt.and_utc();
+    let seconds = dt.timestamp() - CocoaSeconds::EPOCH.offset_seconds();
+    seconds as f64 + f64::from(dt.timestamp_subsec_nanos()) / 1e9
+}
+
+/// A date object in an Apple binary property list (`bplist00`) is a
+/// big-endian double holding a [fractional Cocoa](fn.cocoa_f64.html)
+/// time. This takes the eight bytes after the `0x33` marker byte.
+///
+/// ```
+/// use epochs::bplist_date;
+/// let ndt = bplist_date(&[0x41, 0xae, 0x8c, 0x74, 0xa5, 0x00, 0x00, 0x00]).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.500");
 /// ```
-pub fn to_apfs(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 1_000_000_000, 0)
+pub fn bplist_date(bytes: &[u8; 8]) -> Option<NaiveDateTime> {
+    cocoa_f64(f64::from_be_bytes(*bytes))
 }
 
-/// Chrome time is the number of microseconds since 1601-01-01, which
-/// is 11,644,473,600 seconds before the Unix epoch.
+/// Encode the given NaiveDateTime as the eight bytes of a [binary
+/// plist date](fn.bplist_date.html).
 ///
 /// ```
-/// use epochs::chrome;
-/// let ndt = chrome(12_879_041_490_000_000).unwrap();
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_bplist_date;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_bplist_date(ndt), [0x41, 0xae, 0x8c, 0x74, 0xa5, 0x00, 0x00, 0x00]);
+/// ```
+pub fn to_bplist_date(ndt: NaiveDateTime) -> [u8; 8] {
+    to_cocoa_f64(ndt).to_be_bytes()
+}
+
+/// FileMaker time is the number of seconds since 0001-01-01, which
+/// is 62,135,596,800 seconds before the Unix epoch. FileMaker only
+/// handles the years 1 to 4000, so anything outside that range
+/// (including the year 0 of the proleptic Gregorian calendar) is
+/// rejected rather than converted.
+///
+/// ```
+/// use epochs::filemaker;
+/// let ndt = filemaker(63_370_164_690).unwrap();
 /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert!(filemaker(-1).is_none());
 /// ```
-pub fn chrome(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 1_000_000, -11_644_473_600)
+pub fn filemaker(num: i64) -> Option<NaiveDateTime> {
+    if !(0..=FILEMAKER_MAX).contains(&num) {
+        return None;
+    }
+    FILEMAKER.to_datetime(num)
 }
 
-/// Convert the given NaiveDateTime to a [Chrome](fn.chrome.html) time.
+/// Convert the given NaiveDateTime to a [FileMaker](fn.filemaker.html)
+/// time. Returns None if it is before the year 1 or after the year
+/// 4000.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_chrome;
+/// use epochs::to_filemaker;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_chrome(ndt), 12_879_041_490_000_000);
+/// assert_eq!(to_filemaker(ndt), Some(63_370_164_690));
 /// ```
-pub fn to_chrome(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 1_000_000, -11_644_473_600)
+pub fn to_filemaker(ndt: NaiveDateTime) -> Option<i64> {
+    Some(FILEMAKER.from_datetime(ndt)).filter(|num| (0..=FILEMAKER_MAX).contains(num))
 }
 
-/// Cocoa time is the number of seconds since 2001-01-01, which is
-/// 978,307,200 seconds after the Unix epoch.
+/// Like [to_filemaker](fn.to_filemaker.html), but clamps times
+/// outside of the years 1 to 4000 to the first or last second
+/// FileMaker can show.
 ///
 /// ```
-/// use epochs::cocoa;
-/// let ndt = cocoa(256260690).unwrap();
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_filemaker_saturating;
+/// let ndt = NaiveDateTime::parse_from_str("5000-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_filemaker_saturating(ndt), 126_227_807_999);
+/// ```
+pub fn to_filemaker_saturating(ndt: NaiveDateTime) -> i64 {
+    FILEMAKER.from_datetime(ndt).clamp(0, FILEMAKER_MAX)
+}
+
+/// Garmin FIT time (the `date_time` fields of FIT activity files) is
+/// an unsigned 32-bit count of seconds since 1989-12-31 UTC, which is
+/// 631,065,600 seconds after the Unix epoch. The FIT protocol treats
+/// values below 0x10000000 as seconds since the device powered on
+/// rather than real times, but they are converted like any other.
+///
+/// ```
+/// use epochs::garmin_fit;
+/// let ndt = garmin_fit(603_502_290).unwrap();
 /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
 /// ```
-pub fn cocoa(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 1, 978_307_200)
+pub fn garmin_fit(num: u32) -> Option<NaiveDateTime> {
+    GARMIN_FIT.to_datetime(num.into())
 }
 
-/// Convert the given NaiveDateTime to a [Cocoa](fn.cocoa.html) time.
+/// Convert the given NaiveDateTime to a [Garmin FIT](fn.garmin_fit.html)
+/// time. Returns None if it does not fit in 32 bits, that is, before
+/// 1989-12-31 or after 2126-02-06 06:28:15.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_cocoa;
+/// use epochs::to_garmin_fit;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_cocoa(ndt), 256260690);
+/// assert_eq!(to_garmin_fit(ndt), Some(603_502_290));
 /// ```
-pub fn to_cocoa(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 1, 978_307_200)
+pub fn to_garmin_fit(ndt: NaiveDateTime) -> Option<u32> {
+    u32::try_from(GARMIN_FIT.from_datetime(ndt)).ok()
+}
+
+/// Like [to_garmin_fit](fn.to_garmin_fit.html), but clamps times
+/// outside of the range of a u32.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_garmin_fit_saturating;
+/// let ndt = NaiveDateTime::parse_from_str("1980-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_garmin_fit_saturating(ndt), 0);
+/// ```
+pub fn to_garmin_fit_saturating(ndt: NaiveDateTime) -> u32 {
+    GARMIN_FIT.from_datetime(ndt).clamp(0, u32::MAX.into()) as u32
 }
 
 /// Google Calendar time seems to count 32-day months from the day
 /// before the Unix epoch ([@noppers](https://github.com/noppers)
-/// worked out how to do this).
+/// worked out how to do this). That is, it packs the year, month, day,
+/// and time of day as if every month had 32 days, so there are gaps:
+/// day 0 of a month, or days past its end (*e.g.*, February 30th),
+/// return None. Every number that converts converts back to itself.
 ///
 /// ```
 /// use epochs::google_calendar;
@@ -96,25 +464,20 @@ pub fn to_cocoa(ndt: NaiveDateTime) -> i64 {
 /// ```
 pub fn google_calendar(num: i64) -> Option<NaiveDateTime> {
     let seconds_per_day = 24 * 60 * 60;
-    let total_days = num / seconds_per_day;
-    let seconds = num % seconds_per_day;
-
-    let months = total_days / 32;
-    let days = total_days % 32;
+    let total_days = num.div_euclid(seconds_per_day);
+    let seconds = num.rem_euclid(seconds_per_day) as u32;
 
-    // The Google epoch starts a day early.
-    let ndt = NaiveDate::from_ymd(1969, 12, 31).and_hms(0, 0, 0);
+    let months = total_days.div_euclid(32);
+    let day = total_days.rem_euclid(32) as u32;
 
-    // First, add the days...
-    let ndt = ndt + Duration::days(days);
+    let year = i32::try_from(months.div_euclid(12).checked_add(1970)?).ok()?;
+    let month = months.rem_euclid(12) as u32 + 1;
 
-    // ...then the months...
-    let ndt = plus_months(ndt, months)?;
-
-    // ...then the seconds...
-    let ndt = ndt + Duration::seconds(seconds);
-
-    Some(ndt)
+    NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+    )
 }
 
 /// Convert the given NaiveDateTime to a [Google
@@ -146,16 +509,16 @@ pub fn to_google_calendar(ndt: NaiveDateTime) -> i64 {
 /// ```
 pub fn icq(days: f64) -> Option<NaiveDateTime> {
     let intdays = days as i64;
-    if intdays > MAX_DAYS {
+    if !days.is_finite() || intdays.unsigned_abs() > MAX_DAYS as u64 {
         return None;
     }
 
     let milliseconds = ((days - (intdays as f64)) * MILLIS_PER_DAY) as i64;
 
-    NaiveDate::from_ymd(1899, 12, 30)
-        .and_hms(0, 0, 0)
-        .checked_add_signed(Duration::days(intdays))?
-        .checked_add_signed(Duration::milliseconds(milliseconds))
+    NaiveDate::from_ymd_opt(1899, 12, 30)?
+        .and_hms_opt(0, 0, 0)?
+        .checked_add_signed(Duration::try_days(intdays)?)?
+        .checked_add_signed(Duration::try_milliseconds(milliseconds)?)
 }
 
 /// Convert the given NaiveDateTime to an [ICQ](fn.icq.html) time.
@@ -168,272 +531,822 @@ pub fn icq(days: f64) -> Option<NaiveDateTime> {
 /// assert_eq!(to_icq(ndt), 39857.980208333334);
 /// ```
 pub fn to_icq(ndt: NaiveDateTime) -> f64 {
-    let diff = ndt - NaiveDate::from_ymd(1899, 12, 30).and_hms(0, 0, 0);
+    let diff = ndt
+        - NaiveDate::from_ymd_opt(1899, 12, 30)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
     diff.num_milliseconds() as f64 / MILLIS_PER_DAY
 }
 
-/// Java time is the number of milliseconds since the Unix epoch.
+define_own_epoch! {
+    /// Java time is the number of milliseconds since the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::java;
+    /// let ndt = java(1_234_567_890_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn java;
+
+    /// Convert the given NaiveDateTime to a [Java](fn.java.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_java;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_java(ndt), 1_234_567_890_000);
+    /// ```
+    pub fn to_java;
+
+    /// A [Java](fn.java.html) time, as a count of milliseconds.
+    pub struct JavaMillis;
+
+    unit: Milliseconds,
+    offset: 0,
+}
+
+/// Java `Instant` time is a pair of numbers: the seconds since the Unix
+/// epoch and the nanoseconds within the second, as carried, *e.g.*, in
+/// Kafka and Avro payloads. Unlike [Java](fn.java.html) time, this
+/// keeps nanosecond precision. Returns None if the nanoseconds make up
+/// a whole second or more.
 ///
 /// ```
-/// use epochs::java;
-/// let ndt = java(1_234_567_890_000).unwrap();
-/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// use epochs::java_instant;
+/// let ndt = java_instant(1_234_567_890, 123_456_789).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.123456789");
 /// ```
-pub fn java(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 1000, 0)
+pub fn java_instant(seconds: i64, nanos: u32) -> Option<NaiveDateTime> {
+    if nanos >= 1_000_000_000 {
+        return None;
+    }
+    DateTime::from_timestamp(seconds, nanos).map(|dt| dt.naive_utc())
 }
 
-/// Convert the given NaiveDateTime to a [Java](fn.java.html) time.
+/// Convert the given NaiveDateTime to a [Java
+/// Instant](fn.java_instant.html) time, returning the seconds and the
+/// nanoseconds. Java has no leap seconds, so one is written as the
+/// end of second 59.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_java;
-/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_java(ndt), 1_234_567_890_000);
+/// use epochs::to_java_instant;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.123456789", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_java_instant(ndt), (1_234_567_890, 123_456_789));
 /// ```
-pub fn to_java(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 1000, 0)
+pub fn to_java_instant(ndt: NaiveDateTime) -> (i64, u32) {
+    let dt = ndt.and_utc();
+    (dt.timestamp(), dt.timestamp_subsec_nanos().min(999_999_999))
 }
 
-/// Mozilla time (*e.g.*, Firefox) is the number of microseconds since
-/// the Unix epoch.
+/// Modified Julian Day numbers (as used, *e.g.*, for Firebird dates)
+/// are whole days since 1858-11-17, so this returns a date rather than
+/// a datetime.
 ///
 /// ```
-/// use epochs::mozilla;
-/// let ndt = mozilla(1_234_567_890_000_000).unwrap();
-/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// use epochs::mjd_days;
+/// let nd = mjd_days(54875).unwrap();
+/// assert_eq!(nd.to_string(), "2009-02-13");
 /// ```
-pub fn mozilla(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 1_000_000, 0)
+pub fn mjd_days(days: i64) -> Option<NaiveDate> {
+    from_days_from_ce(days, MJD_DAYS_FROM_CE)
 }
 
-/// Convert the given NaiveDateTime to a [Mozilla](fn.mozilla.html) time.
+/// Convert the given date (or NaiveDateTime, whose time of day is
+/// dropped) to a [Modified Julian Day](fn.mjd_days.html) number.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_mozilla;
+/// use epochs::to_mjd_days;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_mozilla(ndt), 1_234_567_890_000_000);
+/// assert_eq!(to_mjd_days(ndt), 54875);
+/// assert_eq!(to_mjd_days(ndt.date()), 54875);
 /// ```
-pub fn to_mozilla(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 1_000_000, 0)
+pub fn to_mjd_days(date: impl Datelike) -> i64 {
+    i64::from(date.num_days_from_ce()) - MJD_DAYS_FROM_CE
+}
+
+define_own_epoch! {
+    /// Mozilla time (*e.g.*, Firefox) is the number of microseconds since
+    /// the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::mozilla;
+    /// let ndt = mozilla(1_234_567_890_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn mozilla;
+
+    /// Convert the given NaiveDateTime to a [Mozilla](fn.mozilla.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_mozilla;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_mozilla(ndt), 1_234_567_890_000_000);
+    /// ```
+    pub fn to_mozilla;
+
+    /// A [Mozilla](fn.mozilla.html) time, as a count of microseconds.
+    pub struct MozillaMicros;
+
+    unit: Microseconds,
+    offset: 0,
 }
 
-/// Symbian time is the number of microseconds since the year 0, which
-/// is 62,167,219,200 seconds before the Unix epoch.
+/// Palm OS time (*e.g.*, the creation, modification, and backup dates
+/// in PDB and PRC headers) is an unsigned 32-bit count of seconds
+/// since 1904-01-01, which is 2,082,844,800 seconds before the Unix
+/// epoch. Some files were written with Unix times instead; see
+/// [palm_or_unix](fn.palm_or_unix.html).
 ///
 /// ```
-/// use epochs::symbian;
-/// let ndt = symbian(63_401_787_090_000_000).unwrap();
+/// use epochs::palm;
+/// let ndt = palm(3_317_412_690).unwrap();
 /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
 /// ```
-pub fn symbian(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 1_000_000, -62_167_219_200)
+pub fn palm(num: u32) -> Option<NaiveDateTime> {
+    MAC_1904.to_datetime(num.into())
 }
 
-/// Convert the given NaiveDateTime to a [Symbian](fn.symbian.html) time.
+/// Convert the given NaiveDateTime to a [Palm OS](fn.palm.html) time.
+/// Returns None if it is before 1904-01-01 or after 2040-02-06
+/// 06:28:15.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_symbian;
+/// use epochs::to_palm;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_symbian(ndt), 63_401_787_090_000_000);
+/// assert_eq!(to_palm(ndt), Some(3_317_412_690));
 /// ```
-pub fn to_symbian(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 1_000_000, -62_167_219_200)
+pub fn to_palm(ndt: NaiveDateTime) -> Option<u32> {
+    u32::try_from(MAC_1904.from_datetime(ndt)).ok()
 }
 
-/// Unix time is the number of seconds since 1970-01-01.
+/// Like [to_palm](fn.to_palm.html), but clamps times outside of the
+/// range of a u32.
 ///
 /// ```
-/// use epochs::unix;
-/// let ndt = unix(1234567890).unwrap();
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_palm_saturating;
+/// let ndt = NaiveDateTime::parse_from_str("2100-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_palm_saturating(ndt), u32::MAX);
+/// ```
+pub fn to_palm_saturating(ndt: NaiveDateTime) -> u32 {
+    MAC_1904.from_datetime(ndt).clamp(0, u32::MAX.into()) as u32
+}
+
+/// Convert a PDB or PRC header date that may be either a [Palm
+/// OS](fn.palm.html) time or a Unix time. Palm times after 1972 have
+/// the high bit set, so, as the PDB format documentation suggests, a
+/// value with the high bit set is taken to be a Palm time and one
+/// without it a signed 32-bit Unix time.
+///
+/// ```
+/// use epochs::palm_or_unix;
+/// let ndt = palm_or_unix(3_317_412_690).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// let ndt = palm_or_unix(1_234_567_890).unwrap();
 /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
 /// ```
-pub fn unix(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 1, 0)
+pub fn palm_or_unix(num: u32) -> Option<NaiveDateTime> {
+    if num & 0x8000_0000 != 0 {
+        palm(num)
+    } else {
+        unix(num.into())
+    }
+}
+
+/// QuickTime time (the creation and modification times in the `mvhd`,
+/// `tkhd`, and `mdhd` boxes of QuickTime and MP4 files) is an unsigned
+/// count of seconds since 1904-01-01, which is 2,082,844,800 seconds
+/// before the Unix epoch. Version 0 boxes store it in 32 bits and
+/// version 1 boxes in 64, so either can be passed here.
+///
+/// ```
+/// use epochs::quicktime;
+/// let ndt = quicktime(3_317_412_690).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn quicktime(num: u64) -> Option<NaiveDateTime> {
+    MAC_1904.to_datetime(i64::try_from(num).ok()?)
 }
 
-/// Convert the given NaiveDateTime to a [Unix](fn.unix.html) time.
+/// Convert the given NaiveDateTime to a [QuickTime](fn.quicktime.html)
+/// time. Returns None if it is before 1904-01-01. Times after
+/// 2040-02-06 06:28:15 need a version 1 (64-bit) box.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_unix;
+/// use epochs::to_quicktime;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_unix(ndt), 1234567890);
+/// assert_eq!(to_quicktime(ndt), Some(3_317_412_690));
+/// ```
+pub fn to_quicktime(ndt: NaiveDateTime) -> Option<u64> {
+    u64::try_from(MAC_1904.from_datetime(ndt)).ok()
+}
+
+/// Like [to_quicktime](fn.to_quicktime.html), but clamps times
+/// before 1904 to 0.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_quicktime_saturating;
+/// let ndt = NaiveDateTime::parse_from_str("1900-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_quicktime_saturating(ndt), 0);
+/// ```
+pub fn to_quicktime_saturating(ndt: NaiveDateTime) -> u64 {
+    MAC_1904.from_datetime(ndt).max(0) as u64
+}
+
+/// Rata Die numbers (as used in calendrical calculations, and by
+/// chrono itself) count days from 0001-01-01, which is day 1.
+///
+/// ```
+/// use epochs::rata_die;
+/// let nd = rata_die(733451).unwrap();
+/// assert_eq!(nd.to_string(), "2009-02-13");
+/// ```
+pub fn rata_die(days: i64) -> Option<NaiveDate> {
+    from_days_from_ce(days, 0)
+}
+
+/// Convert the given date (or NaiveDateTime, whose time of day is
+/// dropped) to a [Rata Die](fn.rata_die.html) number.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDate;
+/// use epochs::to_rata_die;
+/// let nd = NaiveDate::from_ymd_opt(2009, 2, 13).unwrap();
+/// assert_eq!(to_rata_die(nd), 733451);
+/// ```
+pub fn to_rata_die(date: impl Datelike) -> i64 {
+    i64::from(date.num_days_from_ce())
+}
+
+/// SAS dates are whole days since 1960-01-01, so this returns a date
+/// rather than a datetime.
+///
+/// ```
+/// use epochs::sas_date;
+/// let nd = sas_date(17941).unwrap();
+/// assert_eq!(nd.to_string(), "2009-02-13");
 /// ```
-pub fn to_unix(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 1, 0)
+pub fn sas_date(days: i64) -> Option<NaiveDate> {
+    from_days_from_ce(days, SAS_DAYS_FROM_CE)
 }
 
-/// UUID version 1 time ([RFC
-/// 4122](https://tools.ietf.org/html/rfc4122)) is the number of
-/// hectonanoseconds (100 ns) since 1582-10-15, which is
-/// 12,219,292,800 seconds before the Unix epoch.
+/// Convert the given date (or NaiveDateTime, whose time of day is
+/// dropped) to a [SAS date](fn.sas_date.html).
 ///
 /// ```
-/// use epochs::uuid_v1;
-/// let ndt = uuid_v1(134_538_606_900_000_000).unwrap();
+///# extern crate chrono;
+/// use chrono::NaiveDate;
+/// use epochs::to_sas_date;
+/// let nd = NaiveDate::from_ymd_opt(2009, 2, 13).unwrap();
+/// assert_eq!(to_sas_date(nd), 17941);
+/// ```
+pub fn to_sas_date(date: impl Datelike) -> i64 {
+    i64::from(date.num_days_from_ce()) - SAS_DAYS_FROM_CE
+}
+
+/// Stata `%td` dates are whole days since 1960-01-01, the same as
+/// [SAS dates](fn.sas_date.html).
+///
+/// ```
+/// use epochs::stata_date;
+/// let nd = stata_date(17941).unwrap();
+/// assert_eq!(nd.to_string(), "2009-02-13");
+/// ```
+pub fn stata_date(days: i64) -> Option<NaiveDate> {
+    sas_date(days)
+}
+
+/// Convert the given date (or NaiveDateTime, whose time of day is
+/// dropped) to a [Stata `%td`](fn.stata_date.html) date.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDate;
+/// use epochs::to_stata_date;
+/// let nd = NaiveDate::from_ymd_opt(2009, 2, 13).unwrap();
+/// assert_eq!(to_stata_date(nd), 17941);
+/// ```
+pub fn to_stata_date(date: impl Datelike) -> i64 {
+    to_sas_date(date)
+}
+
+define_own_epoch! {
+    /// Symbian time is the number of microseconds since the year 0, which
+    /// is 62,167,219,200 seconds before the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::symbian;
+    /// let ndt = symbian(63_401_787_090_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn symbian;
+
+    /// Convert the given NaiveDateTime to a [Symbian](fn.symbian.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_symbian;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_symbian(ndt), 63_401_787_090_000_000);
+    /// ```
+    pub fn to_symbian;
+
+    /// A [Symbian](fn.symbian.html) time, as a count of microseconds.
+    pub struct SymbianMicros;
+
+    unit: Microseconds,
+    offset: -62_167_219_200,
+}
+
+/// HP NonStop (Tandem) time, as returned by `JULIANTIMESTAMP`, is the
+/// number of microseconds since Julian day zero, noon on 4713-01-01 BC
+/// in the Julian calendar, which is 210,866,760,000 seconds before the
+/// Unix epoch.
+///
+/// ```
+/// use epochs::tandem;
+/// let ndt = tandem(212_101_327_890_000_000).unwrap();
 /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
 /// ```
-/// 
+pub fn tandem(num: i64) -> Option<NaiveDateTime> {
+    TANDEM.to_datetime(num)
+}
+
+/// Convert the given NaiveDateTime to a [Tandem](fn.tandem.html) time.
+/// Present-day values need more precision than an f64 has, so this
+/// works in integers throughout.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_tandem;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.000001", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_tandem(ndt), 212_101_327_890_000_001);
+/// ```
+pub fn to_tandem(ndt: NaiveDateTime) -> i64 {
+    let t = i128::from(ndt.and_utc().timestamp()) - i128::from(TANDEM.offset_seconds());
+    let micros = i128::from(ndt.and_utc().timestamp_subsec_micros());
+    // Every NaiveDateTime is within about 8.4e18 microseconds of Julian
+    // day zero, so this always fits.
+    (t * 1_000_000 + micros) as i64
+}
+
+define_own_epoch! {
+    /// Unix time is the number of seconds since 1970-01-01.
+    ///
+    /// ```
+    /// use epochs::unix;
+    /// let ndt = unix(1234567890).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn unix;
+
+    /// Convert the given NaiveDateTime to a [Unix](fn.unix.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_unix;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_unix(ndt), 1234567890);
+    /// ```
+    pub fn to_unix;
+
+    /// A [Unix](fn.unix.html) time, as a count of seconds.
+    pub struct UnixSeconds;
+
+    unit: Seconds,
+    offset: 0,
+}
+
+/// Interpret a raw 32-bit value as unsigned [Unix](fn.unix.html)
+/// seconds, as some systems (and most file formats that predate 2038
+/// worries) do; 0xfffffff0 is in 2106 this way. See also
+/// [unix_i32](fn.unix_i32.html).
+///
+/// ```
+/// use epochs::unix_u32;
+/// let ndt = unix_u32(0xffff_fff0).unwrap();
+/// assert_eq!(ndt.to_string(), "2106-02-07 06:28:00");
+/// ```
+pub fn unix_u32(num: u32) -> Option<NaiveDateTime> {
+    unix(num.into())
+}
 
-/// UUIDs typically appear in "8-4-4-4-12" strings like
-/// 
-/// &nbsp;&nbsp;&nbsp;&nbsp; ca4892ce-4f7d-11ea-b77f-2e728ce88125
-/// 
-/// where the timestamp portion is buried inside. This one is
-/// "2020-02-14 23:00:27.148155". That first 1,
-/// 
-/// &nbsp;&nbsp;&nbsp;&nbsp; ca4892ce-4f7d-**1**1ea-b77f-2e728ce88125
-/// 
-/// means it's a version 1 UUID (other versions don't have timestamps
-/// in them), so it's appropriate to take these bytes,
-/// 
-/// &nbsp;&nbsp;&nbsp;&nbsp; **ca4892ce**-**4f7d**-1**1ea**-b77f-2e728ce88125
-/// 
-/// make an integer, 0x1ea4f7dca4892ce, and
-/// perform the calculation in this module on it.
-/// 
+/// Interpret a raw 32-bit value as signed [Unix](fn.unix.html)
+/// seconds, as a 32-bit `time_t` does; 0xfffffff0 is just before 1970
+/// this way. See also [unix_u32](fn.unix_u32.html).
+///
 /// ```
-/// use epochs::uuid_v1;
-/// let ndt = uuid_v1(0x1ea4f7dca4892ce).unwrap();
-/// assert_eq!(ndt.to_string(), "2020-02-14 23:00:27.148155");
+/// use epochs::unix_i32;
+/// let ndt = unix_i32(0xffff_fff0_u32 as i32).unwrap();
+/// assert_eq!(ndt.to_string(), "1969-12-31 23:59:44");
 /// ```
-pub fn uuid_v1(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 10_000_000, -12_219_292_800)
+pub fn unix_i32(num: i32) -> Option<NaiveDateTime> {
+    unix(num.into())
 }
 
-/// Convert the given NaiveDateTime to a [UUIDv1](fn.uuid_v1.html) time.
+/// Convert the given NaiveDateTime to [unsigned 32-bit
+/// Unix](fn.unix_u32.html) seconds, or None if it is before 1970 or
+/// after 2106-02-07 06:28:15.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_uuid_v1;
+/// use epochs::to_unix_u32;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_uuid_v1(ndt), 134_538_606_900_000_000);
+/// assert_eq!(to_unix_u32(ndt), Some(1234567890));
 /// ```
-pub fn to_uuid_v1(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 10_000_000, -12_219_292_800)
+pub fn to_unix_u32(ndt: NaiveDateTime) -> Option<u32> {
+    u32::try_from(ndt.and_utc().timestamp()).ok()
 }
 
-/// Windows date time (e.g., .NET) is the number of hectonanoseconds
-/// (100 ns) since 0001-01-01, which is 62,135,596,800 seconds before
-/// the Unix epoch.
+/// Like [to_unix_u32](fn.to_unix_u32.html), but clamps times
+/// outside of 1970 to 2106.
 ///
 /// ```
-/// use epochs::windows_date;
-/// let ndt = windows_date(633_701_646_900_000_000).unwrap();
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_unix_u32_saturating;
+/// let ndt = NaiveDateTime::parse_from_str("1960-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_unix_u32_saturating(ndt), 0);
+/// ```
+pub fn to_unix_u32_saturating(ndt: NaiveDateTime) -> u32 {
+    ndt.and_utc().timestamp().clamp(0, u32::MAX.into()) as u32
+}
+
+/// Convert the given NaiveDateTime to [signed 32-bit
+/// Unix](fn.unix_i32.html) seconds, or None if it is before
+/// 1901-12-13 20:45:52 or after 2038-01-19 03:14:07.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_unix_i32;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_unix_i32(ndt), Some(1234567890));
+/// ```
+pub fn to_unix_i32(ndt: NaiveDateTime) -> Option<i32> {
+    i32::try_from(ndt.and_utc().timestamp()).ok()
+}
+
+/// Like [to_unix_i32](fn.to_unix_i32.html), but clamps times
+/// outside of 1901 to 2038.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_unix_i32_saturating;
+/// let ndt = NaiveDateTime::parse_from_str("2040-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_unix_i32_saturating(ndt), i32::MAX);
+/// ```
+pub fn to_unix_i32_saturating(ndt: NaiveDateTime) -> i32 {
+    ndt.and_utc()
+        .timestamp()
+        .clamp(i32::MIN.into(), i32::MAX.into()) as i32
+}
+
+/// A `const fn` version of [to_unix](fn.to_unix.html) which takes
+/// the fields of the date and time separately, so it can be used to
+/// compute constants. Returns None for an invalid date or time.
+///
+/// ```
+/// use epochs::{rescale, to_unix_const, Epoch};
+/// const CUTOFF: i64 = to_unix_const(2009, 2, 13, 23, 31, 30).unwrap();
+/// const CHROME_CUTOFF: i64 = rescale(CUTOFF, Epoch::Unix, Epoch::Chrome).unwrap();
+/// assert_eq!(CUTOFF, 1234567890);
+/// assert_eq!(CHROME_CUTOFF, 12_879_041_490_000_000);
+/// ```
+pub const fn to_unix_const(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+) -> Option<i64> {
+    if month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let days = days_from_civil(year as i64, month as i64, day as i64);
+    Some(((days * 24 + hour as i64) * 60 + minute as i64) * 60 + second as i64)
+}
+
+define_own_epoch! {
+    /// UUID version 1 time ([RFC
+    /// 4122](https://tools.ietf.org/html/rfc4122)) is the number of
+    /// hectonanoseconds (100 ns) since 1582-10-15, which is
+    /// 12,219,292,800 seconds before the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::uuid_v1;
+    /// let ndt = uuid_v1(134_538_606_900_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    ///
+    /// UUIDs typically appear in "8-4-4-4-12" strings like
+    ///
+    /// &nbsp;&nbsp;&nbsp;&nbsp; ca4892ce-4f7d-11ea-b77f-2e728ce88125
+    ///
+    /// where the timestamp portion is buried inside. This one is
+    /// "2020-02-14 23:00:27.148155". That first 1,
+    ///
+    /// &nbsp;&nbsp;&nbsp;&nbsp; ca4892ce-4f7d-**1**1ea-b77f-2e728ce88125
+    ///
+    /// means it's a version 1 UUID (other versions don't have timestamps
+    /// in them), so it's appropriate to take these bytes,
+    ///
+    /// &nbsp;&nbsp;&nbsp;&nbsp; **ca4892ce**-**4f7d**-1**1ea**-b77f-2e728ce88125
+    ///
+    /// make an integer, 0x1ea4f7dca4892ce, and
+    /// perform the calculation in this module on it.
+    ///
+    /// ```
+    /// use epochs::uuid_v1;
+    /// let ndt = uuid_v1(0x1ea4f7dca4892ce).unwrap();
+    /// assert_eq!(ndt.to_string(), "2020-02-14 23:00:27.148155");
+    /// ```
+    pub fn uuid_v1;
+
+    /// Convert the given NaiveDateTime to a [UUIDv1](fn.uuid_v1.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_uuid_v1;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_uuid_v1(ndt), 134_538_606_900_000_000);
+    /// ```
+    pub fn to_uuid_v1;
+
+    /// A [UUIDv1](fn.uuid_v1.html) time, as a count of hectonanoseconds.
+    pub struct UuidV1Ticks;
+
+    unit: Hectonanoseconds,
+    offset: -12_219_292_800,
+}
+
+define_own_epoch! {
+    /// Windows date time (e.g., .NET) is the number of hectonanoseconds
+    /// (100 ns) since 0001-01-01, which is 62,135,596,800 seconds before
+    /// the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::windows_date;
+    /// let ndt = windows_date(633_701_646_900_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn windows_date;
+
+    /// Convert the given NaiveDateTime to a [Windows
+    /// Date](fn.windows_date.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_windows_date;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_windows_date(ndt), 633_701_646_900_000_000);
+    /// ```
+    pub fn to_windows_date;
+
+    /// A [Windows
+    /// Date](fn.windows_date.html) time, as a count of hectonanoseconds.
+    pub struct WindowsDateTicks;
+
+    unit: Hectonanoseconds,
+    offset: -62_135_596_800,
+}
+
+define_own_epoch! {
+    /// Windows file time (e.g., NTFS) is the number of hectonanoseconds
+    /// (100 ns) since 1601-01-01, which is 11,644,473,600 seconds before
+    /// the Unix epoch.
+    ///
+    /// ```
+    /// use epochs::windows_file;
+    /// let ndt = windows_file(128_790_414_900_000_000).unwrap();
+    /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    /// ```
+    pub fn windows_file;
+
+    /// Convert the given NaiveDateTime to a [Windows
+    /// File](fn.windows_file.html) time.
+    ///
+    /// ```
+    ///# extern crate chrono;
+    /// use chrono::NaiveDateTime;
+    /// use epochs::to_windows_file;
+    /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+    /// assert_eq!(to_windows_file(ndt), 128_790_414_900_000_000);
+    /// ```
+    pub fn to_windows_file;
+
+    /// A [Windows
+    /// File](fn.windows_file.html) time, as a count of hectonanoseconds.
+    pub struct WindowsFileTime;
+
+    unit: Hectonanoseconds,
+    offset: -11_644_473_600,
+}
+
+/// Windows APIs and many file formats keep a [Windows
+/// File](fn.windows_file.html) time as a `FILETIME` structure: two
+/// 32-bit halves, `dwLowDateTime` first and then `dwHighDateTime`.
+/// Returns None if the high bit is set, which Windows doesn't allow.
+///
+/// ```
+/// use epochs::windows_file_parts;
+/// let ndt = windows_file_parts(0x3296_f500, 0x01c9_8e33).unwrap();
 /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
 /// ```
-pub fn windows_date(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 10_000_000, -62_135_596_800)
+pub fn windows_file_parts(low: u32, high: u32) -> Option<NaiveDateTime> {
+    let num = i64::try_from(u64::from(high) << 32 | u64::from(low)).ok()?;
+    windows_file(num)
 }
 
-/// Convert the given NaiveDateTime to a [Windows
-/// Date](fn.windows_date.html) time.
+/// Convert the given NaiveDateTime to a [Windows File](fn.windows_file.html)
+/// time split into its [low and high halves](fn.windows_file_parts.html).
+/// Returns None for times before 1601.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_windows_date;
+/// use epochs::to_windows_file_parts;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_windows_date(ndt), 633_701_646_900_000_000);
+/// assert_eq!(to_windows_file_parts(ndt), Some((0x3296_f500, 0x01c9_8e33)));
 /// ```
-pub fn to_windows_date(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 10_000_000, -62_135_596_800)
+pub fn to_windows_file_parts(ndt: NaiveDateTime) -> Option<(u32, u32)> {
+    let num = u64::try_from(to_windows_file(ndt)).ok()?;
+    Some((num as u32, (num >> 32) as u32))
 }
 
-/// Windows file time (e.g., NTFS) is the number of hectonanoseconds
-/// (100 ns) since 1601-01-01, which is 11,644,473,600 seconds before
-/// the Unix epoch.
+/// ZigBee time (the ZCL UTCTime type) is an unsigned 32-bit count of
+/// seconds since 2000-01-01, which is 946,684,800 seconds after the
+/// Unix epoch. Note that this is a year earlier than
+/// [Cocoa](fn.cocoa.html) time. ZigBee reserves 0xffffffff for an
+/// invalid time, so that returns None.
 ///
 /// ```
-/// use epochs::windows_file;
-/// let ndt = windows_file(128_790_414_900_000_000).unwrap();
+/// use epochs::zigbee;
+/// let ndt = zigbee(287_883_090).unwrap();
 /// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
 /// ```
-pub fn windows_file(num: i64) -> Option<NaiveDateTime> {
-    epoch2time(num, 10_000_000, -11_644_473_600)
+pub fn zigbee(num: u32) -> Option<NaiveDateTime> {
+    if num == u32::MAX {
+        return None;
+    }
+    ZIGBEE.to_datetime(num.into())
 }
 
-/// Convert the given NaiveDateTime to a [Windows
-/// File](fn.windows_file.html) time.
+/// Convert the given NaiveDateTime to a [ZigBee](fn.zigbee.html) time.
+/// Returns None if it is before 2000-01-01 or too late to fit in 32
+/// bits.
 ///
 /// ```
 ///# extern crate chrono;
 /// use chrono::NaiveDateTime;
-/// use epochs::to_windows_file;
+/// use epochs::to_zigbee;
 /// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
-/// assert_eq!(to_windows_file(ndt), 128_790_414_900_000_000);
+/// assert_eq!(to_zigbee(ndt), Some(287_883_090));
 /// ```
-pub fn to_windows_file(ndt: NaiveDateTime) -> i64 {
-    time2epoch(ndt, 10_000_000, -11_644_473_600)
+pub fn to_zigbee(ndt: NaiveDateTime) -> Option<u32> {
+    u32::try_from(ZIGBEE.from_datetime(ndt))
+        .ok()
+        .filter(|&num| num != u32::MAX)
+}
+
+/// Like [to_zigbee](fn.to_zigbee.html), but clamps times outside of
+/// the range ZigBee can represent (up to 0xfffffffe).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_zigbee_saturating;
+/// let ndt = NaiveDateTime::parse_from_str("1999-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_zigbee_saturating(ndt), 0);
+/// ```
+pub fn to_zigbee_saturating(ndt: NaiveDateTime) -> u32 {
+    ZIGBEE.from_datetime(ndt).clamp(0, (u32::MAX - 1).into()) as u32
+}
+
+/// Convert a binary fraction of a second with the given number of
+/// bits to nanoseconds, rounding down.
+fn fraction_to_nanos(fraction: u128, bits: u32) -> u32 {
+    ((fraction * 1_000_000_000) >> bits) as u32
+}
+
+/// Convert nanoseconds to a binary fraction of a second with the given
+/// number of bits, rounding up so that converting back gives the same
+/// number of nanoseconds (but never rounding up into a whole second).
+fn nanos_to_fraction(nanos: u32, bits: u32) -> u128 {
+    let fraction = ((nanos as u128) << bits).div_ceil(1_000_000_000);
+    fraction.min((1 << bits) - 1)
 }
 
 /// epoch2time adjusts the given epoch x by the given dividend d and
 /// shift s and returns the result as a chrono::NaiveDateTime.
 fn epoch2time(x: i64, d: i64, s: i64) -> Option<NaiveDateTime> {
-    let q = x / d;
-    let n = ((x % d) * (1_000_000_000 / d)) as u32;
-    let t = q.checked_add(s)?;
-    NaiveDateTime::from_timestamp_opt(t, n)
+    epoch2time_checked(x, d, s).ok()
+}
+
+/// epoch2time_checked is [epoch2time](fn.epoch2time.html), but says
+/// which stage failed.
+fn epoch2time_checked(x: i64, d: i64, s: i64) -> Result<NaiveDateTime, ConversionError> {
+    let q = x.div_euclid(d);
+    // Scale the remainder in i128, so units which don't divide a
+    // second into whole nanoseconds are rounded down rather than
+    // drifting.
+    let n = (x.rem_euclid(d) as i128 * 1_000_000_000 / d as i128) as u32;
+    let t = q.checked_add(s).ok_or(ConversionError::Offset)?;
+    DateTime::from_timestamp(t, n)
+        .map(|dt| dt.naive_utc())
+        .ok_or(ConversionError::Timestamp)
 }
 
 /// time2epoch adjusts the given chrono::NaiveDateTime ndt by the
 /// multiplier m and the shift s and returns the result as a 64-bit
-/// integer.
+/// integer, saturating if it doesn't fit.
 fn time2epoch(ndt: NaiveDateTime, m: i64, s: i64) -> i64 {
-    let n = ndt.timestamp_subsec_nanos() as f64;
-    let q = n / 1_000_000_000.0;
-    let t = ndt.timestamp() as f64;
-    let sf = s as f64;
-    let mf = m as f64;
-    (mf * (t + q - sf)) as i64
+    time2epoch_checked(ndt, m, s).unwrap_or_else(|_| {
+        if ndt.and_utc().timestamp() < s {
+            i64::MIN
+        } else {
+            i64::MAX
+        }
+    })
 }
 
-/// This function appears in the chrono documentation, but is not
-/// actually provided as part of the package.
-///
-/// https://lifthrasiir.github.io/rust-chrono/chrono/naive/date/struct.NaiveDate.html#method.day
-///
-/// Combined with NaiveDate::pred, one can determine the number of
-/// days in a particular month.
-fn ndays_in_month(year: i32, month: u32) -> Option<i64> {
-    // the first day of the next month...
-    let (y, m) = if month == 12 {
-        (year + 1, 1)
-    } else {
-        (year, month + 1)
-    };
-    let d = NaiveDate::from_ymd_opt(y, m, 1)?;
-
-    // ...is preceded by the last day of the original month
-    Some(d.pred().day() as i64)
+/// time2epoch_checked is [time2epoch](fn.time2epoch.html), but says
+/// which stage overflowed rather than saturating. Fractions of a unit
+/// are rounded down.
+fn time2epoch_checked(ndt: NaiveDateTime, m: i64, s: i64) -> Result<i64, ConversionError> {
+    let dt = ndt.and_utc();
+    // Work in i128 and only narrow at the end, since the whole seconds
+    // alone can overflow when the fraction would bring the total back
+    // in range (*e.g.*, at i64::MIN ticks of Windows file time).
+    let t = i128::from(dt.timestamp()) - i128::from(s);
+    // A leap second is counted as the end of the second before.
+    let n = i128::from(dt.timestamp_subsec_nanos().min(999_999_999));
+    let units = t * i128::from(m) + n * i128::from(m) / 1_000_000_000;
+    i64::try_from(units).map_err(|_| {
+        if i64::try_from(t).is_err() {
+            ConversionError::Offset
+        } else {
+            ConversionError::Scale
+        }
+    })
 }
 
-/// Add a month to the given NaiveDateTime by finding out how many
-/// days are in the current month and adding that many days.
-fn plus_month(ndt: NaiveDateTime) -> Option<NaiveDateTime> {
-    let days = ndays_in_month(ndt.year(), ndt.month())?;
-    Some(ndt + Duration::days(days))
+/// The date the given number of days after the day with the given
+/// number of days from the common era (as chrono counts them).
+fn from_days_from_ce(days: i64, offset: i64) -> Option<NaiveDate> {
+    let days = i32::try_from(days.checked_add(offset)?).ok()?;
+    NaiveDate::from_num_days_from_ce_opt(days)
 }
 
-/// Add the given number of months to the given NaiveDateTime.
-fn plus_months(ndt: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
-    let years = (months / 12) as i32;
-    let months = months % 12;
-
-    let mut ndt = ndt.with_year(ndt.year() + years)?;
-
-    for _i in 0..months {
-        ndt = plus_month(ndt)?;
+/// The number of days in the given month of the proleptic Gregorian
+/// calendar.
+const fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
     }
-    Some(ndt)
+}
+
+/// The number of days between 1970-01-01 and the given date, using
+/// Howard Hinnant's days_from_civil algorithm
+/// (http://howardhinnant.github.io/date_algorithms.html).
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
 }
 
 #[cfg(test)]
@@ -449,7 +1362,10 @@ mod tests {
     }
     #[test]
     fn to_apfs_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_apfs(ndt), 1234567890000000000);
     }
 
@@ -465,10 +1381,67 @@ mod tests {
     }
     #[test]
     fn to_chrome_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_chrome(ndt), 12879041490000000);
     }
 
+    #[test]
+    fn cics_run() {
+        let ndt = cics(3443556690000).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn cics_with_millis() {
+        let ndt = cics(3476703016559).unwrap();
+        assert_eq!(ndt.to_string(), "2010-03-04 14:50:16.559");
+    }
+    #[test]
+    fn to_cics_run() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        assert_eq!(to_cics(ndt), 3443556690000);
+    }
+
+    #[test]
+    fn cocoa_f64_keeps_fractions() {
+        let a = cocoa_f64(700_000_000.000_001).unwrap();
+        let b = cocoa_f64(700_000_000.000_002).unwrap();
+        assert!(a < b);
+        assert_eq!(
+            cocoa_f64(-0.25).unwrap().to_string(),
+            "2000-12-31 23:59:59.750"
+        );
+        assert_eq!(to_cocoa_f64(cocoa_f64(-0.25).unwrap()), -0.25);
+        assert_eq!(cocoa_f64(256260690.0), cocoa(256260690));
+    }
+    #[test]
+    fn cocoa_f64_out_of_range() {
+        assert!(cocoa_f64(f64::NAN).is_none());
+        assert!(cocoa_f64(f64::INFINITY).is_none());
+        assert!(cocoa_f64(1e300).is_none());
+    }
+    #[test]
+    fn bplist_date_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_micro_opt(12, 34, 56, 789_012)
+            .unwrap();
+        assert_eq!(bplist_date(&to_bplist_date(ndt)), Some(ndt));
+        // Dates before 2001 are negative.
+        let ndt = NaiveDate::from_ymd_opt(1999, 12, 31)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            bplist_date(&(-86_400.0 * 367.0f64).to_be_bytes()),
+            Some(ndt)
+        );
+    }
     #[test]
     fn cocoa_run() {
         let ndt = cocoa(256260690).unwrap();
@@ -476,10 +1449,73 @@ mod tests {
     }
     #[test]
     fn to_cocoa_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_cocoa(ndt), 256260690);
     }
 
+    #[test]
+    fn filemaker_run() {
+        let ndt = filemaker(63370164690).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn filemaker_limits() {
+        assert_eq!(filemaker(0).unwrap().to_string(), "0001-01-01 00:00:00");
+        assert_eq!(
+            filemaker(126227807999).unwrap().to_string(),
+            "4000-12-31 23:59:59"
+        );
+        assert!(filemaker(-1).is_none());
+        assert!(filemaker(126227808000).is_none());
+    }
+    #[test]
+    fn to_filemaker_run() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        assert_eq!(to_filemaker(ndt), Some(63370164690));
+    }
+    #[test]
+    fn to_filemaker_limits() {
+        let last = NaiveDate::from_ymd_opt(4000, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert_eq!(to_filemaker(last), Some(FILEMAKER_MAX));
+        assert_eq!(to_filemaker(last + Duration::seconds(1)), None);
+        let first = NaiveDate::from_ymd_opt(1, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(to_filemaker(first), Some(0));
+        assert_eq!(to_filemaker(first - Duration::seconds(1)), None);
+    }
+
+    #[test]
+    fn garmin_fit_run() {
+        let ndt = garmin_fit(0).unwrap();
+        assert_eq!(ndt.to_string(), "1989-12-31 00:00:00");
+        let ndt = garmin_fit(u32::MAX).unwrap();
+        assert_eq!(ndt.to_string(), "2126-02-06 06:28:15");
+    }
+    #[test]
+    fn to_garmin_fit_run() {
+        let ndt = NaiveDate::from_ymd_opt(2126, 2, 6)
+            .unwrap()
+            .and_hms_opt(6, 28, 15)
+            .unwrap();
+        assert_eq!(to_garmin_fit(ndt), Some(u32::MAX));
+        assert_eq!(to_garmin_fit(ndt + Duration::seconds(1)), None);
+        let ndt = NaiveDate::from_ymd_opt(1989, 12, 30)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert_eq!(to_garmin_fit(ndt), None);
+    }
     #[test]
     fn google_calendar_run() {
         let ndt = google_calendar(1297899090).unwrap();
@@ -488,11 +1524,59 @@ mod tests {
     #[test]
     fn google_calendar_too_big() {
         let obs = google_calendar(12978990900000);
-        assert_eq!(obs.is_none(), true);
+        assert!(obs.is_none());
+    }
+    #[test]
+    fn google_calendar_gaps() {
+        // Day 0, and February 29th in a common year.
+        assert!(google_calendar(0).is_none());
+        assert!(google_calendar(((39 * 12 + 1) * 32 + 29) * 86_400).is_none());
+        assert_eq!(
+            google_calendar(((38 * 12 + 1) * 32 + 29) * 86_400)
+                .unwrap()
+                .to_string(),
+            "2008-02-29 00:00:00"
+        );
+        assert_eq!(google_calendar(86_399), None);
+        assert_eq!(
+            google_calendar(86_400).unwrap().to_string(),
+            "1970-01-01 00:00:00"
+        );
+    }
+    #[test]
+    fn google_calendar_before_1970() {
+        let ndt = NaiveDate::from_ymd_opt(1969, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        let num = to_google_calendar(ndt);
+        assert_eq!(num, -1);
+        assert_eq!(google_calendar(num), Some(ndt));
+    }
+    #[test]
+    fn google_calendar_round_trips() {
+        // A cheap linear congruential generator, for a spread of
+        // numbers near and far.
+        let mut x: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..100_000 {
+            x = x.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            for num in [x as i64 >> 20, x as i64 >> 32, x as i64 >> 40] {
+                if let Some(ndt) = google_calendar(num) {
+                    assert_eq!(to_google_calendar(ndt), num);
+                }
+            }
+        }
+        for ndt in [NaiveDateTime::MIN, NaiveDateTime::MAX] {
+            let ndt = ndt.with_nanosecond(0).unwrap();
+            assert_eq!(google_calendar(to_google_calendar(ndt)), Some(ndt));
+        }
     }
     #[test]
     fn to_google_calendar_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_google_calendar(ndt), 1297899090);
     }
 
@@ -504,12 +1588,12 @@ mod tests {
     #[test]
     fn icq_too_big() {
         let obs = icq(398570000.980209);
-        assert_eq!(obs.is_none(), true);
+        assert!(obs.is_none());
     }
     #[test]
     fn icq_way_too_big() {
         let obs = icq(123456789012.0);
-        assert_eq!(obs.is_none(), true);
+        assert!(obs.is_none());
     }
     #[test]
     fn icq_frac() {
@@ -518,12 +1602,18 @@ mod tests {
     }
     #[test]
     fn to_icq_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert!(to_icq(ndt) - 39857.980209 < 1e-6);
     }
     #[test]
     fn to_icq_frac() {
-        let ndt = NaiveDate::from_ymd(2012, 5, 27).and_hms_milli(6, 36, 17, 971);
+        let ndt = NaiveDate::from_ymd_opt(2012, 5, 27)
+            .unwrap()
+            .and_hms_milli_opt(6, 36, 17, 971)
+            .unwrap();
         assert!(to_icq(ndt) - 41056.275208 < 1e-6);
     }
 
@@ -534,10 +1624,70 @@ mod tests {
     }
     #[test]
     fn to_java_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_java(ndt), 1234567890000);
     }
 
+    #[test]
+    fn java_instant_run() {
+        let ndt = java_instant(-1, 999_999_999).unwrap();
+        assert_eq!(ndt.to_string(), "1969-12-31 23:59:59.999999999");
+        assert!(java_instant(0, 1_000_000_000).is_none());
+        assert!(java_instant(i64::MAX, 0).is_none());
+    }
+    #[test]
+    fn to_java_instant_run() {
+        let ndt = NaiveDate::from_ymd_opt(1969, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1)
+            .unwrap();
+        assert_eq!(to_java_instant(ndt), (-1, 1));
+        assert_eq!(java_instant(-1, 1), Some(ndt));
+        let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1_500_000_000)
+            .unwrap();
+        assert_eq!(to_java_instant(leap), (1_483_228_799, 999_999_999));
+    }
+    #[test]
+    fn mjd_days_run() {
+        assert_eq!(mjd_days(0).unwrap().to_string(), "1858-11-17");
+        assert_eq!(mjd_days(40587).unwrap().to_string(), "1970-01-01");
+        assert_eq!(mjd_days(-1).unwrap().to_string(), "1858-11-16");
+    }
+    #[test]
+    fn mjd_days_too_big() {
+        assert!(mjd_days(i64::MAX).is_none());
+        assert!(mjd_days(i64::MIN).is_none());
+        assert!(mjd_days(i64::from(i32::MAX)).is_none());
+    }
+    #[test]
+    fn to_mjd_days_run() {
+        let nd = NaiveDate::from_ymd_opt(1858, 11, 17).unwrap();
+        assert_eq!(to_mjd_days(nd), 0);
+        assert_eq!(to_mjd_days(nd.pred_opt().unwrap()), -1);
+        assert_eq!(to_mjd_days(nd.and_hms_opt(23, 59, 59).unwrap()), 0);
+    }
+    #[test]
+    fn rata_die_run() {
+        let nd = rata_die(1).unwrap();
+        assert_eq!(nd.to_string(), "0001-01-01");
+        assert_eq!(to_rata_die(nd), 1);
+        assert_eq!(rata_die(0).unwrap().to_string(), "0000-12-31");
+        assert!(rata_die(i64::MAX).is_none());
+    }
+    #[test]
+    fn sas_date_run() {
+        let nd = sas_date(0).unwrap();
+        assert_eq!(nd.to_string(), "1960-01-01");
+        assert_eq!(sas_date(-1).unwrap().to_string(), "1959-12-31");
+        // The time of day is dropped.
+        assert_eq!(to_sas_date(nd.and_hms_opt(23, 59, 59).unwrap()), 0);
+        assert_eq!(stata_date(-1), sas_date(-1));
+    }
     #[test]
     fn mozilla_run() {
         let ndt = mozilla(1234567890000000).unwrap();
@@ -545,10 +1695,63 @@ mod tests {
     }
     #[test]
     fn to_mozilla_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_mozilla(ndt), 1234567890000000);
     }
 
+    #[test]
+    fn palm_run() {
+        let ndt = palm(0).unwrap();
+        assert_eq!(ndt.to_string(), "1904-01-01 00:00:00");
+        let ndt = palm(u32::MAX).unwrap();
+        assert_eq!(ndt.to_string(), "2040-02-06 06:28:15");
+    }
+    #[test]
+    fn palm_or_unix_run() {
+        let ndt = palm_or_unix(0x8000_0000).unwrap();
+        assert_eq!(ndt.to_string(), "1972-01-19 03:14:08");
+        let ndt = palm_or_unix(0x7fff_ffff).unwrap();
+        assert_eq!(ndt.to_string(), "2038-01-19 03:14:07");
+        let ndt = palm_or_unix(0).unwrap();
+        assert_eq!(ndt.to_string(), "1970-01-01 00:00:00");
+    }
+    #[test]
+    fn to_palm_run() {
+        let ndt = NaiveDate::from_ymd_opt(1970, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(to_palm(ndt), Some(2_082_844_800));
+        let ndt = NaiveDate::from_ymd_opt(2040, 2, 6)
+            .unwrap()
+            .and_hms_opt(6, 28, 16)
+            .unwrap();
+        assert_eq!(to_palm(ndt), None);
+    }
+    #[test]
+    fn quicktime_run() {
+        let ndt = quicktime(0).unwrap();
+        assert_eq!(ndt.to_string(), "1904-01-01 00:00:00");
+        let ndt = quicktime(u32::MAX.into()).unwrap();
+        assert_eq!(ndt.to_string(), "2040-02-06 06:28:15");
+        assert!(quicktime(u64::MAX).is_none());
+    }
+    #[test]
+    fn to_quicktime_run() {
+        let ndt = NaiveDate::from_ymd_opt(1970, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(to_quicktime(ndt), Some(2_082_844_800));
+        let ndt = NaiveDate::from_ymd_opt(1903, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert_eq!(to_quicktime(ndt), None);
+    }
     #[test]
     fn symbian_run() {
         let ndt = symbian(63401787090000000).unwrap();
@@ -556,10 +1759,38 @@ mod tests {
     }
     #[test]
     fn to_symbian_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_symbian(ndt), 63401787090000000);
     }
 
+    #[test]
+    fn tandem_run() {
+        let ndt = tandem(0).unwrap();
+        assert_eq!(ndt.to_string(), "-4713-11-24 12:00:00");
+        let ndt = tandem(210_866_760_000_000_000).unwrap();
+        assert_eq!(ndt.to_string(), "1970-01-01 00:00:00");
+    }
+    #[test]
+    fn to_tandem_run() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_micro_opt(23, 31, 30, 654_321)
+            .unwrap();
+        assert_eq!(to_tandem(ndt), 212_101_327_890_654_321);
+        assert_eq!(tandem(to_tandem(ndt)), Some(ndt));
+    }
+    #[test]
+    fn to_tandem_extremes() {
+        let max = NaiveDateTime::MAX.with_nanosecond(999_999_000).unwrap();
+        assert_eq!(tandem(to_tandem(max)), Some(max));
+        assert_eq!(
+            tandem(to_tandem(NaiveDateTime::MIN)),
+            Some(NaiveDateTime::MIN)
+        );
+    }
     #[test]
     fn unix_run() {
         let ndt = unix(1234567890).unwrap();
@@ -572,10 +1803,66 @@ mod tests {
     }
     #[test]
     fn to_unix_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_unix(ndt), 1234567890);
     }
 
+    #[test]
+    fn unix_32_bit_interpretations() {
+        let raw = 0xffff_fff0_u32;
+        assert_eq!(unix_u32(raw).unwrap().to_string(), "2106-02-07 06:28:00");
+        assert_eq!(
+            unix_i32(raw as i32).unwrap().to_string(),
+            "1969-12-31 23:59:44"
+        );
+        assert_eq!(unix_u32(0x7fff_ffff), unix_i32(i32::MAX));
+    }
+    #[test]
+    fn to_unix_32_bit_limits() {
+        let ndt = unix_i32(i32::MAX).unwrap();
+        assert_eq!(to_unix_i32(ndt), Some(i32::MAX));
+        assert_eq!(to_unix_i32(ndt + Duration::seconds(1)), None);
+        assert_eq!(to_unix_u32(ndt + Duration::seconds(1)), Some(1 << 31));
+        let ndt = unix_i32(i32::MIN).unwrap();
+        assert_eq!(to_unix_i32(ndt), Some(i32::MIN));
+        assert_eq!(to_unix_u32(ndt), None);
+        let ndt = unix_u32(u32::MAX).unwrap();
+        assert_eq!(to_unix_u32(ndt), Some(u32::MAX));
+        assert_eq!(to_unix_u32(ndt + Duration::seconds(1)), None);
+    }
+    #[test]
+    fn to_unix_const_matches_to_unix() {
+        for &(y, m, d) in &[
+            (2009, 2, 13),
+            (1970, 1, 1),
+            (1600, 2, 29),
+            (-4713, 11, 24),
+            (9999, 12, 31),
+        ] {
+            let ndt = NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(23, 31, 30)
+                .unwrap();
+            assert_eq!(to_unix_const(y, m, d, 23, 31, 30), Some(to_unix(ndt)));
+        }
+    }
+    #[test]
+    fn to_unix_const_invalid() {
+        assert_eq!(to_unix_const(1900, 2, 29, 0, 0, 0), None);
+        assert_eq!(to_unix_const(2009, 13, 1, 0, 0, 0), None);
+        assert_eq!(to_unix_const(2009, 2, 13, 24, 0, 0), None);
+    }
+
+    #[test]
+    fn unix_seconds_run() {
+        let ndt = UnixSeconds(1234567890).to_datetime().unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+        assert_eq!(i64::from(UnixSeconds::from_datetime(ndt)), 1234567890);
+    }
+
     #[test]
     fn uuid_run() {
         let ndt = uuid_v1(134538606900000000).unwrap();
@@ -588,7 +1875,10 @@ mod tests {
     }
     #[test]
     fn to_uuid_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_uuid_v1(ndt), 134538606900000000);
     }
 
@@ -604,23 +1894,130 @@ mod tests {
     }
     #[test]
     fn to_windows_date_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_windows_date(ndt), 633701646900000000);
     }
 
+    #[test]
+    fn windows_file_parts_order() {
+        let ndt = windows_file(0x0000_0001_0000_0000).unwrap();
+        assert_eq!(windows_file_parts(0, 1), Some(ndt));
+        let ndt = windows_file_parts(0x3b9a_ca05, 2).unwrap();
+        assert_eq!(to_windows_file_parts(ndt), Some((0x3b9a_ca05, 2)));
+        let ndt = windows_file_parts(0, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1601-01-01 00:00:00");
+        assert_eq!(to_windows_file_parts(ndt), Some((0, 0)));
+        assert!(windows_file_parts(0, 0x8000_0000).is_none());
+        let ndt = NaiveDate::from_ymd_opt(1600, 12, 31)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert!(to_windows_file_parts(ndt).is_none());
+    }
     #[test]
     fn windows_file_run() {
         let ndt = windows_file(128790414900000000).unwrap();
         assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
     }
     #[test]
+    fn saturating_inverses() {
+        let early = NaiveDate::from_ymd_opt(-100, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let late = NaiveDate::from_ymd_opt(100_000, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(to_filemaker_saturating(early), 0);
+        assert_eq!(to_filemaker_saturating(late), FILEMAKER_MAX);
+        assert_eq!(to_garmin_fit_saturating(late), u32::MAX);
+        assert_eq!(to_palm_saturating(early), 0);
+        assert_eq!(to_quicktime_saturating(late), to_quicktime(late).unwrap());
+        assert_eq!(to_unix_u32_saturating(late), u32::MAX);
+        assert_eq!(to_unix_i32_saturating(early), i32::MIN);
+        assert_eq!(to_zigbee_saturating(late), u32::MAX - 1);
+        let ndt = unix(1_234_567_890).unwrap();
+        assert_eq!(to_unix_i32_saturating(ndt), 1_234_567_890);
+        assert_eq!(Some(to_zigbee_saturating(ndt)), to_zigbee(ndt));
+    }
+    #[test]
+    fn newtype_arithmetic() {
+        let t = WindowsFileTime(128_790_414_900_000_000);
+        assert_eq!(t.add_ticks(10), WindowsFileTime(128_790_414_900_000_010));
+        assert_eq!(
+            t + Duration::seconds(1),
+            WindowsFileTime(128_790_414_910_000_000)
+        );
+        assert_eq!(
+            t - Duration::nanoseconds(150),
+            WindowsFileTime(128_790_414_899_999_999)
+        );
+        assert_eq!(ChromeMicros(0).add_micros(-1), ChromeMicros(-1));
+        assert_eq!(UnixSeconds(i64::MAX).checked_add_seconds(1), None);
+        assert_eq!(ApfsNanos(0).checked_add_duration(Duration::MAX), None);
+        assert_eq!(
+            ApfsNanos(i64::MIN).checked_sub_duration(Duration::nanoseconds(1)),
+            None
+        );
+    }
+    #[test]
+    fn newtype_arithmetic_saturates() {
+        assert_eq!(ChromeMicros(i64::MAX).add_micros(1), ChromeMicros(i64::MAX));
+        assert_eq!(UnixSeconds(i64::MIN).add_seconds(-1), UnixSeconds(i64::MIN));
+    }
+    #[test]
+    #[should_panic(expected = "overflow when adding duration")]
+    fn newtype_arithmetic_overflow() {
+        let _ = ChromeMicros(i64::MAX) + Duration::microseconds(1);
+    }
+    #[test]
+    fn windows_file_time_run() {
+        let ndt = WindowsFileTime::from(128790414900000000)
+            .to_datetime()
+            .unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+        assert_eq!(
+            WindowsFileTime::from_datetime(ndt),
+            WindowsFileTime(128790414900000000)
+        );
+    }
+    #[test]
     fn windows_file_micros() {
         let ndt = windows_file(0x1cabbaa00ca9000).unwrap();
         assert_eq!(ndt.to_string(), "2010-03-04 14:50:16.559001600");
     }
     #[test]
     fn to_windows_file_run() {
-        let ndt = NaiveDate::from_ymd(2009, 2, 13).and_hms(23, 31, 30);
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
         assert_eq!(to_windows_file(ndt), 128790414900000000);
     }
+    #[test]
+    fn zigbee_run() {
+        let ndt = zigbee(0).unwrap();
+        assert_eq!(ndt.to_string(), "2000-01-01 00:00:00");
+        let ndt = zigbee(u32::MAX - 1).unwrap();
+        assert_eq!(ndt.to_string(), "2136-02-07 06:28:14");
+        assert!(zigbee(u32::MAX).is_none());
+    }
+    #[test]
+    fn to_zigbee_run() {
+        let ndt = NaiveDate::from_ymd_opt(2001, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(to_zigbee(ndt), Some(31_622_400));
+        assert_eq!(to_zigbee(ndt - Duration::days(367)), None);
+        let ndt = NaiveDate::from_ymd_opt(2136, 2, 7)
+            .unwrap()
+            .and_hms_opt(6, 28, 15)
+            .unwrap();
+        assert_eq!(to_zigbee(ndt), None);
+    }
 }
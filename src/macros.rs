@@ -0,0 +1,305 @@
+//! The macro for defining simple epochs.
+
+/// Define a conversion pair and a newtype for an epoch which counts a
+/// fixed [Unit](enum.Unit.html) from an origin some number of seconds
+/// after (or, if negative, before) the Unix epoch. This is how the
+/// crate defines most of its own epochs, and it works just as well in
+/// other crates.
+///
+/// The doc comments given for each item are passed through, so
+/// doctests can go there as usual. The newtype gets `to_datetime` and
+/// `from_datetime` methods, conversions to and from i64 and
+/// NaiveDateTime (saturating one way, like `from_datetime`, and
+/// fallible the other), and an `EPOCH` constant holding the equivalent
+/// [CustomEpoch](struct.CustomEpoch.html). With the `clock` feature (on
+/// by default), it also gets `now` and `to_local` methods.
+///
+/// This crate's own epochs defined this way are also variants of
+/// [Epoch](enum.Epoch.html), whose conversions come from the newtype,
+/// so only the variant's name is given anywhere else. An enum cannot
+/// gain variants from other crates, though, so epochs defined there are
+/// not; their `EPOCH` constant works anywhere a CustomEpoch does
+/// instead.
+///
+/// It also gets arithmetic in its own units, named for the unit
+/// (*e.g.*, `add_micros` for microseconds, `add_ticks` for
+/// hectonanoseconds), which saturate, and with chrono Durations, which
+/// are truncated to whole units. The `+` and `-` operators panic on
+/// overflow, like those of the integer types; the `checked_` methods
+/// return None instead.
+///
+/// ```
+/// epochs::define_epoch! {
+///     /// Centiseconds since 1995-06-01.
+///     pub fn acme;
+///
+///     /// Convert the given NaiveDateTime to an Acme time.
+///     pub fn to_acme;
+///
+///     /// An Acme time.
+///     pub struct AcmeTime;
+///
+///     unit: Centiseconds,
+///     offset: 801_964_800,
+/// }
+///
+/// let ndt = acme(43_260_309_000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(to_acme(ndt), 43_260_309_000);
+/// assert_eq!(AcmeTime::from_datetime(ndt), AcmeTime(43_260_309_000));
+/// assert_eq!(chrono::NaiveDateTime::try_from(AcmeTime(43_260_309_000)), Ok(ndt));
+///
+/// let later = AcmeTime(43_260_309_000).add_centiseconds(150);
+/// assert_eq!(later, AcmeTime(43_260_309_150));
+/// assert_eq!(later - chrono::Duration::milliseconds(1500), AcmeTime(43_260_309_000));
+/// ```
+#[macro_export]
+macro_rules! define_epoch {
+    (
+        $(#[$from_meta:meta])*
+        pub fn $name:ident;
+
+        $(#[$to_meta:meta])*
+        pub fn $to_name:ident;
+
+        $(#[$type_meta:meta])*
+        pub struct $newtype:ident;
+
+        unit: $unit:ident,
+        offset: $offset:expr $(,)?
+    ) => {
+        $(#[$from_meta])*
+        pub fn $name(num: i64) -> Option<$crate::__private::NaiveDateTime> {
+            $newtype::EPOCH.to_datetime(num)
+        }
+
+        $(#[$to_meta])*
+        pub fn $to_name(ndt: $crate::__private::NaiveDateTime) -> i64 {
+            $newtype::EPOCH.from_datetime(ndt)
+        }
+
+        $(#[$type_meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $newtype(pub i64);
+
+        impl $newtype {
+            /// The epoch this type counts in.
+            pub const EPOCH: $crate::CustomEpoch =
+                $crate::CustomEpoch::new($crate::Unit::$unit, $offset);
+
+            /// Convert this time to a NaiveDateTime.
+            pub fn to_datetime(self) -> Option<$crate::__private::NaiveDateTime> {
+                Self::EPOCH.to_datetime(self.0)
+            }
+
+            /// Convert the given NaiveDateTime to this type.
+            pub fn from_datetime(ndt: $crate::__private::NaiveDateTime) -> Self {
+                $newtype(Self::EPOCH.from_datetime(ndt))
+            }
+
+            /// Add the given Duration, truncated to whole units,
+            /// returning None on overflow.
+            pub fn checked_add_duration(self, d: $crate::__private::Duration) -> Option<Self> {
+                let units = Self::EPOCH.unit().from_duration(d)?;
+                self.0.checked_add(units).map($newtype)
+            }
+
+            /// Subtract the given Duration, truncated to whole units,
+            /// returning None on overflow.
+            pub fn checked_sub_duration(self, d: $crate::__private::Duration) -> Option<Self> {
+                let units = Self::EPOCH.unit().from_duration(d)?;
+                self.0.checked_sub(units).map($newtype)
+            }
+        }
+
+        $crate::__unit_methods!($newtype, $unit);
+        $crate::__clock_methods!($newtype);
+
+        impl ::std::ops::Add<$crate::__private::Duration> for $newtype {
+            type Output = Self;
+
+            fn add(self, d: $crate::__private::Duration) -> Self {
+                self.checked_add_duration(d)
+                    .expect("overflow when adding duration")
+            }
+        }
+
+        impl ::std::ops::Sub<$crate::__private::Duration> for $newtype {
+            type Output = Self;
+
+            fn sub(self, d: $crate::__private::Duration) -> Self {
+                self.checked_sub_duration(d)
+                    .expect("overflow when subtracting duration")
+            }
+        }
+
+        impl From<i64> for $newtype {
+            fn from(num: i64) -> Self {
+                $newtype(num)
+            }
+        }
+
+        impl From<$newtype> for i64 {
+            fn from(t: $newtype) -> Self {
+                t.0
+            }
+        }
+
+        impl From<$crate::__private::NaiveDateTime> for $newtype {
+            fn from(ndt: $crate::__private::NaiveDateTime) -> Self {
+                Self::from_datetime(ndt)
+            }
+        }
+
+        impl TryFrom<$newtype> for $crate::__private::NaiveDateTime {
+            type Error = $crate::ConversionError;
+
+            fn try_from(t: $newtype) -> Result<Self, Self::Error> {
+                $newtype::EPOCH.try_to_datetime(t.0)
+            }
+        }
+    };
+}
+
+/// Define one of this crate's own epochs with
+/// [define_epoch!](macro.define_epoch.html). With the `diesel` feature,
+/// the newtype also derives Diesel's traits for BigInt columns, which
+/// only this crate can do, since the calling crate need not depend on
+/// `diesel` itself.
+macro_rules! define_own_epoch {
+    (
+        $(#[$from_meta:meta])*
+        pub fn $name:ident;
+
+        $(#[$to_meta:meta])*
+        pub fn $to_name:ident;
+
+        $(#[$type_meta:meta])*
+        pub struct $newtype:ident;
+
+        unit: $unit:ident,
+        offset: $offset:expr $(,)?
+    ) => {
+        define_epoch! {
+            $(#[$from_meta])*
+            pub fn $name;
+
+            $(#[$to_meta])*
+            pub fn $to_name;
+
+            $(#[$type_meta])*
+            #[cfg_attr(
+                feature = "diesel",
+                derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+                diesel(sql_type = diesel::sql_types::BigInt)
+            )]
+            pub struct $newtype;
+
+            unit: $unit,
+            offset: $offset,
+        }
+    };
+}
+
+/// Call the given macro with every newtype this crate defines with
+/// [define_epoch!](macro.define_epoch.html), for the impls of the
+/// optional database and serialization features.
+#[cfg(any(
+    feature = "diesel",
+    feature = "rusqlite",
+    feature = "serde",
+    feature = "sqlx"
+))]
+macro_rules! for_each_newtype {
+    ($mac:ident) => {
+        $mac!(
+            ApfsNanos,
+            ChromeMicros,
+            CicsMillis,
+            CocoaSeconds,
+            JavaMillis,
+            MozillaMicros,
+            SymbianMicros,
+            UnixSeconds,
+            UuidV1Ticks,
+            WindowsDateTicks,
+            WindowsFileTime
+        );
+    };
+}
+
+/// Define the add methods named for the unit of a
+/// [define_epoch!](macro.define_epoch.html) newtype.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __unit_methods {
+    ($newtype:ident, Seconds) => {
+        $crate::__unit_methods!(@named $newtype, add_seconds, checked_add_seconds, "seconds");
+    };
+    ($newtype:ident, Deciseconds) => {
+        $crate::__unit_methods!(@named $newtype, add_deciseconds, checked_add_deciseconds, "deciseconds");
+    };
+    ($newtype:ident, Centiseconds) => {
+        $crate::__unit_methods!(@named $newtype, add_centiseconds, checked_add_centiseconds, "centiseconds");
+    };
+    ($newtype:ident, Milliseconds) => {
+        $crate::__unit_methods!(@named $newtype, add_millis, checked_add_millis, "milliseconds");
+    };
+    ($newtype:ident, Microseconds) => {
+        $crate::__unit_methods!(@named $newtype, add_micros, checked_add_micros, "microseconds");
+    };
+    ($newtype:ident, Hectonanoseconds) => {
+        $crate::__unit_methods!(@named $newtype, add_ticks, checked_add_ticks, "hectonanosecond ticks");
+    };
+    ($newtype:ident, Nanoseconds) => {
+        $crate::__unit_methods!(@named $newtype, add_nanos, checked_add_nanos, "nanoseconds");
+    };
+    (@named $newtype:ident, $add:ident, $checked_add:ident, $what:literal) => {
+        impl $newtype {
+            #[doc = concat!("Add the given number of ", $what, ", saturating at the limits of i64.")]
+            pub fn $add(self, n: i64) -> Self {
+                $newtype(self.0.saturating_add(n))
+            }
+
+            #[doc = concat!("Add the given number of ", $what, ", returning None on overflow.")]
+            pub fn $checked_add(self, n: i64) -> Option<Self> {
+                self.0.checked_add(n).map($newtype)
+            }
+        }
+    };
+}
+
+/// Define the methods of a [define_epoch!](macro.define_epoch.html)
+/// newtype that need the system clock or time zone. Which version of
+/// this macro exists depends on the features of this crate, not those
+/// of the crate calling define_epoch!.
+#[cfg(feature = "clock")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __clock_methods {
+    ($newtype:ident) => {
+        impl $newtype {
+            /// The current time (in UTC) as this type.
+            pub fn now() -> Self {
+                Self::from_datetime($crate::__private::Utc::now().naive_utc())
+            }
+
+            /// Convert this time to a DateTime in the local time zone.
+            pub fn to_local(self) -> Option<$crate::__private::DateTime<$crate::__private::Local>> {
+                Some(
+                    self.to_datetime()?
+                        .and_utc()
+                        .with_timezone(&$crate::__private::Local),
+                )
+            }
+        }
+    };
+}
+
+/// Without the `clock` feature, there are no clock methods.
+#[cfg(not(feature = "clock"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __clock_methods {
+    ($newtype:ident) => {};
+}
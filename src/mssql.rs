@@ -0,0 +1,351 @@
+//! SQL Server's date and time types: the legacy datetime and
+//! smalldatetime, and the binary encodings of datetime2 and
+//! datetimeoffset.
+
+use std::num::NonZeroU32;
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+
+use crate::{CustomEpoch, Unit};
+
+/// Three hundred ticks per second, from 1900-01-01.
+const MSSQL_DATETIME: CustomEpoch = CustomEpoch::new(
+    Unit::PerSecond(NonZeroU32::new(300).unwrap()),
+    -2_208_988_800,
+);
+
+const TICKS_PER_DAY: i64 = 24 * 60 * 60 * 300;
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// datetime covers 1753-01-01 to 9999-12-31.
+const DATETIME_DAYS: RangeInclusive<i32> = -53_690..=2_958_463;
+
+/// SQL Server's datetime type is a pair of 32-bit numbers: the days
+/// since 1900-01-01 (negative back to 1753-01-01) and the ticks of
+/// 1/300 of a second since midnight. Returns None if the days are out
+/// of that range or the ticks are not within a day.
+///
+/// ```
+/// use epochs::mssql_datetime;
+/// let ndt = mssql_datetime(39855, 25_407_000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// let ndt = mssql_datetime(39855, 25_407_001).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.003333333");
+/// ```
+pub fn mssql_datetime(days: i32, ticks: i32) -> Option<NaiveDateTime> {
+    if !DATETIME_DAYS.contains(&days) || !(0..TICKS_PER_DAY).contains(&i64::from(ticks)) {
+        return None;
+    }
+    MSSQL_DATETIME.to_datetime(i64::from(days) * TICKS_PER_DAY + i64::from(ticks))
+}
+
+/// Convert the given NaiveDateTime to a [SQL Server
+/// datetime](fn.mssql_datetime.html), returning the days and the ticks.
+/// The time is rounded to the nearest tick. Returns None for times
+/// outside of 1753 to 9999.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_mssql_datetime;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.003", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_mssql_datetime(ndt), Some((39855, 25_407_001)));
+/// ```
+pub fn to_mssql_datetime(ndt: NaiveDateTime) -> Option<(i32, i32)> {
+    let dt = ndt.and_utc();
+    let seconds = dt.timestamp() - MSSQL_DATETIME.offset_seconds();
+    let nanos = i64::from(dt.timestamp_subsec_nanos());
+    let total = seconds * 300 + (nanos * 3 + 5_000_000) / 10_000_000;
+    let days = i32::try_from(total.div_euclid(TICKS_PER_DAY)).ok()?;
+    if !DATETIME_DAYS.contains(&days) {
+        return None;
+    }
+    Some((days, total.rem_euclid(TICKS_PER_DAY) as i32))
+}
+
+/// SQL Server's smalldatetime type is a pair of 16-bit numbers: the
+/// days since 1900-01-01 (up to 2079-06-06) and the minutes since
+/// midnight. Returns None if the minutes are not within a day.
+///
+/// ```
+/// use epochs::mssql_smalldatetime;
+/// let ndt = mssql_smalldatetime(39855, 1411).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:00");
+/// ```
+pub fn mssql_smalldatetime(days: u16, minutes: u16) -> Option<NaiveDateTime> {
+    if minutes >= MINUTES_PER_DAY {
+        return None;
+    }
+    mssql_datetime(0, 0)?
+        .checked_add_signed(Duration::days(days.into()))?
+        .checked_add_signed(Duration::minutes(minutes.into()))
+}
+
+/// Convert the given NaiveDateTime to a [SQL Server
+/// smalldatetime](fn.mssql_smalldatetime.html), returning the days and
+/// the minutes. As in SQL Server, the time is rounded to the nearest
+/// minute, with 30 seconds or more rounding up. Returns None for times
+/// outside of 1900-01-01 to 2079-06-06.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_mssql_smalldatetime;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_mssql_smalldatetime(ndt), Some((39855, 1412)));
+/// ```
+pub fn to_mssql_smalldatetime(ndt: NaiveDateTime) -> Option<(u16, u16)> {
+    let rounded = ndt
+        .with_nanosecond(0)?
+        .checked_add_signed(Duration::seconds(30))?;
+    let minutes = (rounded - mssql_datetime(0, 0)?)
+        .num_seconds()
+        .div_euclid(60);
+    if minutes < 0 {
+        return None;
+    }
+    let days = u16::try_from(minutes / i64::from(MINUTES_PER_DAY)).ok()?;
+    Some((days, (minutes % i64::from(MINUTES_PER_DAY)) as u16))
+}
+
+/// The number of bytes datetime2 and datetimeoffset use for the time
+/// of day at the given scale (digits of fractional seconds), or None
+/// if the scale is more than 7.
+fn time_len(scale: u8) -> Option<usize> {
+    match scale {
+        0..=2 => Some(3),
+        3..=4 => Some(4),
+        5..=7 => Some(5),
+        _ => None,
+    }
+}
+
+/// Read a little-endian unsigned number from up to eight bytes.
+fn from_le(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |n, &b| (n << 8) | u64::from(b))
+}
+
+/// SQL Server's datetime2 type, as stored on disk and sent over TDS, is
+/// the time since midnight in units of 10<sup>-scale</sup> seconds
+/// (three to five little-endian bytes, depending on the scale, which
+/// is not itself stored), followed by the days since 0001-01-01 (three
+/// little-endian bytes). Returns None if the scale is more than 7, the
+/// number of bytes is wrong for it, or the time is not within a day.
+///
+/// ```
+/// use epochs::mssql_datetime2;
+/// let bytes = [0x00, 0x75, 0x2c, 0x2f, 0xc5, 0x0a, 0x31, 0x0b];
+/// let ndt = mssql_datetime2(&bytes, 7).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn mssql_datetime2(bytes: &[u8], scale: u8) -> Option<NaiveDateTime> {
+    let len = time_len(scale)?;
+    if bytes.len() != len + 3 {
+        return None;
+    }
+    let units = from_le(&bytes[..len]);
+    let per_second = 10u64.pow(scale.into());
+    if units >= 24 * 60 * 60 * per_second {
+        return None;
+    }
+    let days = from_le(&bytes[len..]) as i32;
+    let nanos = units % per_second * (1_000_000_000 / per_second);
+    NaiveDate::from_num_days_from_ce_opt(days + 1)?
+        .and_hms_opt(0, 0, 0)?
+        .checked_add_signed(Duration::seconds((units / per_second) as i64))?
+        .checked_add_signed(Duration::nanoseconds(nanos as i64))
+}
+
+/// Encode the given NaiveDateTime as a [SQL Server
+/// datetime2](fn.mssql_datetime2.html) with the given scale. Fractions
+/// of a second beyond the scale are dropped. Returns None if the scale
+/// is more than 7 or the year is outside of 1 to 9999.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_mssql_datetime2;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(
+///     to_mssql_datetime2(ndt, 7),
+///     Some(vec![0x00, 0x75, 0x2c, 0x2f, 0xc5, 0x0a, 0x31, 0x0b]),
+/// );
+/// ```
+pub fn to_mssql_datetime2(ndt: NaiveDateTime, scale: u8) -> Option<Vec<u8>> {
+    let len = time_len(scale)?;
+    if !(1..=9999).contains(&ndt.year()) {
+        return None;
+    }
+    let per_second = 10u64.pow(scale.into());
+    // A leap second is written as the end of second 59.
+    let nanos = u64::from(ndt.nanosecond().min(999_999_999));
+    let units = u64::from(ndt.num_seconds_from_midnight()) * per_second
+        + nanos / (1_000_000_000 / per_second);
+    let days = ndt.num_days_from_ce() as u32 - 1;
+    let mut bytes = units.to_le_bytes()[..len].to_vec();
+    bytes.extend_from_slice(&days.to_le_bytes()[..3]);
+    Some(bytes)
+}
+
+/// SQL Server's datetimeoffset type is a [datetime2](fn.mssql_datetime2.html)
+/// holding the UTC time, followed by the offset from UTC in minutes
+/// (two little-endian bytes, signed). This returns the UTC time and the
+/// offset; the local time is their sum. Returns None if the scale is
+/// more than 7, the number of bytes is wrong for it, or any of the
+/// fields are invalid.
+///
+/// ```
+/// use epochs::mssql_datetimeoffset;
+/// let bytes = [0x50, 0x44, 0x0c, 0x05, 0x0a, 0x31, 0x0b, 0xd4, 0xfe];
+/// let (ndt, offset) = mssql_datetimeoffset(&bytes, 3).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(offset.to_string(), "-05:00");
+/// ```
+pub fn mssql_datetimeoffset(bytes: &[u8], scale: u8) -> Option<(NaiveDateTime, FixedOffset)> {
+    let (datetime2, offset) = bytes.split_at(bytes.len().checked_sub(2)?);
+    let minutes = i16::from_le_bytes(offset.try_into().ok()?);
+    // SQL Server allows offsets of up to ±14:00.
+    if minutes.unsigned_abs() > 14 * 60 {
+        return None;
+    }
+    let offset = FixedOffset::east_opt(i32::from(minutes) * 60)?;
+    Some((mssql_datetime2(datetime2, scale)?, offset))
+}
+
+/// Encode the given UTC NaiveDateTime and offset as a [SQL Server
+/// datetimeoffset](fn.mssql_datetimeoffset.html) with the given scale.
+/// Returns None if the scale is more than 7, the year is outside of 1
+/// to 9999, or the offset is not a whole number of minutes within
+/// ±14:00.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{FixedOffset, NaiveDateTime};
+/// use epochs::to_mssql_datetimeoffset;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let offset = FixedOffset::west_opt(5 * 60 * 60).unwrap();
+/// assert_eq!(
+///     to_mssql_datetimeoffset(ndt, offset, 3),
+///     Some(vec![0x50, 0x44, 0x0c, 0x05, 0x0a, 0x31, 0x0b, 0xd4, 0xfe]),
+/// );
+/// ```
+pub fn to_mssql_datetimeoffset(
+    ndt: NaiveDateTime,
+    offset: FixedOffset,
+    scale: u8,
+) -> Option<Vec<u8>> {
+    let seconds = offset.local_minus_utc();
+    if seconds % 60 != 0 || seconds.unsigned_abs() > 14 * 60 * 60 {
+        return None;
+    }
+    let mut bytes = to_mssql_datetime2(ndt, scale)?;
+    bytes.extend_from_slice(&((seconds / 60) as i16).to_le_bytes());
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn mssql_datetime_range() {
+        let ndt = mssql_datetime(*DATETIME_DAYS.start(), 0).unwrap();
+        assert_eq!(ndt.to_string(), "1753-01-01 00:00:00");
+        let ndt = mssql_datetime(*DATETIME_DAYS.end(), 25_919_999).unwrap();
+        assert_eq!(ndt.to_string(), "9999-12-31 23:59:59.996666666");
+        assert!(mssql_datetime(*DATETIME_DAYS.start() - 1, 0).is_none());
+        assert!(mssql_datetime(0, -1).is_none());
+        assert!(mssql_datetime(0, 25_920_000).is_none());
+    }
+    #[test]
+    fn mssql_datetime_before_1900() {
+        let ndt = mssql_datetime(-1, 1).unwrap();
+        assert_eq!(ndt.to_string(), "1899-12-31 00:00:00.003333333");
+        assert_eq!(to_mssql_datetime(ndt), Some((-1, 1)));
+    }
+    #[test]
+    fn to_mssql_datetime_rounds() {
+        // The last tick rounds up into the next day.
+        let ndt = NaiveDate::from_ymd_opt(2000, 1, 1)
+            .unwrap()
+            .and_hms_milli_opt(23, 59, 59, 999)
+            .unwrap();
+        let (days, ticks) = to_mssql_datetime(ndt).unwrap();
+        assert_eq!(
+            mssql_datetime(days, ticks).unwrap().to_string(),
+            "2000-01-02 00:00:00"
+        );
+        let last = mssql_datetime(*DATETIME_DAYS.end(), 25_919_999).unwrap();
+        assert!(to_mssql_datetime(last + Duration::milliseconds(2)).is_none());
+    }
+    #[test]
+    fn mssql_smalldatetime_range() {
+        let ndt = mssql_smalldatetime(u16::MAX, 1439).unwrap();
+        assert_eq!(ndt.to_string(), "2079-06-06 23:59:00");
+        assert_eq!(to_mssql_smalldatetime(ndt), Some((u16::MAX, 1439)));
+        assert!(to_mssql_smalldatetime(ndt + Duration::seconds(30)).is_none());
+        assert!(mssql_smalldatetime(0, 1440).is_none());
+        let ndt = mssql_smalldatetime(0, 0).unwrap() - Duration::seconds(31);
+        assert!(to_mssql_smalldatetime(ndt).is_none());
+    }
+    #[test]
+    fn mssql_datetime2_scales() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_nano_opt(23, 31, 30, 123_456_789)
+            .unwrap();
+        for (scale, len, fraction) in [
+            (0, 6, 0),
+            (2, 6, 120_000_000),
+            (4, 7, 123_400_000),
+            (7, 8, 123_456_700),
+        ] {
+            let bytes = to_mssql_datetime2(ndt, scale).unwrap();
+            assert_eq!(bytes.len(), len, "{scale}");
+            let back = mssql_datetime2(&bytes, scale).unwrap();
+            assert_eq!(back, ndt.with_nanosecond(fraction).unwrap(), "{scale}");
+        }
+    }
+    #[test]
+    fn mssql_datetime2_range() {
+        let ndt = mssql_datetime2(&[0, 0, 0, 0, 0, 0], 0).unwrap();
+        assert_eq!(ndt.to_string(), "0001-01-01 00:00:00");
+        let last = NaiveDate::from_ymd_opt(9999, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 999_999_900)
+            .unwrap();
+        let bytes = to_mssql_datetime2(last, 7).unwrap();
+        assert_eq!(mssql_datetime2(&bytes, 7), Some(last));
+        assert!(to_mssql_datetime2(last + Duration::seconds(1), 7).is_none());
+    }
+    #[test]
+    fn mssql_datetime2_bad_input() {
+        // The wrong length for the scale, too big a scale, and a
+        // time of 24:00:00.
+        assert!(mssql_datetime2(&[0; 7], 0).is_none());
+        assert!(mssql_datetime2(&[0; 8], 8).is_none());
+        assert!(mssql_datetime2(&[0x80, 0x51, 0x01, 0, 0, 0], 0).is_none());
+    }
+    #[test]
+    fn mssql_datetimeoffset_bad_offset() {
+        let mut bytes = vec![0, 0, 0, 0, 0, 0];
+        bytes.extend_from_slice(&841i16.to_le_bytes());
+        assert!(mssql_datetimeoffset(&bytes, 0).is_none());
+        assert!(mssql_datetimeoffset(&[0], 0).is_none());
+        let ndt = mssql_datetime2(&[0; 6], 0).unwrap();
+        let offset = FixedOffset::east_opt(30).unwrap();
+        assert!(to_mssql_datetimeoffset(ndt, offset, 0).is_none());
+    }
+    #[test]
+    fn to_mssql_smalldatetime_rounds_down() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_milli_opt(23, 31, 29, 998)
+            .unwrap();
+        assert_eq!(to_mssql_smalldatetime(ndt), Some((39855, 1411)));
+    }
+}
@@ -0,0 +1,199 @@
+//! Network Time Protocol formats
+//! ([RFC 5905](https://tools.ietf.org/html/rfc5905)).
+
+use chrono::{DateTime, Duration, NaiveDateTime};
+
+use crate::{fraction_to_nanos, nanos_to_fraction};
+
+/// The NTP prime epoch, 1900-01-01, is 2,208,988,800 seconds before
+/// the Unix epoch.
+const NTP_OFFSET: i64 = 2_208_988_800;
+
+/// NTP time is a 64-bit timestamp of seconds since 1900-01-01 in the
+/// high 32 bits and a binary fraction of a second in the low 32 bits.
+/// The seconds roll over every 136 years, and the timestamp does not
+/// say how many times they have done so; this function assumes era 0,
+/// which ends in 2036. See [ntp_date](fn.ntp_date.html) for the
+/// format which includes the era.
+///
+/// ```
+/// use epochs::ntp;
+/// let ndt = ntp(0xcd40_8152_8000_0000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.500");
+/// ```
+pub fn ntp(num: u64) -> Option<NaiveDateTime> {
+    let seconds = (num >> 32) as i64 - NTP_OFFSET;
+    let nanos = fraction_to_nanos((num & 0xffff_ffff) as u128, 32);
+    DateTime::from_timestamp(seconds, nanos).map(|dt| dt.naive_utc())
+}
+
+/// Convert the given NaiveDateTime to an [NTP](fn.ntp.html) time.
+/// Times outside era 0 (1900 to 2036) wrap around, just as they do in
+/// NTP itself.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_ntp;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_ntp(ndt), 0xcd40_8152_8000_0000);
+/// ```
+pub fn to_ntp(ndt: NaiveDateTime) -> u64 {
+    let (_era, offset, nanos) = split(ndt);
+    (offset as u64) << 32 | nanos_to_fraction(nanos, 32) as u64
+}
+
+/// NTP short format is 16 bits of seconds and 16 bits of binary
+/// fraction. It is only used for intervals (*e.g.*, root delay and
+/// root dispersion), so it converts to a Duration rather than a time.
+///
+/// ```
+/// use epochs::ntp_short;
+/// assert_eq!(ntp_short(0x0001_8000).num_milliseconds(), 1500);
+/// ```
+pub fn ntp_short(num: u32) -> Duration {
+    let seconds = (num >> 16) as i64;
+    let nanos = fraction_to_nanos((num & 0xffff) as u128, 16);
+    Duration::seconds(seconds) + Duration::nanoseconds(nanos as i64)
+}
+
+/// Convert the given Duration to [NTP short format](fn.ntp_short.html).
+/// Returns None for negative durations and for those of 65,536 seconds
+/// or more, which do not fit.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::Duration;
+/// use epochs::to_ntp_short;
+/// assert_eq!(to_ntp_short(Duration::milliseconds(1500)), Some(0x0001_8000));
+/// ```
+pub fn to_ntp_short(d: Duration) -> Option<u32> {
+    let nanos = d.num_nanoseconds()?;
+    if !(0..65_536 * 1_000_000_000).contains(&nanos) {
+        return None;
+    }
+    let seconds = (nanos / 1_000_000_000) as u32;
+    let fraction = nanos_to_fraction((nanos % 1_000_000_000) as u32, 16) as u32;
+    Some(seconds << 16 | fraction)
+}
+
+/// NTP date format is 128 bits: a signed 32-bit era number, the 32-bit
+/// number of seconds into that era, and a 64-bit binary fraction of a
+/// second. Era 0 starts at 1900-01-01, era 1 in 2036, and era -1 in
+/// 1764.
+///
+/// ```
+/// use epochs::ntp_date;
+/// let ndt = ntp_date(0x0000_0001_0000_0000_8000_0000_0000_0000).unwrap();
+/// assert_eq!(ndt.to_string(), "2036-02-07 06:28:16.500");
+/// ```
+pub fn ntp_date(num: u128) -> Option<NaiveDateTime> {
+    let era = (num >> 96) as u32 as i32 as i64;
+    let offset = (num >> 64) as u32 as i64;
+    let fraction = num as u64;
+    let seconds = ((era << 32) + offset).checked_sub(NTP_OFFSET)?;
+    let nanos = fraction_to_nanos(fraction as u128, 64);
+    DateTime::from_timestamp(seconds, nanos).map(|dt| dt.naive_utc())
+}
+
+/// Convert the given NaiveDateTime to an [NTP date](fn.ntp_date.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_ntp_date;
+/// let ndt = NaiveDateTime::parse_from_str("2036-02-07 06:28:16.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_ntp_date(ndt), 0x0000_0001_0000_0000_8000_0000_0000_0000);
+/// ```
+pub fn to_ntp_date(ndt: NaiveDateTime) -> u128 {
+    let (era, offset, nanos) = split(ndt);
+    (era as u32 as u128) << 96 | (offset as u128) << 64 | nanos_to_fraction(nanos, 64)
+}
+
+/// Split the given NaiveDateTime into an NTP era, the seconds into that
+/// era, and the nanoseconds.
+fn split(ndt: NaiveDateTime) -> (i32, u32, u32) {
+    let dt = ndt.and_utc();
+    let seconds = dt.timestamp() + NTP_OFFSET;
+    let era = seconds.div_euclid(1 << 32) as i32;
+    let offset = seconds.rem_euclid(1 << 32) as u32;
+    let nanos = dt.timestamp_subsec_nanos().min(999_999_999);
+    (era, offset, nanos)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn ntp_run() {
+        let ndt = ntp(0xcd40_8152_0000_0000).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn ntp_prime_epoch() {
+        let ndt = ntp(0).unwrap();
+        assert_eq!(ndt.to_string(), "1900-01-01 00:00:00");
+    }
+    #[test]
+    fn ntp_max() {
+        let ndt = ntp(u64::MAX).unwrap();
+        assert_eq!(ndt.to_string(), "2036-02-07 06:28:15.999999999");
+    }
+    #[test]
+    fn to_ntp_wraps() {
+        let ndt = NaiveDate::from_ymd_opt(2036, 2, 7)
+            .unwrap()
+            .and_hms_opt(6, 28, 16)
+            .unwrap();
+        assert_eq!(to_ntp(ndt), 0);
+    }
+    #[test]
+    fn ntp_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_nano_opt(23, 31, 30, 123_456_789)
+            .unwrap();
+        assert_eq!(ntp(to_ntp(ndt)), Some(ndt));
+    }
+    #[test]
+    fn ntp_short_run() {
+        assert_eq!(ntp_short(0), Duration::zero());
+        assert_eq!(ntp_short(0x0000_0001).num_nanoseconds(), Some(15258));
+    }
+    #[test]
+    fn to_ntp_short_out_of_range() {
+        assert_eq!(to_ntp_short(Duration::seconds(-1)), None);
+        assert_eq!(to_ntp_short(Duration::seconds(65_536)), None);
+        assert_eq!(
+            to_ntp_short(Duration::nanoseconds(65_535_999_999_999)),
+            Some(u32::MAX)
+        );
+    }
+    #[test]
+    fn ntp_short_round_trip() {
+        for num in [0, 1, 0x1234_5678, u32::MAX] {
+            assert_eq!(to_ntp_short(ntp_short(num)), Some(num));
+        }
+    }
+    #[test]
+    fn ntp_date_negative_era() {
+        let ndt = ntp_date(0xffff_ffff_0000_0000_0000_0000_0000_0000).unwrap();
+        assert_eq!(ndt.to_string(), "1763-11-24 17:31:44");
+    }
+    #[test]
+    fn ntp_date_matches_ntp() {
+        let ndt = ntp(0xcd40_8152_8000_0000).unwrap();
+        assert_eq!(ntp_date(0xcd40_8152_8000_0000_0000_0000), Some(ndt));
+    }
+    #[test]
+    fn ntp_date_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(1492, 10, 12)
+            .unwrap()
+            .and_hms_nano_opt(1, 2, 3, 999_999_999)
+            .unwrap();
+        assert_eq!(ntp_date(to_ntp_date(ndt)), Some(ndt));
+    }
+}
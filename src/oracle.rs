@@ -0,0 +1,165 @@
+//! Oracle's internal DATE and TIMESTAMP formats.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+
+/// Oracle stores a DATE as seven bytes: century and year in the
+/// century (each in excess-100 notation), month, day, and hour,
+/// minute, and second (each plus one). BC years have century and
+/// year bytes below 100; Oracle has no year 0, so its year -1 (1 BC)
+/// is year 0 here, as in chrono. Returns None if any of the fields
+/// are invalid.
+///
+/// ```
+/// use epochs::oracle_date;
+/// let ndt = oracle_date(&[120, 109, 2, 13, 24, 32, 31]).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn oracle_date(bytes: &[u8; 7]) -> Option<NaiveDateTime> {
+    let [century, year, month, day, hour, minute, second] = bytes.map(i32::from);
+    let year = if century == 100 && year == 100 {
+        // There is no year 0.
+        return None;
+    } else if century >= 100 && year >= 100 {
+        (century - 100) * 100 + (year - 100)
+    } else if century <= 100 && year <= 100 {
+        1 - ((100 - century) * 100 + (100 - year))
+    } else {
+        return None;
+    };
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)?.and_hms_opt(
+        hour.checked_sub(1)? as u32,
+        minute.checked_sub(1)? as u32,
+        second.checked_sub(1)? as u32,
+    )
+}
+
+/// Oracle stores a TIMESTAMP as an eleven-byte [DATE](fn.oracle_date.html)
+/// followed by the nanoseconds as a big-endian 32-bit number. (A
+/// TIMESTAMP with no fractional seconds is stored as a plain seven-byte
+/// DATE.) Returns None if any of the fields are invalid.
+///
+/// ```
+/// use epochs::oracle_timestamp;
+/// let bytes = [120, 109, 2, 13, 24, 32, 31, 0x07, 0x5b, 0xcd, 0x15];
+/// let ndt = oracle_timestamp(&bytes).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.123456789");
+/// ```
+pub fn oracle_timestamp(bytes: &[u8; 11]) -> Option<NaiveDateTime> {
+    let (date, nanos) = bytes.split_at(7);
+    let nanos = u32::from_be_bytes(nanos.try_into().ok()?);
+    if nanos >= 1_000_000_000 {
+        return None;
+    }
+    oracle_date(date.try_into().ok()?)?.with_nanosecond(nanos)
+}
+
+/// Encode the given NaiveDateTime as an [Oracle DATE](fn.oracle_date.html).
+/// Fractions of a second are dropped. Returns None if the year is
+/// outside of 4712 BC (year -4711 in chrono) to 9999, the range Oracle
+/// allows.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_oracle_date;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_oracle_date(ndt), Some([120, 109, 2, 13, 24, 32, 31]));
+/// ```
+pub fn to_oracle_date(ndt: NaiveDateTime) -> Option<[u8; 7]> {
+    let year = ndt.year();
+    if !(-4711..=9999).contains(&year) {
+        return None;
+    }
+    let (century, year) = if year > 0 {
+        (100 + year / 100, 100 + year % 100)
+    } else {
+        let bc = 1 - year;
+        (100 - bc / 100, 100 - bc % 100)
+    };
+    Some([
+        century as u8,
+        year as u8,
+        ndt.month() as u8,
+        ndt.day() as u8,
+        ndt.hour() as u8 + 1,
+        ndt.minute() as u8 + 1,
+        // A leap second is written as second 59.
+        ndt.second().min(59) as u8 + 1,
+    ])
+}
+
+/// Encode the given NaiveDateTime as an [Oracle
+/// TIMESTAMP](fn.oracle_timestamp.html). Returns None if the year is
+/// outside of the range Oracle allows.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_oracle_timestamp;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.123456789", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(
+///     to_oracle_timestamp(ndt),
+///     Some([120, 109, 2, 13, 24, 32, 31, 0x07, 0x5b, 0xcd, 0x15]),
+/// );
+/// ```
+pub fn to_oracle_timestamp(ndt: NaiveDateTime) -> Option<[u8; 11]> {
+    let date = to_oracle_date(ndt)?;
+    let nanos = ndt.nanosecond().min(999_999_999).to_be_bytes();
+    let mut bytes = [0; 11];
+    bytes[..7].copy_from_slice(&date);
+    bytes[7..].copy_from_slice(&nanos);
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn oracle_date_bc() {
+        // The earliest Oracle date, January 1st, 4712 BC.
+        let ndt = oracle_date(&[53, 88, 1, 1, 1, 1, 1]).unwrap();
+        assert_eq!(ndt.year(), -4711);
+        assert_eq!(to_oracle_date(ndt), Some([53, 88, 1, 1, 1, 1, 1]));
+        // 1 BC and 100 BC.
+        assert_eq!(oracle_date(&[100, 99, 1, 1, 1, 1, 1]).unwrap().year(), 0);
+        assert_eq!(oracle_date(&[99, 100, 1, 1, 1, 1, 1]).unwrap().year(), -99);
+    }
+    #[test]
+    fn oracle_date_century_boundaries() {
+        for (year, bytes) in [(1, [100, 101]), (100, [101, 100]), (2000, [120, 100])] {
+            let ndt = NaiveDate::from_ymd_opt(year, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap();
+            let encoded = to_oracle_date(ndt).unwrap();
+            assert_eq!(encoded[..2], bytes, "{year}");
+            assert_eq!(oracle_date(&encoded), Some(ndt), "{year}");
+        }
+    }
+    #[test]
+    fn oracle_date_bad_fields() {
+        // Mixed signs of century and year, and year 0.
+        assert!(oracle_date(&[120, 99, 2, 13, 24, 32, 31]).is_none());
+        assert!(oracle_date(&[100, 100, 1, 1, 1, 1, 1]).is_none());
+        // Hour 24 and a zero (missing the plus one) minute.
+        assert!(oracle_date(&[120, 109, 2, 13, 25, 32, 31]).is_none());
+        assert!(oracle_date(&[120, 109, 2, 13, 24, 0, 31]).is_none());
+        assert!(oracle_date(&[120, 109, 2, 30, 24, 32, 31]).is_none());
+    }
+    #[test]
+    fn oracle_timestamp_bad_nanos() {
+        let bytes = [120, 109, 2, 13, 24, 32, 31, 0x3b, 0x9a, 0xca, 0x00];
+        assert!(oracle_timestamp(&bytes).is_none());
+    }
+    #[test]
+    fn to_oracle_out_of_range() {
+        let ndt = NaiveDate::from_ymd_opt(10000, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert!(to_oracle_date(ndt).is_none());
+        assert!(to_oracle_timestamp(ndt).is_none());
+    }
+}
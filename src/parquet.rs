@@ -0,0 +1,109 @@
+//! Parquet's legacy INT96 timestamps.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+/// The Julian day number of 0001-01-01 minus one, so that adding
+/// chrono's days from the common era gives a Julian day number.
+const JULIAN_DAY_CE: i32 = 1_721_425;
+
+const NANOS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// INT96 timestamps, as written by Impala, Hive, and older versions of
+/// Spark, are twelve bytes: the nanoseconds since midnight as a
+/// little-endian 64-bit number, followed by the Julian day number as a
+/// little-endian 32-bit number. Julian day 2440588 is 1970-01-01. The
+/// time is UTC (or, for some writers, local time and with no record of
+/// which). Returns None if the nanoseconds are not within a day.
+///
+/// ```
+/// use epochs::parquet_int96;
+/// let mut bytes = [0; 12];
+/// bytes[..8].copy_from_slice(&84_690_654_321_000i64.to_le_bytes());
+/// bytes[8..].copy_from_slice(&2_454_876i32.to_le_bytes());
+/// let ndt = parquet_int96(&bytes).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.654321");
+/// ```
+pub fn parquet_int96(bytes: &[u8; 12]) -> Option<NaiveDateTime> {
+    let (nanos, day) = bytes.split_at(8);
+    let nanos = i64::from_le_bytes(nanos.try_into().ok()?);
+    let day = i32::from_le_bytes(day.try_into().ok()?);
+    if !(0..NANOS_PER_DAY).contains(&nanos) {
+        return None;
+    }
+    NaiveDate::from_num_days_from_ce_opt(day.checked_sub(JULIAN_DAY_CE)?)?
+        .and_hms_opt(0, 0, 0)?
+        .checked_add_signed(Duration::nanoseconds(nanos))
+}
+
+/// Encode the given NaiveDateTime as a [Parquet INT96](fn.parquet_int96.html)
+/// timestamp. A leap second is folded into the last nanosecond of the
+/// second before it.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::{parquet_int96, to_parquet_int96};
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.654321", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// let bytes = to_parquet_int96(ndt);
+/// assert_eq!(&bytes[8..], &2_454_876i32.to_le_bytes());
+/// assert_eq!(parquet_int96(&bytes), Some(ndt));
+/// ```
+pub fn to_parquet_int96(ndt: NaiveDateTime) -> [u8; 12] {
+    let nanos = i64::from(ndt.num_seconds_from_midnight()) * 1_000_000_000
+        + i64::from(ndt.nanosecond().min(999_999_999));
+    // Every chrono date is well within range of an i32 Julian day.
+    let day = ndt.num_days_from_ce() + JULIAN_DAY_CE;
+    let mut bytes = [0; 12];
+    bytes[..8].copy_from_slice(&nanos.to_le_bytes());
+    bytes[8..].copy_from_slice(&day.to_le_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn int96(nanos: i64, day: i32) -> [u8; 12] {
+        let mut bytes = [0; 12];
+        bytes[..8].copy_from_slice(&nanos.to_le_bytes());
+        bytes[8..].copy_from_slice(&day.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn unix_epoch() {
+        let ndt = parquet_int96(&int96(0, 2_440_588)).unwrap();
+        assert_eq!(ndt.to_string(), "1970-01-01 00:00:00");
+    }
+    #[test]
+    fn julian_day_zero() {
+        // Noon on 4713-01-01 BC in the Julian calendar, but INT96 days
+        // start at midnight.
+        let ndt = parquet_int96(&int96(0, 0)).unwrap();
+        assert_eq!(ndt.to_string(), "-4713-11-24 00:00:00");
+        assert_eq!(to_parquet_int96(ndt), int96(0, 0));
+    }
+    #[test]
+    fn invalid() {
+        assert_eq!(parquet_int96(&int96(-1, 2_440_588)), None);
+        assert_eq!(parquet_int96(&int96(NANOS_PER_DAY, 2_440_588)), None);
+        assert_eq!(parquet_int96(&int96(0, i32::MIN)), None);
+        assert_eq!(parquet_int96(&int96(0, i32::MAX)), None);
+    }
+    #[test]
+    fn leap_second() {
+        let ndt = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1_500_000_000)
+            .unwrap();
+        let ndt = parquet_int96(&to_parquet_int96(ndt)).unwrap();
+        assert_eq!(ndt.to_string(), "2016-12-31 23:59:59.999999999");
+    }
+    #[test]
+    fn round_trip() {
+        for ndt in [NaiveDateTime::MIN, NaiveDateTime::MAX] {
+            assert_eq!(parquet_int96(&to_parquet_int96(ndt)), Some(ndt));
+        }
+    }
+}
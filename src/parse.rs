@@ -0,0 +1,511 @@
+//! Parse numbers and datetimes the way they turn up in the wild.
+
+use std::error::Error;
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+/// A number parsed by [parse_value](fn.parse_value.html), which is
+/// either an integer (for most epochs) or a float (for ICQ time).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+}
+
+impl Value {
+    /// This value as an integer, if it is one or is a float with no
+    /// fractional part that fits in an i64.
+    pub fn as_i64(self) -> Option<i64> {
+        match self {
+            Value::Int(n) => Some(n),
+            Value::Float(x) if x.fract() == 0.0 && x >= i64::MIN as f64 && x < i64::MAX as f64 => {
+                Some(x as i64)
+            }
+            Value::Float(_) => None,
+        }
+    }
+
+    /// This value as a float.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Value::Int(n) => n as f64,
+            Value::Float(x) => x,
+        }
+    }
+}
+
+/// The error returned when a string is not a number
+/// [parse_value](fn.parse_value.html) understands, saying where it went
+/// wrong and what was expected there.
+///
+/// ```
+/// use epochs::parse_value;
+/// let err = parse_value("12,879,04l,490").unwrap_err();
+/// assert_eq!(err.offset(), 9);
+/// assert_eq!(err.expected(), "a digit");
+/// assert_eq!(err.to_string(), r#"invalid number: "12,879,04l,490": expected a digit at offset 9"#);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseValueError {
+    input: String,
+    offset: usize,
+    expected: &'static str,
+}
+
+impl ParseValueError {
+    pub(crate) fn new(input: &str, offset: usize, expected: &'static str) -> ParseValueError {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(input, offset, expected, "invalid number");
+        ParseValueError {
+            input: input.to_string(),
+            offset,
+            expected,
+        }
+    }
+
+    /// The string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The byte offset into the string where parsing failed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// What was expected at that offset.
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+}
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid number: {:?}: expected {} at offset {}",
+            self.input, self.expected, self.offset
+        )
+    }
+}
+
+impl Error for ParseValueError {}
+
+/// Where in the string (relative to some start) parsing failed, and
+/// what was expected there.
+type Failure = (usize, &'static str);
+
+const IN_RANGE: &str = "a number within the range of an i64";
+
+/// Parse a number copied from a hex dump, a spreadsheet, or source
+/// code. Besides plain decimal integers, this accepts
+///
+/// - hexadecimal with a `0x` prefix, like `0x1ea4f7dca4892ce`,
+/// - octal with a `0o` prefix or binary with a `0b` prefix, like
+///   `0o11145401322` or `0b1001001100101100000001011010010`,
+/// - underscores between digits, like `12_879_041_490_000_000`,
+/// - commas between groups of three digits, like `1,234,567,890`, and
+/// - floats, with or without an exponent, like `1.2345679e9`.
+///
+/// Integers come back as [Value::Int](enum.Value.html),
+/// anything with a decimal point or exponent as
+/// [Value::Float](enum.Value.html).
+///
+/// ```
+/// use epochs::{parse_value, uuid_v1, Value};
+/// assert_eq!(parse_value("1,234,567,890"), Ok(Value::Int(1234567890)));
+/// assert_eq!(parse_value("1.2345679e9"), Ok(Value::Float(1234567900.0)));
+///
+/// let num = parse_value("0x1ea4f7dca4892ce").unwrap().as_i64().unwrap();
+/// assert_eq!(uuid_v1(num).unwrap().to_string(), "2020-02-14 23:00:27.148155");
+/// ```
+pub fn parse_value(s: &str) -> Result<Value, ParseValueError> {
+    let start = s.len() - s.trim_start().len();
+    let trimmed = s.trim();
+    let (negative, unsigned) = split_sign(trimmed);
+    let base = start + trimmed.len() - unsigned.len();
+    let err = |(offset, expected): Failure| ParseValueError::new(s, offset, expected);
+
+    if let Some((radix, digits)) = strip_radix_prefix(unsigned) {
+        let n = from_radix(digits, radix).map_err(|(i, e)| err((base + 2 + i, e)))?;
+        return signed(n, negative)
+            .map(Value::Int)
+            .ok_or_else(|| err((start, IN_RANGE)));
+    }
+
+    let (int_part, rest) = match unsigned.find(['.', 'e', 'E']) {
+        Some(i) => unsigned.split_at(i),
+        None => (unsigned, ""),
+    };
+    let int_digits = decimal_digits(int_part).map_err(|(i, e)| err((base + i, e)))?;
+
+    if rest.is_empty() {
+        let n = int_digits
+            .parse::<u64>()
+            .map_err(|_| err((start, IN_RANGE)))?;
+        signed(n, negative)
+            .map(Value::Int)
+            .ok_or_else(|| err((start, IN_RANGE)))
+    } else {
+        let rest =
+            fraction_and_exponent(rest).map_err(|(i, e)| err((base + int_part.len() + i, e)))?;
+        let sign = if negative { "-" } else { "" };
+        let x = format!("{}{}{}", sign, int_digits, rest)
+            .parse::<f64>()
+            .map_err(|_| err((start, "a number")))?;
+        if x.is_finite() {
+            Ok(Value::Float(x))
+        } else {
+            Err(err((start, "a finite number")))
+        }
+    }
+}
+
+/// Parse a hexadecimal integer, with or without a `0x` prefix, like
+/// those copied from a hex editor or a registry dump. As with
+/// [parse_value](fn.parse_value.html), a sign and underscores between
+/// digits are allowed.
+///
+/// ```
+/// use epochs::{parse_hex, windows_file};
+/// let num = parse_hex("1c98e333296f500").unwrap();
+/// assert_eq!(windows_file(num).unwrap().to_string(), "2009-02-13 23:31:30");
+/// assert_eq!(parse_hex("0x1C98E333296F500"), Ok(num));
+/// assert_eq!(parse_hex("0x1c98e33329gf500").unwrap_err().offset(), 12);
+/// ```
+pub fn parse_hex(s: &str) -> Result<i64, ParseValueError> {
+    let start = s.len() - s.trim_start().len();
+    let (negative, unsigned) = split_sign(s.trim());
+    let digits = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))
+        .unwrap_or(unsigned);
+    let base = start + s.trim().len() - digits.len();
+    let n = from_radix(digits, 16).map_err(|(i, e)| ParseValueError::new(s, base + i, e))?;
+    signed(n, negative).ok_or_else(|| ParseValueError::new(s, start, IN_RANGE))
+}
+
+/// Parse a datetime written in any of the common ISO 8601 forms: with
+/// a `T` or a space between the date and the time, with or without
+/// seconds and fractional seconds, and with a trailing `Z`, an offset
+/// from UTC (which is applied), or nothing. A date on its own is
+/// taken to be midnight.
+///
+/// ```
+/// use epochs::{parse_datetime, to_unix};
+/// for s in [
+///     "2009-02-13 23:31:30",
+///     "2009-02-13T23:31:30Z",
+///     "2009-02-13T23:31:30.000",
+///     "2009-02-14T00:31:30+01:00",
+/// ] {
+///     assert_eq!(to_unix(parse_datetime(s).unwrap()), 1234567890);
+/// }
+/// assert_eq!(parse_datetime("2009-02-13").unwrap().to_string(), "2009-02-13 00:00:00");
+/// assert_eq!(parse_datetime("Friday the 13th"), None);
+/// ```
+pub fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
+    }
+    // There are no spaces in the date, so this can only be the one
+    // between the date and the time.
+    let s = s.replacen(' ', "T", 1);
+    if let Ok(dt) = DateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(dt.naive_utc());
+    }
+    let s = s.strip_suffix(['Z', 'z']).unwrap_or(&s);
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+}
+
+/// Split off a leading sign, if any, saying whether it was a minus.
+fn split_sign(s: &str) -> (bool, &str) {
+    match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    }
+}
+
+/// Split off a `0x`, `0o`, or `0b` prefix, returning the radix it
+/// stands for and the rest.
+fn strip_radix_prefix(s: &str) -> Option<(u32, &str)> {
+    let rest = s.strip_prefix('0')?;
+    let radix = match rest.as_bytes().first()? {
+        b'x' | b'X' => 16,
+        b'o' | b'O' => 8,
+        b'b' | b'B' => 2,
+        _ => return None,
+    };
+    Some((radix, &rest[1..]))
+}
+
+/// Parse unsigned digits in the given radix, allowing underscores
+/// between them.
+fn from_radix(digits: &str, radix: u32) -> Result<u64, Failure> {
+    let expected = match radix {
+        2 => "a binary digit",
+        8 => "an octal digit",
+        _ => "a hex digit",
+    };
+    let digits = without_underscores(digits, |c| c.is_digit(radix), expected)?;
+    // Every character is a digit, so this can only overflow.
+    u64::from_str_radix(&digits, radix).map_err(|_| (0, IN_RANGE))
+}
+
+/// Apply the sign to the given magnitude, if the result fits in an
+/// i64.
+fn signed(n: u64, negative: bool) -> Option<i64> {
+    if negative {
+        0i64.checked_sub_unsigned(n)
+    } else {
+        i64::try_from(n).ok()
+    }
+}
+
+/// Check that the given string is digits, with underscores only
+/// between two of them, and return the digits.
+fn without_underscores(
+    s: &str,
+    is_digit: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> Result<String, Failure> {
+    if s.is_empty() {
+        return Err((0, expected));
+    }
+    let mut after_digit = false;
+    for (i, c) in s.char_indices() {
+        if c == '_' && after_digit && s[i + 1..].starts_with(|c: char| is_digit(c)) {
+            after_digit = false;
+        } else if is_digit(c) {
+            after_digit = true;
+        } else {
+            return Err((i, expected));
+        }
+    }
+    Ok(s.chars().filter(|&c| c != '_').collect())
+}
+
+/// Check the whole part of a decimal number, which may group its
+/// digits in threes with commas or separate them with underscores, and
+/// return the digits.
+fn decimal_digits(s: &str) -> Result<String, Failure> {
+    if !s.contains(',') {
+        return without_underscores(s, |c| c.is_ascii_digit(), "a digit");
+    }
+    let mut digits = String::new();
+    let mut at = 0;
+    for (n, group) in s.split(',').enumerate() {
+        let group_digits = without_underscores(group, |c| c.is_ascii_digit(), "a digit")
+            .map_err(|(i, e)| (at + i, e))?;
+        if group.len() > 3 {
+            return Err((at + 3, "a comma"));
+        }
+        if n > 0 && group.len() < 3 {
+            return Err((at + group.len(), "a digit"));
+        }
+        digits.push_str(&group_digits);
+        at += group.len() + 1;
+    }
+    Ok(digits)
+}
+
+/// Check the fractional part and exponent of a decimal number, and
+/// return them without any underscores.
+fn fraction_and_exponent(s: &str) -> Result<String, Failure> {
+    let mut out = String::new();
+    let mut rest = s;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        out.push('.');
+        let end = fraction.find(['e', 'E']).unwrap_or(fraction.len());
+        let digits = &fraction[..end];
+        // A trailing decimal point is fine, as in "1." or "1.e9".
+        if !digits.is_empty() {
+            let digits = without_underscores(digits, |c| c.is_ascii_digit(), "a digit")
+                .map_err(|(i, e)| (1 + i, e))?;
+            out.push_str(&digits);
+        }
+        rest = &fraction[end..];
+    }
+    let at = s.len() - rest.len();
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        out.push('e');
+        let (negative, digits) = split_sign(exponent);
+        if negative {
+            out.push('-');
+        }
+        let skipped = 1 + exponent.len() - digits.len();
+        let digits = without_underscores(digits, |c| c.is_ascii_digit(), "a digit in the exponent")
+            .map_err(|(i, e)| (at + skipped + i, e))?;
+        out.push_str(&digits);
+    } else if !rest.is_empty() {
+        return Err((at, "a digit"));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_decimal() {
+        assert_eq!(parse_value(" 1234567890\n"), Ok(Value::Int(1234567890)));
+        assert_eq!(parse_value("-1234567890"), Ok(Value::Int(-1234567890)));
+        assert_eq!(parse_value("+0"), Ok(Value::Int(0)));
+    }
+    #[test]
+    fn parse_hex() {
+        assert_eq!(
+            parse_value("0x1dc7711a73088f5"),
+            Ok(Value::Int(0x1dc7711a73088f5))
+        );
+        assert_eq!(parse_value("0XFF_FF"), Ok(Value::Int(0xffff)));
+        assert_eq!(parse_value("-0x10"), Ok(Value::Int(-16)));
+    }
+    #[test]
+    fn parse_octal_and_binary() {
+        assert_eq!(parse_value("0o11145401322"), Ok(Value::Int(1234567890)));
+        assert_eq!(
+            parse_value("0b1001001100101100000001011010010"),
+            Ok(Value::Int(1234567890))
+        );
+        assert_eq!(parse_value("-0B1_0000"), Ok(Value::Int(-16)));
+        assert!(parse_value("0o8").is_err());
+        assert!(parse_value("0b2").is_err());
+        assert!(parse_value("0x+1").is_err());
+    }
+    #[test]
+    fn parse_plain_hex() {
+        assert_eq!(super::parse_hex("ff"), Ok(255));
+        assert_eq!(super::parse_hex(" 0xFF\n"), Ok(255));
+        assert_eq!(super::parse_hex("-10"), Ok(-16));
+        assert_eq!(super::parse_hex("7fff_ffff_ffff_ffff"), Ok(i64::MAX));
+        assert!(super::parse_hex("8000000000000000").is_err());
+        assert!(super::parse_hex("").is_err());
+        assert!(super::parse_hex("0x").is_err());
+        assert!(super::parse_hex("fg").is_err());
+    }
+    #[test]
+    fn parse_underscores() {
+        assert_eq!(
+            parse_value("12_879_041_490_000_000"),
+            Ok(Value::Int(12_879_041_490_000_000))
+        );
+        assert!(parse_value("_12").is_err());
+        assert!(parse_value("12_").is_err());
+        assert!(parse_value("1__2").is_err());
+    }
+    #[test]
+    fn parse_commas() {
+        assert_eq!(
+            parse_value("12,879,041,490,000,000"),
+            Ok(Value::Int(12_879_041_490_000_000))
+        );
+        assert_eq!(parse_value("39,857.980208"), Ok(Value::Float(39857.980208)));
+        assert!(parse_value("1,5").is_err());
+        assert!(parse_value("1234,567").is_err());
+        assert!(parse_value(",123").is_err());
+    }
+    #[test]
+    fn parse_float() {
+        assert_eq!(
+            parse_value("39857.980208333334"),
+            Ok(Value::Float(39857.980208333334))
+        );
+        assert_eq!(parse_value("-1.5E3"), Ok(Value::Float(-1500.0)));
+        assert!(parse_value("1e999").is_err());
+    }
+    #[test]
+    fn parse_limits() {
+        assert_eq!(
+            parse_value("-9223372036854775808"),
+            Ok(Value::Int(i64::MIN))
+        );
+        assert!(parse_value("9223372036854775808").is_err());
+        assert!(parse_value("0xffffffffffffffff").is_err());
+    }
+    #[test]
+    fn parse_garbage() {
+        assert!(parse_value("").is_err());
+        assert!(parse_value("-").is_err());
+        assert!(parse_value("0x").is_err());
+        assert!(parse_value("12abc").is_err());
+        assert!(parse_value("1.2.3").is_err());
+        assert_eq!(
+            parse_value("nope").unwrap_err().to_string(),
+            "invalid number: \"nope\": expected a digit at offset 0"
+        );
+    }
+    #[test]
+    fn parse_error_offsets() {
+        for (s, offset, expected) in [
+            ("", 0, "a digit"),
+            ("  -", 3, "a digit"),
+            (" 12abc ", 3, "a digit"),
+            ("1.2.3", 3, "a digit"),
+            ("1e", 2, "a digit in the exponent"),
+            ("1.5e+x", 5, "a digit in the exponent"),
+            ("1__2", 1, "a digit"),
+            ("1234,567", 3, "a comma"),
+            ("1,5", 3, "a digit"),
+            ("0x12g4", 4, "a hex digit"),
+            ("0o8", 2, "an octal digit"),
+            ("-0b102", 5, "a binary digit"),
+            (" 9223372036854775808", 1, IN_RANGE),
+            ("1e999", 0, "a finite number"),
+        ] {
+            let err = parse_value(s).unwrap_err();
+            assert_eq!((err.offset(), err.expected()), (offset, expected), "{s:?}");
+            assert_eq!(err.input(), s);
+        }
+        let err = super::parse_hex("  0xffff_fffg").unwrap_err();
+        assert_eq!((err.offset(), err.expected()), (12, "a hex digit"));
+    }
+    #[test]
+    fn value_as_i64() {
+        assert_eq!(Value::Float(1234567900.0).as_i64(), Some(1234567900));
+        assert_eq!(Value::Float(1.5).as_i64(), None);
+        assert_eq!(Value::Float(1e300).as_i64(), None);
+        assert_eq!(Value::Int(7).as_f64(), 7.0);
+    }
+    #[test]
+    fn datetimes() {
+        let expected = NaiveDate::from_ymd_opt(2009, 2, 13)
+            .unwrap()
+            .and_hms_opt(23, 31, 30)
+            .unwrap();
+        for s in [
+            "2009-02-13 23:31:30",
+            "2009-02-13T23:31:30",
+            "2009-02-13T23:31:30z",
+            " 2009-02-13T23:31:30Z\n",
+            "2009-02-13T18:31:30-05:00",
+            "2009-02-13 18:31:30-0500",
+        ] {
+            assert_eq!(parse_datetime(s), Some(expected), "{:?}", s);
+        }
+        let ndt = parse_datetime("2009-02-13T23:31:30.654321Z").unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.654321");
+        let ndt = parse_datetime("2009-02-13 23:31").unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:00");
+    }
+    #[test]
+    fn not_datetimes() {
+        for s in [
+            "",
+            "1234567890",
+            "2009-02-30",
+            "2009-02-13 25:00:00",
+            "2009-02-13 23:31:30 UTC",
+            "13/02/2009",
+        ] {
+            assert_eq!(parse_datetime(s), None, "{:?}", s);
+        }
+    }
+}
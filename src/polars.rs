@@ -0,0 +1,154 @@
+//! Convert whole [Polars](https://pola.rs) series of epoch values,
+//! available with the `polars` feature.
+
+use chrono::DateTime;
+use polars::prelude::{
+    ChunkApply, DataType, Int64Chunked, IntoSeries, PolarsError, PolarsResult, Series, TimeUnit,
+};
+
+use crate::{convert_slice, Epoch};
+
+/// Convert a series of integers in the given epoch to a series of
+/// nanosecond datetimes with the same name. Null values stay null, as
+/// do values that cannot be converted or that fall outside the range of
+/// nanosecond datetimes (roughly 1677 to 2262). The values are
+/// converted all together, as by [convert_slice](../fn.convert_slice.html).
+/// Any integer type is accepted; anything else is an error.
+///
+/// ```
+/// use epochs::{polars::to_datetime_series, Epoch};
+/// use polars::prelude::*;
+/// let input = Series::new("visited".into(), [Some(12_879_041_490_000_000i64), None]);
+/// let output = to_datetime_series(Epoch::Chrome, &input).unwrap();
+/// assert_eq!(output.name().as_str(), "visited");
+/// assert_eq!(output.dtype(), &DataType::Datetime(TimeUnit::Nanoseconds, None));
+/// let nanos = output.datetime().unwrap().physical();
+/// assert_eq!(nanos.get(0), Some(1_234_567_890_000_000_000));
+/// assert_eq!(nanos.get(1), None);
+/// ```
+pub fn to_datetime_series(epoch: Epoch, input: &Series) -> PolarsResult<Series> {
+    if !input.dtype().is_integer() {
+        return Err(PolarsError::SchemaMismatch(
+            format!("expected integers, not {}", input.dtype()).into(),
+        ));
+    }
+    let input = input.strict_cast(&DataType::Int64)?;
+    let nums = input.i64()?;
+    // Convert each chunk's values at once, then put the nulls back.
+    let mut out = Vec::with_capacity(nums.len());
+    for chunk in nums.downcast_iter() {
+        convert_slice(epoch, chunk.values(), &mut out);
+    }
+    let nanos: Int64Chunked = nums
+        .iter()
+        .zip(out)
+        .map(|(num, ndt)| {
+            num?;
+            ndt?.and_utc().timestamp_nanos_opt()
+        })
+        .collect();
+    Ok(nanos
+        .with_name(input.name().clone())
+        .into_datetime(TimeUnit::Nanoseconds, None)
+        .into_series())
+}
+
+/// Convert a series of datetimes to a series of values in the given
+/// epoch with the same name. Null values stay null. Datetimes in any
+/// time unit are accepted; datetimes with a time zone are converted
+/// from UTC. Anything else is an error.
+///
+/// ```
+/// use epochs::{polars::{from_datetime_series, to_datetime_series}, Epoch};
+/// use polars::prelude::*;
+/// let input = Series::new("now".into(), [1_234_567_890i64]);
+/// let datetimes = to_datetime_series(Epoch::Unix, &input).unwrap();
+/// let output = from_datetime_series(Epoch::Chrome, &datetimes).unwrap();
+/// assert_eq!(output.i64().unwrap().get(0), Some(12_879_041_490_000_000));
+/// ```
+pub fn from_datetime_series(epoch: Epoch, input: &Series) -> PolarsResult<Series> {
+    let f = epoch.inverter();
+    let DataType::Datetime(_, zone) = input.dtype() else {
+        return Err(PolarsError::SchemaMismatch(
+            format!("expected datetimes, not {}", input.dtype()).into(),
+        ));
+    };
+    // The values of a datetime with a time zone are still UTC.
+    let input = input.strict_cast(&DataType::Datetime(TimeUnit::Nanoseconds, zone.clone()))?;
+    // Map the values where they are, keeping the nulls as they are.
+    let values = input
+        .datetime()?
+        .physical()
+        .apply_values(|nanos| f(DateTime::from_timestamp_nanos(nanos).naive_utc()));
+    Ok(values.with_name(input.name().clone()).into_series())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use polars::prelude::NamedFrom;
+
+    #[test]
+    fn to_datetime_series_out_of_range() {
+        let input = Series::new("x".into(), [0, 1234567890, i64::MAX]);
+        let output = to_datetime_series(Epoch::Unix, &input).unwrap();
+        let nanos = output.datetime().unwrap().physical();
+        assert_eq!(nanos.len(), 3);
+        assert_eq!(nanos.get(1), Some(1_234_567_890_000_000_000));
+        assert_eq!(nanos.get(2), None);
+    }
+    #[test]
+    fn to_datetime_series_other_integers() {
+        let input = Series::new("x".into(), [1234567890i32]);
+        let output = to_datetime_series(Epoch::Unix, &input).unwrap();
+        let nanos = output.datetime().unwrap().physical();
+        assert_eq!(nanos.get(0), Some(1_234_567_890_000_000_000));
+    }
+    #[test]
+    fn to_datetime_series_not_integers() {
+        let input = Series::new("x".into(), ["1234567890"]);
+        assert!(to_datetime_series(Epoch::Unix, &input).is_err());
+        let input = Series::new("x".into(), [1234567890.0]);
+        assert!(to_datetime_series(Epoch::Unix, &input).is_err());
+    }
+    #[test]
+    fn from_datetime_series_not_datetimes() {
+        let input = Series::new("x".into(), [1234567890i64]);
+        assert!(from_datetime_series(Epoch::Unix, &input).is_err());
+    }
+    #[test]
+    fn from_datetime_series_milliseconds() {
+        let input = Series::new("x".into(), [1_234_567_890_000i64])
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap();
+        let output = from_datetime_series(Epoch::Unix, &input).unwrap();
+        assert_eq!(output.i64().unwrap().get(0), Some(1234567890));
+    }
+    #[test]
+    fn to_datetime_series_chunks() {
+        let mut input = Series::new("x".into(), [Some(0i64), None]);
+        input
+            .append(&Series::new("x".into(), [1234567890i64]))
+            .unwrap();
+        assert_eq!(input.n_chunks(), 2);
+        let output = to_datetime_series(Epoch::Unix, &input).unwrap();
+        let nanos = output.datetime().unwrap().physical();
+        assert_eq!(nanos.get(0), Some(0));
+        assert_eq!(nanos.get(1), None);
+        assert_eq!(nanos.get(2), Some(1_234_567_890_000_000_000));
+    }
+    #[test]
+    fn round_trip() {
+        let input = Series::new(
+            "x".into(),
+            [Some(128790414900000000i64), None, Some(0x1cabbaa00ca9000)],
+        );
+        let output = from_datetime_series(
+            Epoch::WindowsFile,
+            &to_datetime_series(Epoch::WindowsFile, &input).unwrap(),
+        )
+        .unwrap();
+        assert!(output.equals_missing(&input));
+    }
+}
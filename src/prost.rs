@@ -0,0 +1,105 @@
+//! Convert epoch values to and from the protobuf well-known
+//! [Timestamp](https://protobuf.dev/reference/protobuf/google.protobuf/#timestamp)
+//! type, as generated by [prost](https://github.com/tokio-rs/prost),
+//! available with the `prost` feature.
+
+use chrono::DateTime;
+use prost_types::Timestamp;
+
+use crate::Epoch;
+
+/// Convert the given number in the given epoch to a Timestamp, or None
+/// if it cannot be converted. A leap second is folded into the last
+/// nanosecond of the second before it, since a Timestamp has no room
+/// for one.
+///
+/// ```
+/// use epochs::{prost::to_timestamp, Epoch};
+/// let ts = to_timestamp(Epoch::WindowsFile, 128_790_414_906_543_210).unwrap();
+/// assert_eq!(ts.seconds, 1_234_567_890);
+/// assert_eq!(ts.nanos, 654_321_000);
+/// ```
+pub fn to_timestamp(epoch: Epoch, num: i64) -> Option<Timestamp> {
+    let dt = epoch.to_datetime(num)?.and_utc();
+    Some(Timestamp {
+        seconds: dt.timestamp(),
+        nanos: dt.timestamp_subsec_nanos().min(999_999_999) as i32,
+    })
+}
+
+/// Convert the given Timestamp to a number in the given epoch, or None
+/// if it is out of range. A Timestamp with nanos outside of 0 to
+/// 999,999,999 is normalized first, as protobuf does.
+///
+/// ```
+/// use epochs::{prost::from_timestamp, Epoch};
+/// use prost_types::Timestamp;
+/// let ts = Timestamp { seconds: 1_234_567_890, nanos: 654_321_000 };
+/// assert_eq!(from_timestamp(Epoch::Chrome, &ts), Some(12_879_041_490_654_321));
+/// ```
+pub fn from_timestamp(epoch: Epoch, ts: &Timestamp) -> Option<i64> {
+    let mut ts = *ts;
+    ts.normalize();
+    let ndt = DateTime::from_timestamp(ts.seconds, ts.nanos as u32)?.naive_utc();
+    epoch.try_from_datetime(ndt).ok()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn before_unix_epoch() {
+        let ts = to_timestamp(Epoch::Unix, -1).unwrap();
+        assert_eq!(
+            ts,
+            Timestamp {
+                seconds: -1,
+                nanos: 0
+            }
+        );
+        let ts = to_timestamp(Epoch::Java, -1).unwrap();
+        assert_eq!(
+            ts,
+            Timestamp {
+                seconds: -1,
+                nanos: 999_000_000
+            }
+        );
+    }
+    #[test]
+    fn out_of_range() {
+        assert_eq!(to_timestamp(Epoch::Unix, i64::MAX), None);
+        let ts = Timestamp {
+            seconds: i64::MAX,
+            nanos: 0,
+        };
+        assert_eq!(from_timestamp(Epoch::Unix, &ts), None);
+        let ts = Timestamp {
+            seconds: 1i64 << 40,
+            nanos: 0,
+        };
+        assert_eq!(from_timestamp(Epoch::Apfs, &ts), None);
+    }
+    #[test]
+    fn normalized() {
+        let ts = Timestamp {
+            seconds: 1_234_567_891,
+            nanos: -1_000_000,
+        };
+        assert_eq!(from_timestamp(Epoch::Java, &ts), Some(1_234_567_890_999));
+    }
+    #[test]
+    fn round_trip() {
+        for epoch in Epoch::ALL {
+            let num = epoch.from_datetime(
+                DateTime::from_timestamp(1_234_567_890, 0)
+                    .unwrap()
+                    .naive_utc(),
+            );
+            let ts = to_timestamp(epoch, num).unwrap();
+            assert_eq!(from_timestamp(epoch, &ts), Some(num), "{}", epoch.name());
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Precision Time Protocol timestamps (IEEE 1588).
+
+use chrono::{DateTime, Duration, NaiveDateTime};
+
+/// The largest number of seconds a PTP timestamp can hold.
+const MAX_SECONDS: i64 = (1 << 48) - 1;
+
+/// PTP time is a 48-bit count of seconds plus a 32-bit count of
+/// nanoseconds since 1970-01-01 TAI. This returns the TAI time, which
+/// is ahead of UTC by however many leap seconds there have been (37
+/// since 2017); use [ptp_utc](fn.ptp_utc.html) to get UTC. Returns
+/// None if the seconds don't fit in 48 bits or the nanoseconds make up
+/// a whole second or more.
+///
+/// ```
+/// use epochs::ptp;
+/// let ndt = ptp(1_234_567_924, 500_000_000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:32:04.500");
+/// ```
+pub fn ptp(seconds: i64, nanos: u32) -> Option<NaiveDateTime> {
+    if !(0..=MAX_SECONDS).contains(&seconds) || nanos >= 1_000_000_000 {
+        return None;
+    }
+    DateTime::from_timestamp(seconds, nanos).map(|dt| dt.naive_utc())
+}
+
+/// Like [ptp](fn.ptp.html), but subtract the given UTC offset (the
+/// currentUtcOffset field of a PTP Announce message) to get UTC.
+///
+/// ```
+/// use epochs::ptp_utc;
+/// let ndt = ptp_utc(1_234_567_924, 500_000_000, 34).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.500");
+/// ```
+pub fn ptp_utc(seconds: i64, nanos: u32, utc_offset: i32) -> Option<NaiveDateTime> {
+    ptp(seconds, nanos)?.checked_sub_signed(Duration::seconds(utc_offset as i64))
+}
+
+/// Convert the given TAI NaiveDateTime to a [PTP](fn.ptp.html) time,
+/// returning the seconds and the nanoseconds. Returns None for times
+/// before 1970 or too late for 48 bits of seconds. A leap second is
+/// folded into the last nanosecond of the second before it, since PTP
+/// counts TAI, which has none.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_ptp;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:32:04.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_ptp(ndt), Some((1_234_567_924, 500_000_000)));
+/// ```
+pub fn to_ptp(ndt: NaiveDateTime) -> Option<(i64, u32)> {
+    let dt = ndt.and_utc();
+    let seconds = dt.timestamp();
+    if !(0..=MAX_SECONDS).contains(&seconds) {
+        return None;
+    }
+    Some((seconds, dt.timestamp_subsec_nanos().min(999_999_999)))
+}
+
+/// Convert the given UTC NaiveDateTime to a [PTP](fn.ptp.html) time,
+/// adding the given UTC offset to get TAI. Returns None if the
+/// result is out of range, as for [to_ptp](fn.to_ptp.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_ptp_utc;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+/// assert_eq!(to_ptp_utc(ndt, 34), Some((1_234_567_924, 500_000_000)));
+/// ```
+pub fn to_ptp_utc(ndt: NaiveDateTime, utc_offset: i32) -> Option<(i64, u32)> {
+    let tai = ndt.checked_add_signed(Duration::seconds(utc_offset as i64))?;
+    to_ptp(tai)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn ptp_epoch() {
+        let ndt = ptp(0, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1970-01-01 00:00:00");
+    }
+    #[test]
+    fn ptp_out_of_range() {
+        assert!(ptp(-1, 0).is_none());
+        assert!(ptp(1 << 48, 0).is_none());
+        assert!(ptp(0, 1_000_000_000).is_none());
+    }
+    #[test]
+    fn ptp_beyond_chrono() {
+        // 48 bits of seconds goes well past the year 262,143.
+        assert!(ptp(MAX_SECONDS, 999_999_999).is_none());
+    }
+    #[test]
+    fn ptp_utc_current_offset() {
+        let ndt = ptp_utc(1_700_000_037, 0, 37).unwrap();
+        assert_eq!(ndt, crate::unix(1_700_000_000).unwrap());
+    }
+    #[test]
+    fn to_ptp_before_1970() {
+        let ndt = crate::unix(-1).unwrap();
+        assert_eq!(to_ptp(ndt), None);
+        assert_eq!(to_ptp_utc(crate::unix(0).unwrap(), -1), None);
+        assert_eq!(to_ptp_utc(crate::unix(0).unwrap(), 0), Some((0, 0)));
+    }
+    #[test]
+    fn to_ptp_latest() {
+        // The last time chrono can hold is far short of 48 bits of
+        // seconds, so every time from 1970 on has a PTP time.
+        let (seconds, nanos) = to_ptp(NaiveDateTime::MAX).unwrap();
+        assert!(seconds < MAX_SECONDS);
+        assert_eq!(ptp(seconds, nanos), Some(NaiveDateTime::MAX));
+    }
+    #[test]
+    fn to_ptp_leap_second() {
+        // 2008-12-31 23:59:60.5
+        let leap = DateTime::from_timestamp(1_230_767_999, 1_500_000_000)
+            .unwrap()
+            .naive_utc();
+        let (seconds, nanos) = to_ptp(leap).unwrap();
+        assert_eq!((seconds, nanos), (1_230_767_999, 999_999_999));
+        assert!(ptp(seconds, nanos).is_some());
+    }
+    #[test]
+    fn round_trip() {
+        let (seconds, nanos) = (1_234_567_924, 123_456_789);
+        assert_eq!(to_ptp(ptp(seconds, nanos).unwrap()), Some((seconds, nanos)));
+        let utc = ptp_utc(seconds, nanos, 37).unwrap();
+        assert_eq!(to_ptp_utc(utc, 37), Some((seconds, nanos)));
+    }
+}
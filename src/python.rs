@@ -0,0 +1,397 @@
+//! Python bindings for the conversions, built with
+//! [maturin](https://www.maturin.rs) when the `python` feature is
+//! enabled.
+//!
+//! Every conversion in [all_epochs](../fn.all_epochs.html) is wrapped
+//! under its Rust name, along with its `to_` inverse. Byte formats take
+//! and return Python bytes, and the .NET `Kind` is a string ("utc",
+//! "local", or "unspecified"). `to_datetime` and `from_datetime` take
+//! any name `Epoch` parses, so an epoch added to the table is in Python
+//! too.
+//!
+//! Python's datetime only covers the years 1 to 9999, so conversions
+//! which land outside that range return None.
+//!
+//! ```text
+//! $ maturin develop --release
+//! $ python -c 'import epochs; print(epochs.chrome(12879041490654321))'
+//! 2009-02-13 23:31:30.654321
+//! $ python -c 'import epochs; print(epochs.to_datetime("palm", 3317412690))'
+//! 2009-02-13 23:31:30
+//! ```
+
+use chrono::{Datelike, FixedOffset, NaiveDate, NaiveDateTime};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{DotnetKind, Epoch};
+
+/// Wrap conversions as Python functions with the same names as the Rust
+/// ones, and register them, along with the functions written by hand,
+/// in one place. Results of the `forward` and `by_ref` conversions are
+/// kept only if Python can represent them; `by_ref` passes its
+/// arguments to Rust by reference; `backward` results pass straight
+/// through.
+macro_rules! wrap {
+    (
+        forward { $($name:ident($($arg:ident: $t:ty),*) -> $ret:ty;)* }
+        by_ref { $($ref_name:ident($($ref_arg:ident: $ref_t:ty),*) -> $ref_ret:ty;)* }
+        backward { $($to_name:ident($($to_arg:ident: $to_t:ty),*) -> $to_ret:ty;)* }
+        by_hand { $($hand:ident),* $(,)? }
+    ) => {
+        $(
+            #[pyfunction]
+            fn $name($($arg: $t),*) -> $ret {
+                super::$name($($arg),*).representable()
+            }
+        )*
+
+        $(
+            #[pyfunction]
+            fn $ref_name($($ref_arg: $ref_t),*) -> $ref_ret {
+                super::$ref_name($(&$ref_arg),*).representable()
+            }
+        )*
+
+        $(
+            #[pyfunction]
+            fn $to_name($($to_arg: $to_t),*) -> $to_ret {
+                super::$to_name($($to_arg),*)
+            }
+        )*
+
+        /// Add every function to the module.
+        fn add_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+            $(m.add_function(wrap_pyfunction!($name, m)?)?;)*
+            $(m.add_function(wrap_pyfunction!($ref_name, m)?)?;)*
+            $(m.add_function(wrap_pyfunction!($to_name, m)?)?;)*
+            $(m.add_function(wrap_pyfunction!($hand, m)?)?;)*
+            Ok(())
+        }
+
+        /// The names of the functions in the module.
+        #[cfg(test)]
+        const FUNCTIONS: &[&str] = &[
+            $(stringify!($name),)*
+            $(stringify!($ref_name),)*
+            $(stringify!($to_name),)*
+            $(stringify!($hand),)*
+        ];
+    };
+}
+
+wrap! {
+    forward {
+        amiga(days: i64, minutes: i64, ticks: i64) -> Option<NaiveDateTime>;
+        apfs(num: i64) -> Option<NaiveDateTime>;
+        as400_dts(num: u64) -> Option<NaiveDateTime>;
+        bcd_rtc(bytes: &[u8]) -> Option<NaiveDateTime>;
+        beidou_t(week: i32, sow: f64) -> Option<NaiveDateTime>;
+        besselian_year(year: f64) -> Option<NaiveDateTime>;
+        cdma(num: i64) -> Option<NaiveDateTime>;
+        cdma_frames(num: i64) -> Option<NaiveDateTime>;
+        chrome(num: i64) -> Option<NaiveDateTime>;
+        cics(num: i64) -> Option<NaiveDateTime>;
+        cocoa(num: i64) -> Option<NaiveDateTime>;
+        cocoa_f64(num: f64) -> Option<NaiveDateTime>;
+        excel_1900(serial: f64) -> Option<NaiveDateTime>;
+        excel_1900_date(serial: i64) -> Option<NaiveDate>;
+        excel_1904(serial: f64) -> Option<NaiveDateTime>;
+        excel_1904_date(serial: i64) -> Option<NaiveDate>;
+        exfat(timestamp: u32, ten_ms: u8, utc_offset: i8)
+            -> Option<(NaiveDateTime, Option<FixedOffset>)>;
+        ext4(seconds: i32, extra: u32) -> Option<NaiveDateTime>;
+        fat(date: u16, time: u16, tenths: Option<u8>) -> Option<NaiveDateTime>;
+        filemaker(num: i64) -> Option<NaiveDateTime>;
+        galileo_st(week: i32, tow: f64) -> Option<NaiveDateTime>;
+        garmin_fit(num: u32) -> Option<NaiveDateTime>;
+        glonass(n4: u32, nt: u32, tod: f64) -> Option<NaiveDateTime>;
+        go_binary(bytes: &[u8]) -> Option<(NaiveDateTime, FixedOffset)>;
+        google_calendar(num: i64) -> Option<NaiveDateTime>;
+        gps_week_tow(week: i32, tow: f64) -> Option<NaiveDateTime>;
+        horolog(days: i64, seconds: i64) -> Option<NaiveDateTime>;
+        icq(days: f64) -> Option<NaiveDateTime>;
+        j2000_seconds(seconds: f64) -> Option<NaiveDateTime>;
+        java(num: i64) -> Option<NaiveDateTime>;
+        java_instant(seconds: i64, nanos: u32) -> Option<NaiveDateTime>;
+        julian_epoch_year(year: f64) -> Option<NaiveDateTime>;
+        labview(seconds: i64, fraction: u64) -> Option<NaiveDateTime>;
+        mjd_days(days: i64) -> Option<NaiveDate>;
+        mozilla(num: i64) -> Option<NaiveDateTime>;
+        mssql_datetime(days: i32, ticks: i32) -> Option<NaiveDateTime>;
+        mssql_datetime2(bytes: &[u8], scale: u8) -> Option<NaiveDateTime>;
+        mssql_datetimeoffset(bytes: &[u8], scale: u8) -> Option<(NaiveDateTime, FixedOffset)>;
+        mssql_smalldatetime(days: u16, minutes: u16) -> Option<NaiveDateTime>;
+        ntp(num: u64) -> Option<NaiveDateTime>;
+        ntp_date(num: u128) -> Option<NaiveDateTime>;
+        palm(num: u32) -> Option<NaiveDateTime>;
+        ptp(seconds: i64, nanos: u32) -> Option<NaiveDateTime>;
+        quicktime(num: u64) -> Option<NaiveDateTime>;
+        rata_die(days: i64) -> Option<NaiveDate>;
+        sas_date(days: i64) -> Option<NaiveDate>;
+        stata_date(days: i64) -> Option<NaiveDate>;
+        symbian(num: i64) -> Option<NaiveDateTime>;
+        tai(num: i64) -> Option<NaiveDateTime>;
+        tandem(num: i64) -> Option<NaiveDateTime>;
+        tod_clock(num: u64) -> Option<NaiveDateTime>;
+        tod_clock_extended(num: u128) -> Option<NaiveDateTime>;
+        unix(num: i64) -> Option<NaiveDateTime>;
+        unix_i32(num: i32) -> Option<NaiveDateTime>;
+        unix_right(num: i64) -> Option<NaiveDateTime>;
+        unix_u32(num: u32) -> Option<NaiveDateTime>;
+        uuid_v1(num: i64) -> Option<NaiveDateTime>;
+        windows_date(num: i64) -> Option<NaiveDateTime>;
+        windows_file(num: i64) -> Option<NaiveDateTime>;
+        windows_file_parts(low: u32, high: u32) -> Option<NaiveDateTime>;
+        zigbee(num: u32) -> Option<NaiveDateTime>;
+    }
+    by_ref {
+        bplist_date(bytes: [u8; 8]) -> Option<NaiveDateTime>;
+        gsm_scts(bytes: [u8; 7]) -> Option<(NaiveDateTime, FixedOffset)>;
+        iso9660_dec_datetime(bytes: [u8; 17]) -> Option<(NaiveDateTime, FixedOffset)>;
+        iso9660_dir_datetime(bytes: [u8; 7]) -> Option<(NaiveDateTime, FixedOffset)>;
+        oracle_date(bytes: [u8; 7]) -> Option<NaiveDateTime>;
+        oracle_timestamp(bytes: [u8; 11]) -> Option<NaiveDateTime>;
+        parquet_int96(bytes: [u8; 12]) -> Option<NaiveDateTime>;
+        systemtime_from_le_bytes(bytes: [u8; 16]) -> Option<NaiveDateTime>;
+    }
+    backward {
+        to_amiga(ndt: NaiveDateTime) -> (i64, i64, i64);
+        to_apfs(ndt: NaiveDateTime) -> i64;
+        to_as400_dts(ndt: NaiveDateTime) -> Option<u64>;
+        to_bcd_rtc(ndt: NaiveDateTime, twelve_hour: bool) -> Option<[u8; 7]>;
+        to_beidou_t(ndt: NaiveDateTime) -> (i32, f64);
+        to_besselian_year(ndt: NaiveDateTime) -> f64;
+        to_bplist_date(ndt: NaiveDateTime) -> [u8; 8];
+        to_cdma(ndt: NaiveDateTime) -> i64;
+        to_cdma_frames(ndt: NaiveDateTime) -> i64;
+        to_chrome(ndt: NaiveDateTime) -> i64;
+        to_cics(ndt: NaiveDateTime) -> i64;
+        to_cocoa(ndt: NaiveDateTime) -> i64;
+        to_cocoa_f64(ndt: NaiveDateTime) -> f64;
+        to_excel_1900(ndt: NaiveDateTime) -> Option<f64>;
+        to_excel_1900_date(date: NaiveDate) -> Option<i64>;
+        to_excel_1904(ndt: NaiveDateTime) -> Option<f64>;
+        to_excel_1904_date(date: NaiveDate) -> Option<i64>;
+        to_exfat(ndt: NaiveDateTime, offset: Option<FixedOffset>) -> Option<(u32, u8, i8)>;
+        to_ext4(ndt: NaiveDateTime) -> Option<(i32, u32)>;
+        to_fat(ndt: NaiveDateTime) -> Option<(u16, u16, u8)>;
+        to_filemaker(ndt: NaiveDateTime) -> Option<i64>;
+        to_galileo_st(ndt: NaiveDateTime) -> (i32, f64);
+        to_garmin_fit(ndt: NaiveDateTime) -> Option<u32>;
+        to_glonass(ndt: NaiveDateTime) -> Option<(u32, u32, f64)>;
+        to_go_binary(ndt: NaiveDateTime, offset: FixedOffset) -> Vec<u8>;
+        to_google_calendar(ndt: NaiveDateTime) -> i64;
+        to_gps_week_tow(ndt: NaiveDateTime) -> (i32, f64);
+        to_gsm_scts(ndt: NaiveDateTime, offset: FixedOffset) -> Option<[u8; 7]>;
+        to_horolog(ndt: NaiveDateTime) -> (i64, i64);
+        to_icq(ndt: NaiveDateTime) -> f64;
+        to_iso9660_dec_datetime(ndt: NaiveDateTime, offset: FixedOffset) -> Option<[u8; 17]>;
+        to_iso9660_dir_datetime(ndt: NaiveDateTime, offset: FixedOffset) -> Option<[u8; 7]>;
+        to_j2000_seconds(ndt: NaiveDateTime) -> f64;
+        to_java(ndt: NaiveDateTime) -> i64;
+        to_java_instant(ndt: NaiveDateTime) -> (i64, u32);
+        to_julian_epoch_year(ndt: NaiveDateTime) -> f64;
+        to_labview(ndt: NaiveDateTime) -> (i64, u64);
+        to_mjd_days(date: NaiveDate) -> i64;
+        to_mozilla(ndt: NaiveDateTime) -> i64;
+        to_mssql_datetime(ndt: NaiveDateTime) -> Option<(i32, i32)>;
+        to_mssql_datetime2(ndt: NaiveDateTime, scale: u8) -> Option<Vec<u8>>;
+        to_mssql_datetimeoffset(ndt: NaiveDateTime, offset: FixedOffset, scale: u8)
+            -> Option<Vec<u8>>;
+        to_mssql_smalldatetime(ndt: NaiveDateTime) -> Option<(u16, u16)>;
+        to_ntp(ndt: NaiveDateTime) -> u64;
+        to_ntp_date(ndt: NaiveDateTime) -> u128;
+        to_oracle_date(ndt: NaiveDateTime) -> Option<[u8; 7]>;
+        to_oracle_timestamp(ndt: NaiveDateTime) -> Option<[u8; 11]>;
+        to_palm(ndt: NaiveDateTime) -> Option<u32>;
+        to_parquet_int96(ndt: NaiveDateTime) -> [u8; 12];
+        to_ptp(ndt: NaiveDateTime) -> Option<(i64, u32)>;
+        to_quicktime(ndt: NaiveDateTime) -> Option<u64>;
+        to_rata_die(date: NaiveDate) -> i64;
+        to_sas_date(date: NaiveDate) -> i64;
+        to_stata_date(date: NaiveDate) -> i64;
+        to_symbian(ndt: NaiveDateTime) -> i64;
+        to_tai(ndt: NaiveDateTime) -> i64;
+        to_tandem(ndt: NaiveDateTime) -> i64;
+        to_tod_clock(ndt: NaiveDateTime) -> Option<u64>;
+        to_tod_clock_extended(ndt: NaiveDateTime) -> Option<u128>;
+        to_unix(ndt: NaiveDateTime) -> i64;
+        to_unix_i32(ndt: NaiveDateTime) -> Option<i32>;
+        to_unix_right(ndt: NaiveDateTime) -> i64;
+        to_unix_u32(ndt: NaiveDateTime) -> Option<u32>;
+        to_uuid_v1(ndt: NaiveDateTime) -> i64;
+        to_windows_date(ndt: NaiveDateTime) -> i64;
+        to_windows_file(ndt: NaiveDateTime) -> i64;
+        to_windows_file_parts(ndt: NaiveDateTime) -> Option<(u32, u32)>;
+        to_zigbee(ndt: NaiveDateTime) -> Option<u32>;
+    }
+    by_hand {
+        datetime_to_systemtime,
+        dotnet_binary,
+        to_dotnet_binary,
+        parse_uuid_v1,
+        uuid_v1_fields,
+        to_datetime,
+        from_datetime,
+        guess,
+        guess_in_range,
+        view_all,
+    }
+}
+
+/// Convert a datetime to the 16 little-endian bytes of a Windows
+/// SYSTEMTIME, the inverse of systemtime_from_le_bytes.
+#[pyfunction]
+fn datetime_to_systemtime(ndt: NaiveDateTime) -> Option<[u8; 16]> {
+    super::datetime_to_systemtime(ndt).map(|st| st.to_le_bytes())
+}
+
+/// Convert a .NET `DateTime.ToBinary` value to a (datetime, kind) pair.
+#[pyfunction]
+fn dotnet_binary(num: i64) -> Option<(NaiveDateTime, &'static str)> {
+    let (ndt, kind) = super::dotnet_binary(num).representable()?;
+    let kind = match kind {
+        DotnetKind::Unspecified => "unspecified",
+        DotnetKind::Utc => "utc",
+        DotnetKind::Local => "local",
+    };
+    Some((ndt, kind))
+}
+
+/// Convert a datetime and kind to a .NET `DateTime.ToBinary` value.
+#[pyfunction]
+fn to_dotnet_binary(ndt: NaiveDateTime, kind: &str) -> PyResult<Option<i64>> {
+    let kind = match kind {
+        "unspecified" => DotnetKind::Unspecified,
+        "utc" => DotnetKind::Utc,
+        "local" => DotnetKind::Local,
+        _ => return Err(PyValueError::new_err(format!("unknown kind: {kind}"))),
+    };
+    Ok(super::to_dotnet_binary(ndt, kind))
+}
+
+/// Parse a version 1 UUID string into its (datetime, clock sequence,
+/// node) fields, raising ValueError if it isn't one.
+#[pyfunction]
+fn parse_uuid_v1(s: &str) -> PyResult<(NaiveDateTime, u16, [u8; 6])> {
+    super::parse_uuid_v1(s)
+        .map(|f| (f.timestamp, f.clock_seq, f.node))
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Split a version 1 UUID, given as an integer, into its (datetime,
+/// clock sequence, node) fields.
+#[pyfunction]
+fn uuid_v1_fields(uuid: u128) -> Option<(NaiveDateTime, u16, [u8; 6])> {
+    super::uuid_v1_fields(uuid).map(|f| (f.timestamp, f.clock_seq, f.node))
+}
+
+/// Convert a number in the named epoch to a datetime.
+#[pyfunction]
+fn to_datetime(epoch: &str, num: i64) -> PyResult<Option<NaiveDateTime>> {
+    Ok(parse_epoch(epoch)?.to_datetime(num).representable())
+}
+
+/// Convert a datetime to a number in the named epoch, or None if it is
+/// out of the epoch's range.
+#[pyfunction]
+fn from_datetime(epoch: &str, ndt: NaiveDateTime) -> PyResult<Option<i64>> {
+    Ok(parse_epoch(epoch)?.try_from_datetime(ndt).ok())
+}
+
+/// Guess which epochs the given number might be in, as a list of
+/// (name, datetime) pairs.
+#[pyfunction]
+fn guess(num: i64) -> Vec<(&'static str, NaiveDateTime)> {
+    named(super::guess(num))
+        .into_iter()
+        .filter(|&(_, ndt)| Some(ndt).representable().is_some())
+        .collect()
+}
+
+/// Like guess, but only keep interpretations between start and end.
+#[pyfunction]
+fn guess_in_range(
+    num: i64,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Vec<(&'static str, NaiveDateTime)> {
+    named(super::guess_in_range(num, start, end))
+        .into_iter()
+        .filter(|&(_, ndt)| Some(ndt).representable().is_some())
+        .collect()
+}
+
+/// Interpret the given number under every epoch, as a list of (name,
+/// datetime) pairs with None where the number cannot be converted.
+#[pyfunction]
+fn view_all(num: i64) -> Vec<(&'static str, Option<NaiveDateTime>)> {
+    named(super::view_all(num))
+        .into_iter()
+        .map(|(name, ndt)| (name, ndt.representable()))
+        .collect()
+}
+
+/// Look up an epoch by name, raising ValueError for an unknown one.
+fn parse_epoch(name: &str) -> PyResult<Epoch> {
+    name.parse()
+        .map_err(|e: crate::ParseEpochError| PyValueError::new_err(e.to_string()))
+}
+
+/// Replace each Epoch with its name, since Python knows nothing of the
+/// enum.
+fn named<T>(pairs: Vec<(Epoch, T)>) -> Vec<(&'static str, T)> {
+    pairs
+        .into_iter()
+        .map(|(epoch, t)| (epoch.name(), t))
+        .collect()
+}
+
+/// Results which are kept only if Python's datetime can represent
+/// them.
+trait Representable {
+    fn representable(self) -> Self;
+}
+
+impl Representable for Option<NaiveDateTime> {
+    fn representable(self) -> Self {
+        self.filter(|ndt| (1..=9999).contains(&ndt.year()))
+    }
+}
+
+impl Representable for Option<NaiveDate> {
+    fn representable(self) -> Self {
+        self.filter(|date| (1..=9999).contains(&date.year()))
+    }
+}
+
+impl<T> Representable for Option<(NaiveDateTime, T)> {
+    fn representable(self) -> Self {
+        self.filter(|(ndt, _)| (1..=9999).contains(&ndt.year()))
+    }
+}
+
+/// The `epochs` Python module.
+#[pymodule]
+fn epochs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    add_functions(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_epoch_is_wrapped() {
+        for info in crate::all_epochs() {
+            let to_name = match info.name {
+                "systemtime_from_le_bytes" => "datetime_to_systemtime".to_string(),
+                name => format!("to_{name}"),
+            };
+            for name in [info.name, &to_name] {
+                assert!(FUNCTIONS.contains(&name), "{name} is not in the module");
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Packed-BCD real-time clock registers.
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+#[cfg(feature = "clock")]
+use chrono::{Local, LocalResult};
+
+/// In the hours register, bit 6 selects the 12-hour mode, in which bit
+/// 5 is set for PM.
+const TWELVE_HOUR: u8 = 0x40;
+const PM: u8 = 0x20;
+
+/// Decode one packed-BCD byte.
+fn from_bcd(b: u8) -> Option<u32> {
+    let (tens, units) = (b >> 4, b & 0x0f);
+    if tens > 9 || units > 9 {
+        return None;
+    }
+    Some(u32::from(tens) * 10 + u32::from(units))
+}
+
+/// Encode a number below 100 as a packed-BCD byte.
+fn to_bcd(n: u32) -> u8 {
+    (((n / 10) << 4) | (n % 10)) as u8
+}
+
+/// Real-time clock chips (*e.g.*, the DS1307 and its relatives, or the
+/// MC146818 in BCD mode) keep the time as packed-BCD registers. This
+/// takes six or seven bytes: second, minute, hour, day, month, year
+/// (in the century), and optionally the century, which otherwise is
+/// taken to be 20. The clock-halt bit of the seconds and the century
+/// flag of the month (bit 7 of each) are ignored. If bit 6 of the hour
+/// is set, it's in 12-hour mode, with bit 5 set for PM. Returns None
+/// if there are too few or too many bytes, or any of the digits or
+/// fields are invalid.
+///
+/// ```
+/// use epochs::bcd_rtc;
+/// let ndt = bcd_rtc(&[0x30, 0x31, 0x23, 0x13, 0x02, 0x09]).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// let ndt = bcd_rtc(&[0x30, 0x31, 0x71, 0x13, 0x02, 0x09, 0x20]).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn bcd_rtc(bytes: &[u8]) -> Option<NaiveDateTime> {
+    let (&[second, minute, hour, day, month, year], century) = match bytes.len() {
+        6 => (bytes.try_into().ok()?, 0x20),
+        7 => (bytes[..6].try_into().ok()?, bytes[6]),
+        _ => return None,
+    };
+    let hour = if hour & TWELVE_HOUR != 0 {
+        let h = from_bcd(hour & 0x1f)?;
+        if !(1..=12).contains(&h) {
+            return None;
+        }
+        h % 12 + if hour & PM != 0 { 12 } else { 0 }
+    } else {
+        from_bcd(hour)?
+    };
+    let year = from_bcd(century)? * 100 + from_bcd(year)?;
+    NaiveDate::from_ymd_opt(year as i32, from_bcd(month & 0x7f)?, from_bcd(day)?)?.and_hms_opt(
+        hour,
+        from_bcd(minute)?,
+        from_bcd(second & 0x7f)?,
+    )
+}
+
+/// Encode the given NaiveDateTime as [BCD RTC](fn.bcd_rtc.html)
+/// registers, including the century, with the hour in 12-hour mode if
+/// asked. Fractions of a second are dropped. Returns None for years
+/// outside of 0 to 9999.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_bcd_rtc;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_bcd_rtc(ndt, false), Some([0x30, 0x31, 0x23, 0x13, 0x02, 0x09, 0x20]));
+/// assert_eq!(to_bcd_rtc(ndt, true), Some([0x30, 0x31, 0x71, 0x13, 0x02, 0x09, 0x20]));
+/// ```
+pub fn to_bcd_rtc(ndt: NaiveDateTime, twelve_hour: bool) -> Option<[u8; 7]> {
+    let year = u32::try_from(ndt.year()).ok().filter(|&y| y <= 9999)?;
+    let hour = if twelve_hour {
+        let (pm, h) = ndt.hour12();
+        TWELVE_HOUR | if pm { PM } else { 0 } | to_bcd(h)
+    } else {
+        to_bcd(ndt.hour())
+    };
+    Some([
+        // A leap second is written as second 59.
+        to_bcd(ndt.second().min(59)),
+        to_bcd(ndt.minute()),
+        hour,
+        to_bcd(ndt.day()),
+        to_bcd(ndt.month()),
+        to_bcd(year % 100),
+        to_bcd(year / 100),
+    ])
+}
+
+/// Like [bcd_rtc](fn.bcd_rtc.html), but for a clock known to be set
+/// to local time at the given offset from UTC, as most are, returning
+/// the time with that offset.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::FixedOffset;
+/// use epochs::bcd_rtc_with_offset;
+/// let offset = FixedOffset::east_opt(9 * 60 * 60).unwrap();
+/// let dt = bcd_rtc_with_offset(&[0x30, 0x31, 0x08, 0x14, 0x02, 0x09], offset).unwrap();
+/// assert_eq!(dt.naive_utc().to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn bcd_rtc_with_offset(bytes: &[u8], offset: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    bcd_rtc(bytes)?.and_local_timezone(offset).single()
+}
+
+/// Like [bcd_rtc](fn.bcd_rtc.html), but for a clock set to the local
+/// time zone of this machine, which may be ambiguous or may never have
+/// happened (see [fat_local](fn.fat_local.html)). Returns None if the
+/// registers are invalid.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::Local;
+/// use epochs::{bcd_rtc, bcd_rtc_local};
+/// let bytes = [0x30, 0x31, 0x23, 0x13, 0x02, 0x09];
+/// let ndt = bcd_rtc(&bytes).unwrap();
+/// assert_eq!(bcd_rtc_local(&bytes), Some(ndt.and_local_timezone(Local)));
+/// ```
+#[cfg(feature = "clock")]
+pub fn bcd_rtc_local(bytes: &[u8]) -> Option<LocalResult<DateTime<Local>>> {
+    Some(bcd_rtc(bytes)?.and_local_timezone(Local))
+}
+
+/// Encode the given DateTime as [BCD RTC](fn.bcd_rtc.html) registers
+/// in its own offset from UTC. See [to_bcd_rtc](fn.to_bcd_rtc.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::DateTime;
+/// use epochs::to_bcd_rtc_with_offset;
+/// let dt = DateTime::parse_from_rfc3339("2009-02-14T08:31:30+09:00").unwrap();
+/// assert_eq!(
+///     to_bcd_rtc_with_offset(dt, false),
+///     Some([0x30, 0x31, 0x08, 0x14, 0x02, 0x09, 0x20])
+/// );
+/// ```
+pub fn to_bcd_rtc_with_offset(dt: DateTime<FixedOffset>, twelve_hour: bool) -> Option<[u8; 7]> {
+    to_bcd_rtc(dt.naive_local(), twelve_hour)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn bcd_rtc_twelve_hour_edges() {
+        // 12 AM is midnight and 12 PM is noon.
+        let ndt = bcd_rtc(&[0x00, 0x00, 0x52, 0x01, 0x01, 0x24]).unwrap();
+        assert_eq!(ndt.to_string(), "2024-01-01 00:00:00");
+        let ndt = bcd_rtc(&[0x00, 0x00, 0x72, 0x01, 0x01, 0x24]).unwrap();
+        assert_eq!(ndt.to_string(), "2024-01-01 12:00:00");
+        // There is no hour 0 or 13 in 12-hour mode.
+        assert!(bcd_rtc(&[0x00, 0x00, 0x40, 0x01, 0x01, 0x24]).is_none());
+        assert!(bcd_rtc(&[0x00, 0x00, 0x53, 0x01, 0x01, 0x24]).is_none());
+    }
+    #[test]
+    fn bcd_rtc_ignores_flag_bits() {
+        // Clock halted, and the century flag set.
+        let ndt = bcd_rtc(&[0xb0, 0x31, 0x23, 0x13, 0x82, 0x09]).unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn bcd_rtc_century() {
+        let ndt = bcd_rtc(&[0x00, 0x00, 0x00, 0x31, 0x12, 0x99, 0x19]).unwrap();
+        assert_eq!(ndt.to_string(), "1999-12-31 00:00:00");
+    }
+    #[test]
+    fn bcd_rtc_bad_input() {
+        assert!(bcd_rtc(&[0x30, 0x31, 0x23, 0x13, 0x02]).is_none());
+        assert!(bcd_rtc(&[0x30, 0x31, 0x23, 0x13, 0x02, 0x09, 0x20, 0x00]).is_none());
+        assert!(bcd_rtc(&[0x3a, 0x31, 0x23, 0x13, 0x02, 0x09]).is_none());
+        assert!(bcd_rtc(&[0x30, 0x31, 0x24, 0x13, 0x02, 0x09]).is_none());
+        assert!(bcd_rtc(&[0x30, 0x31, 0x23, 0x30, 0x02, 0x09]).is_none());
+    }
+    #[test]
+    fn round_trip() {
+        for hour in 0..24 {
+            let ndt = NaiveDate::from_ymd_opt(2038, 1, 19)
+                .unwrap()
+                .and_hms_opt(hour, 14, 7)
+                .unwrap();
+            for twelve_hour in [false, true] {
+                let bytes = to_bcd_rtc(ndt, twelve_hour).unwrap();
+                assert_eq!(bcd_rtc(&bytes), Some(ndt), "{hour} {twelve_hour}");
+            }
+        }
+    }
+    #[test]
+    fn with_offset_round_trip() {
+        let offset = FixedOffset::west_opt(3 * 60 * 60 + 30 * 60).unwrap();
+        let dt = bcd_rtc_with_offset(&[0x30, 0x31, 0x23, 0x13, 0x02, 0x09], offset).unwrap();
+        assert_eq!(dt.naive_utc().to_string(), "2009-02-14 03:01:30");
+        let bytes = to_bcd_rtc_with_offset(dt, true).unwrap();
+        assert_eq!(bcd_rtc_with_offset(&bytes, offset), Some(dt));
+    }
+}
@@ -0,0 +1,102 @@
+//! Reading and writing epoch newtypes with
+//! [rusqlite](https://github.com/rusqlite/rusqlite), available with the
+//! `rusqlite` feature.
+//!
+//! Browser histories are SQLite databases full of raw epoch values,
+//! like `moz_places.last_visit_date` (Mozilla time) or Chrome's
+//! `visits.visit_time` (Chrome time). With this feature, they can be
+//! read straight into the matching newtype.
+//!
+//! ```
+//! use epochs::MozillaMicros;
+//! use rusqlite::Connection;
+//!
+//! let conn = Connection::open_in_memory().unwrap();
+//! conn.execute_batch(
+//!     "CREATE TABLE moz_places (url TEXT, last_visit_date INTEGER);
+//!      INSERT INTO moz_places VALUES ('https://example.com/', 1234567890654321);",
+//! )
+//! .unwrap();
+//! let visited: MozillaMicros = conn
+//!     .query_row("SELECT last_visit_date FROM moz_places", [], |row| row.get(0))
+//!     .unwrap();
+//! assert_eq!(
+//!     visited.to_datetime().unwrap().to_string(),
+//!     "2009-02-13 23:31:30.654321"
+//! );
+//! ```
+
+use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+/// Store each of the given newtypes as its raw value.
+macro_rules! integer {
+    ($($newtype:ident),*) => {
+        $(
+            impl FromSql for crate::$newtype {
+                fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                    i64::column_result(value).map(Self)
+                }
+            }
+
+            impl ToSql for crate::$newtype {
+                fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                    Ok(ToSqlOutput::from(self.0))
+                }
+            }
+        )*
+    };
+}
+
+for_each_newtype!(integer);
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{ChromeMicros, JavaMillis, UnixSeconds, WindowsFileTime};
+    use rusqlite::{Connection, Error};
+
+    #[test]
+    fn round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        let t = WindowsFileTime(128_790_414_900_000_000);
+        let back: WindowsFileTime = conn.query_row("SELECT ?1", [t], |row| row.get(0)).unwrap();
+        assert_eq!(back, t);
+    }
+    #[test]
+    fn chrome_visits() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE visits (id INTEGER PRIMARY KEY, visit_time INTEGER NOT NULL);
+             INSERT INTO visits (visit_time) VALUES (12879041490000000), (12879041491000000);",
+        )
+        .unwrap();
+        let mut stmt = conn
+            .prepare("SELECT visit_time FROM visits ORDER BY id")
+            .unwrap();
+        let times: Vec<ChromeMicros> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            times,
+            [
+                ChromeMicros(12_879_041_490_000_000),
+                ChromeMicros(12_879_041_491_000_000)
+            ]
+        );
+    }
+    #[test]
+    fn nullable() {
+        let conn = Connection::open_in_memory().unwrap();
+        let t: Option<JavaMillis> = conn.query_row("SELECT NULL", [], |row| row.get(0)).unwrap();
+        assert_eq!(t, None);
+    }
+    #[test]
+    fn not_an_integer() {
+        let conn = Connection::open_in_memory().unwrap();
+        let result: Result<UnixSeconds, _> =
+            conn.query_row("SELECT 'yesterday'", [], |row| row.get(0));
+        assert!(matches!(result, Err(Error::InvalidColumnType(..))));
+    }
+}
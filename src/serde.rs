@@ -0,0 +1,191 @@
+//! Serializing and deserializing epoch newtypes with
+//! [Serde](https://serde.rs), available with the `serde` feature.
+//!
+//! The newtypes serialize as their raw values. They deserialize from
+//! whatever an API happens to send: an integer (or a float with no
+//! fractional part), a string holding a number in any form
+//! [parse_value](../fn.parse_value.html) understands, or a string
+//! holding an ISO 8601 datetime in any form
+//! [parse_datetime](../fn.parse_datetime.html) understands. A datetime
+//! with an offset is converted to UTC; one without is taken to be UTC
+//! already. A datetime the newtype can't hold is an error rather than
+//! being clamped to the limits of an i64. Binary formats, which are not
+//! human readable, just use the raw value.
+//!
+//! ```
+//! use epochs::JavaMillis;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Event {
+//!     created: JavaMillis,
+//! }
+//!
+//! for json in [
+//!     r#"{"created": 1234567890000}"#,
+//!     r#"{"created": "1234567890000"}"#,
+//!     r#"{"created": "2009-02-13T23:31:30Z"}"#,
+//!     r#"{"created": "2009-02-14T00:31:30+01:00"}"#,
+//! ] {
+//!     let event: Event = serde_json::from_str(json).unwrap();
+//!     assert_eq!(event.created, JavaMillis(1_234_567_890_000));
+//! }
+//! ```
+
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{parse_datetime, parse_value, ConversionError, Value};
+
+/// A visitor for a newtype, given how to make one from a raw value or
+/// from a datetime (which fails if the datetime is out of range).
+struct EpochVisitor<T> {
+    name: &'static str,
+    from_raw: fn(i64) -> T,
+    from_datetime: fn(NaiveDateTime) -> Result<T, ConversionError>,
+}
+
+impl<'de, T> Visitor<'de> for EpochVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a {} as an integer, a string of digits, or an ISO 8601 datetime",
+            self.name
+        )
+    }
+
+    fn visit_i64<E: de::Error>(self, num: i64) -> Result<T, E> {
+        Ok((self.from_raw)(num))
+    }
+
+    fn visit_u64<E: de::Error>(self, num: u64) -> Result<T, E> {
+        i64::try_from(num)
+            .map(self.from_raw)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(num), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, x: f64) -> Result<T, E> {
+        Value::Float(x)
+            .as_i64()
+            .map(self.from_raw)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Float(x), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<T, E> {
+        let s = s.trim();
+        if let Some(num) = parse_value(s).ok().and_then(Value::as_i64) {
+            return Ok((self.from_raw)(num));
+        }
+        parse_datetime(s)
+            .and_then(|ndt| (self.from_datetime)(ndt).ok())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(s), &self))
+    }
+}
+
+/// Serialize each of the given newtypes as its raw value, and
+/// deserialize it from anything that looks like one.
+macro_rules! serde_impls {
+    ($($newtype:ident),*) => {
+        $(
+            impl Serialize for crate::$newtype {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_i64(self.0)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for crate::$newtype {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let visitor = EpochVisitor {
+                        name: stringify!($newtype),
+                        from_raw: Self,
+                        from_datetime: |ndt| {
+                            Self::EPOCH.try_from_datetime(ndt).map(Self)
+                        },
+                    };
+                    // Binary formats cannot say what they hold, but then
+                    // they only ever hold what was serialized.
+                    if deserializer.is_human_readable() {
+                        deserializer.deserialize_any(visitor)
+                    } else {
+                        deserializer.deserialize_i64(visitor)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+for_each_newtype!(serde_impls);
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{ApfsNanos, ChromeMicros, JavaMillis, UnixSeconds, WindowsFileTime};
+
+    fn unix(json: &str) -> Result<UnixSeconds, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(unix("1234567890").unwrap(), UnixSeconds(1234567890));
+        assert_eq!(unix("-1").unwrap(), UnixSeconds(-1));
+        assert_eq!(unix("1.23456789e9").unwrap(), UnixSeconds(1234567890));
+        assert!(unix("1234567890.5").is_err());
+        assert!(unix("9223372036854775808").is_err());
+    }
+    #[test]
+    fn strings() {
+        assert_eq!(unix("\"1234567890\"").unwrap(), UnixSeconds(1234567890));
+        assert_eq!(
+            unix("\" 1,234,567,890 \"").unwrap(),
+            UnixSeconds(1234567890)
+        );
+        assert_eq!(unix("\"0x499602d2\"").unwrap(), UnixSeconds(1234567890));
+    }
+    #[test]
+    fn datetimes() {
+        for json in [
+            "\"2009-02-13T23:31:30Z\"",
+            "\"2009-02-13T18:31:30-05:00\"",
+            "\"2009-02-13T23:31:30\"",
+            "\"2009-02-13 23:31:30\"",
+        ] {
+            assert_eq!(unix(json).unwrap(), UnixSeconds(1234567890), "{}", json);
+        }
+        let t: ChromeMicros = serde_json::from_str("\"2009-02-13T23:31:30.654321Z\"").unwrap();
+        assert_eq!(t, ChromeMicros(12_879_041_490_654_321));
+    }
+    #[test]
+    fn invalid() {
+        let err = unix("\"yesterday\"").unwrap_err().to_string();
+        assert!(err.contains("UnixSeconds"), "{}", err);
+        assert!(unix("true").is_err());
+        assert!(unix("null").is_err());
+    }
+    #[test]
+    fn out_of_range() {
+        let t: Result<ApfsNanos, _> = serde_json::from_str("\"2300-01-01T00:00:00Z\"");
+        let err = t.unwrap_err().to_string();
+        assert!(err.contains("2300-01-01T00:00:00Z"), "{}", err);
+        let t: ApfsNanos = serde_json::from_str("\"2262-04-11T23:47:16.854775807Z\"").unwrap();
+        assert_eq!(t, ApfsNanos(i64::MAX));
+    }
+    #[test]
+    fn serialize() {
+        let json = serde_json::to_string(&WindowsFileTime(128_790_414_900_000_000)).unwrap();
+        assert_eq!(json, "128790414900000000");
+        let t: WindowsFileTime = serde_json::from_str(&json).unwrap();
+        assert_eq!(t, WindowsFileTime(128_790_414_900_000_000));
+    }
+    #[test]
+    fn optional() {
+        let t: Option<JavaMillis> = serde_json::from_str("null").unwrap();
+        assert_eq!(t, None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Storing epoch newtypes in [Diesel](https://diesel.rs) BigInt
+//! columns, available with the `diesel` feature.
+//!
+//! Many schemas keep times as raw epoch values. With this feature, a
+//! model can use the newtype for such a column, so that the value is
+//! stored as it is but is a `to_datetime` away from a NaiveDateTime.
+//!
+//! ```
+//! use diesel::prelude::*;
+//! use epochs::UnixSeconds;
+//!
+//! diesel::table! {
+//!     events (id) {
+//!         id -> BigInt,
+//!         created -> BigInt,
+//!     }
+//! }
+//!
+//! #[derive(Queryable, Insertable)]
+//! #[diesel(table_name = events)]
+//! struct Event {
+//!     id: i64,
+//!     created: UnixSeconds,
+//! }
+//!
+//! let mut conn = SqliteConnection::establish(":memory:").unwrap();
+//! diesel::sql_query("CREATE TABLE events (id BIGINT PRIMARY KEY, created BIGINT NOT NULL)")
+//!     .execute(&mut conn)
+//!     .unwrap();
+//! diesel::insert_into(events::table)
+//!     .values(Event { id: 1, created: UnixSeconds(1234567890) })
+//!     .execute(&mut conn)
+//!     .unwrap();
+//! let event: Event = events::table.first(&mut conn).unwrap();
+//! assert_eq!(event.created.to_datetime().unwrap().to_string(), "2009-02-13 23:31:30");
+//! ```
+//!
+//! To work with NaiveDateTimes in the model instead, name the newtype
+//! with `serialize_as` and `deserialize_as`. Reading a value that has
+//! no NaiveDateTime is then an error.
+//!
+//! ```
+//! use chrono::NaiveDateTime;
+//! use diesel::prelude::*;
+//! use epochs::UnixSeconds;
+//!
+//! diesel::table! {
+//!     events (id) {
+//!         id -> BigInt,
+//!         created -> BigInt,
+//!     }
+//! }
+//!
+//! #[derive(Queryable, Insertable)]
+//! #[diesel(table_name = events)]
+//! struct Event {
+//!     id: i64,
+//!     #[diesel(serialize_as = UnixSeconds, deserialize_as = UnixSeconds)]
+//!     created: NaiveDateTime,
+//! }
+//!
+//! let mut conn = SqliteConnection::establish(":memory:").unwrap();
+//! diesel::sql_query("CREATE TABLE events (id BIGINT PRIMARY KEY, created BIGINT NOT NULL)")
+//!     .execute(&mut conn)
+//!     .unwrap();
+//! let created = epochs::unix(1234567890).unwrap();
+//! diesel::insert_into(events::table)
+//!     .values(Event { id: 1, created })
+//!     .execute(&mut conn)
+//!     .unwrap();
+//! let event: Event = events::table.first(&mut conn).unwrap();
+//! assert_eq!(event.created, created);
+//! let raw: i64 = events::table.select(events::created).first(&mut conn).unwrap();
+//! assert_eq!(raw, 1234567890);
+//! ```
+
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::BigInt;
+
+/// Store each of the given newtypes as its raw value.
+macro_rules! big_int {
+    ($($newtype:ident),*) => {
+        $(
+            impl<DB: Backend> FromSql<BigInt, DB> for crate::$newtype
+            where
+                i64: FromSql<BigInt, DB>,
+            {
+                fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+                    i64::from_sql(bytes).map(Self)
+                }
+            }
+
+            impl<DB: Backend> ToSql<BigInt, DB> for crate::$newtype
+            where
+                i64: ToSql<BigInt, DB>,
+            {
+                fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+                    self.0.to_sql(out)
+                }
+            }
+        )*
+    };
+}
+
+for_each_newtype!(big_int);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{ChromeMicros, JavaMillis, WindowsFileTime};
+    use chrono::NaiveDateTime;
+    use diesel::dsl::sql;
+    use diesel::prelude::*;
+    use diesel::sql_types::Nullable;
+
+    fn connection() -> SqliteConnection {
+        SqliteConnection::establish(":memory:").unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut conn = connection();
+        let t = WindowsFileTime(128_790_414_900_000_000);
+        let back: WindowsFileTime = diesel::select(t.into_sql::<BigInt>())
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(back, t);
+    }
+    #[test]
+    fn raw_values() {
+        let mut conn = connection();
+        let t: ChromeMicros = diesel::select(sql::<BigInt>("12879041490000000"))
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(t.to_datetime().unwrap().to_string(), "2009-02-13 23:31:30");
+    }
+    #[test]
+    fn try_into_datetime() {
+        let mut conn = connection();
+        let ndt: NaiveDateTime = diesel::select(sql::<BigInt>("12879041490000000"))
+            .get_result::<ChromeMicros>(&mut conn)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+        let t: ChromeMicros = diesel::select(sql::<BigInt>("9223372036854775807"))
+            .get_result(&mut conn)
+            .unwrap();
+        assert!(NaiveDateTime::try_from(t).is_err());
+    }
+    #[test]
+    fn nullable() {
+        let mut conn = connection();
+        let t: Option<JavaMillis> = diesel::select(sql::<Nullable<BigInt>>("NULL"))
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(t, None);
+        let t: Option<JavaMillis> =
+            diesel::select(Some(JavaMillis(1_234_567_890_000)).into_sql::<Nullable<BigInt>>())
+                .get_result(&mut conn)
+                .unwrap();
+        assert_eq!(t, Some(JavaMillis(1_234_567_890_000)));
+    }
+}
@@ -0,0 +1,114 @@
+//! Storing epoch newtypes in integer columns with
+//! [SQLx](https://github.com/launchbadge/sqlx), available with the
+//! `sqlx` feature.
+//!
+//! The newtypes have the same type, encoding, and decoding as i64 on
+//! any database that has them, including Postgres (BIGINT), MySQL
+//! (BIGINT), and SQLite (INTEGER).
+//!
+//! ```
+//! use epochs::UnixSeconds;
+//! use sqlx::{Connection, SqliteConnection};
+//!
+//! # futures_executor::block_on(async {
+//! let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+//! let (created,): (UnixSeconds,) = sqlx::query_as("SELECT ?")
+//!     .bind(UnixSeconds(1234567890))
+//!     .fetch_one(&mut conn)
+//!     .await
+//!     .unwrap();
+//! assert_eq!(created.to_datetime().unwrap().to_string(), "2009-02-13 23:31:30");
+//! # });
+//! ```
+
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type};
+
+/// Treat each of the given newtypes as its raw value.
+macro_rules! as_i64 {
+    ($($newtype:ident),*) => {
+        $(
+            impl<DB: Database> Type<DB> for crate::$newtype
+            where
+                i64: Type<DB>,
+            {
+                fn type_info() -> DB::TypeInfo {
+                    <i64 as Type<DB>>::type_info()
+                }
+
+                fn compatible(ty: &DB::TypeInfo) -> bool {
+                    <i64 as Type<DB>>::compatible(ty)
+                }
+            }
+
+            impl<'q, DB: Database> Encode<'q, DB> for crate::$newtype
+            where
+                i64: Encode<'q, DB>,
+            {
+                fn encode_by_ref(
+                    &self,
+                    buf: &mut <DB as Database>::ArgumentBuffer,
+                ) -> Result<IsNull, BoxDynError> {
+                    <i64 as Encode<'q, DB>>::encode_by_ref(&self.0, buf)
+                }
+            }
+
+            impl<'r, DB: Database> Decode<'r, DB> for crate::$newtype
+            where
+                i64: Decode<'r, DB>,
+            {
+                fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+                    <i64 as Decode<'r, DB>>::decode(value).map(Self)
+                }
+            }
+        )*
+    };
+}
+
+for_each_newtype!(as_i64);
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{ChromeMicros, JavaMillis, WindowsFileTime};
+    use futures_executor::block_on;
+    use sqlx::{Connection, SqliteConnection};
+
+    #[test]
+    fn round_trip() {
+        block_on(async {
+            let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+            let t = WindowsFileTime(128_790_414_900_000_000);
+            let (back,): (WindowsFileTime,) = sqlx::query_as("SELECT ?")
+                .bind(t)
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(back, t);
+        });
+    }
+    #[test]
+    fn raw_values() {
+        block_on(async {
+            let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+            let (t,): (ChromeMicros,) = sqlx::query_as("SELECT 12879041490000000")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(t.to_datetime().unwrap().to_string(), "2009-02-13 23:31:30");
+        });
+    }
+    #[test]
+    fn nullable() {
+        block_on(async {
+            let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+            let (t,): (Option<JavaMillis>,) = sqlx::query_as("SELECT ?")
+                .bind(None::<JavaMillis>)
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(t, None);
+        });
+    }
+}
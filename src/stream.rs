@@ -0,0 +1,495 @@
+//! Convert epoch columns of CSV data as it streams past, available
+//! with the `csv` feature.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+
+use crate::{
+    parse_hex, parse_value, rescale, ConversionError, Epoch, ParseEpochError, ParseValueError,
+};
+
+/// What to convert a column to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// An ISO 8601 date and time, like `2009-02-13T23:31:30.654321`.
+    Iso8601,
+    /// A number in another epoch, with any remainder smaller than its
+    /// unit rounded down.
+    Epoch(Epoch),
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Iso8601 => f.write_str("iso8601"),
+            Target::Epoch(epoch) => epoch.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = ParseEpochError;
+
+    /// Parse "iso8601" (or "iso") or the name of an epoch.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.eq_ignore_ascii_case("iso8601") || name.eq_ignore_ascii_case("iso") {
+            Ok(Target::Iso8601)
+        } else {
+            name.parse().map(Target::Epoch)
+        }
+    }
+}
+
+/// Which column a rule applies to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+/// Why a cell could not be converted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CellErrorKind {
+    /// The cell is not an integer.
+    Parse(ParseValueError),
+    /// The number is out of range for the epoch (or the target).
+    Conversion(ConversionError),
+}
+
+/// A cell that could not be converted, and so was copied through as
+/// it was.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellError {
+    /// The line the record starts on, counting from 1.
+    pub line: u64,
+    /// The column, counting from 0.
+    pub column: usize,
+    /// The contents of the cell.
+    pub input: String,
+    /// What went wrong.
+    pub kind: CellErrorKind,
+}
+
+impl fmt::Display for CellError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column + 1)?;
+        match &self.kind {
+            CellErrorKind::Parse(e) => e.fmt(f),
+            CellErrorKind::Conversion(e) => write!(f, "{:?}: {}", self.input.trim(), e),
+        }
+    }
+}
+
+impl Error for CellError {}
+
+/// The error returned when the data cannot be rewritten at all.
+#[derive(Debug)]
+pub enum RewriteError {
+    /// Reading or writing the CSV failed.
+    Csv(csv::Error),
+    /// A rule names a column the header does not have (or there is no
+    /// header).
+    UnknownColumn(String),
+}
+
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RewriteError::Csv(_) => f.write_str("could not read or write the CSV"),
+            RewriteError::UnknownColumn(name) => write!(f, "no column named {:?}", name),
+        }
+    }
+}
+
+impl Error for RewriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RewriteError::Csv(e) => Some(e),
+            RewriteError::UnknownColumn(_) => None,
+        }
+    }
+}
+
+impl From<csv::Error> for RewriteError {
+    fn from(e: csv::Error) -> Self {
+        RewriteError::Csv(e)
+    }
+}
+
+/// What happened in a [rewrite](struct.CsvEpochRewriter.html#method.run).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// The number of records, not counting the header.
+    pub records: u64,
+    /// The number of cells converted.
+    pub converted: u64,
+    /// The number of cells that could not be converted.
+    pub failed: u64,
+}
+
+/// Rewrites the epoch columns of CSV data read from `R` to `W`, one
+/// record at a time, so memory use does not grow with the input. The
+/// other columns are copied through, with quoting added wherever it is
+/// needed. Empty cells stay empty, and cells that cannot be converted
+/// are copied through as they are.
+///
+/// ```
+/// use epochs::stream::{CsvEpochRewriter, Target};
+/// use epochs::Epoch;
+///
+/// let input = "id,visited\n1,12879041490654321\n2,\n";
+/// let mut output = Vec::new();
+/// let summary = CsvEpochRewriter::new(input.as_bytes(), &mut output)
+///     .has_headers(true)
+///     .named_column("visited", Epoch::Chrome, Target::Iso8601)
+///     .run()
+///     .unwrap();
+/// assert_eq!(summary.converted, 1);
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "id,visited\n1,2009-02-13T23:31:30.654321\n2,\n"
+/// );
+/// ```
+pub struct CsvEpochRewriter<R, W> {
+    reader: R,
+    writer: W,
+    rules: Vec<(Column, Epoch, Target)>,
+    delimiter: u8,
+    has_headers: bool,
+    hex: bool,
+}
+
+impl<R: Read, W: Write> CsvEpochRewriter<R, W> {
+    /// A rewriter for comma-separated data with no header and, until
+    /// columns are added, nothing to convert.
+    pub fn new(reader: R, writer: W) -> Self {
+        CsvEpochRewriter {
+            reader,
+            writer,
+            rules: Vec::new(),
+            delimiter: b',',
+            has_headers: false,
+            hex: false,
+        }
+    }
+
+    /// Convert the column at the given index (counting from 0) from
+    /// one epoch to the target.
+    pub fn column(mut self, index: usize, from: Epoch, to: Target) -> Self {
+        self.rules.push((Column::Index(index), from, to));
+        self
+    }
+
+    /// Convert the column with the given header from one epoch to the
+    /// target. This needs [has_headers](#method.has_headers).
+    pub fn named_column(mut self, name: &str, from: Epoch, to: Target) -> Self {
+        self.rules.push((Column::Name(name.to_string()), from, to));
+        self
+    }
+
+    /// Use the given field delimiter (*e.g.*, `b'\t'`) instead of a
+    /// comma.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether the first record is a header, to be copied through
+    /// unchanged.
+    pub fn has_headers(mut self, yes: bool) -> Self {
+        self.has_headers = yes;
+        self
+    }
+
+    /// Read the cells as hexadecimal, as [parse_hex](../fn.parse_hex.html)
+    /// does, rather than as [parse_value](../fn.parse_value.html) does.
+    pub fn hex(mut self, yes: bool) -> Self {
+        self.hex = yes;
+        self
+    }
+
+    /// Rewrite all of the data, ignoring the cells that cannot be
+    /// converted.
+    pub fn run(self) -> Result<Summary, RewriteError> {
+        self.run_with(|_| {})
+    }
+
+    /// Rewrite all of the data, passing each cell that cannot be
+    /// converted to `on_error`.
+    ///
+    /// ```
+    /// use epochs::stream::{CsvEpochRewriter, Target};
+    /// use epochs::Epoch;
+    ///
+    /// let mut errors = Vec::new();
+    /// let summary = CsvEpochRewriter::new("1234567890\nsoon\n".as_bytes(), std::io::sink())
+    ///     .column(0, Epoch::Unix, Target::Epoch(Epoch::Java))
+    ///     .run_with(|e| errors.push(e.to_string()))
+    ///     .unwrap();
+    /// assert_eq!(summary.failed, 1);
+    /// assert_eq!(errors, ["line 2, column 1: invalid number: \"soon\": expected a digit at offset 0"]);
+    /// ```
+    pub fn run_with<F>(self, mut on_error: F) -> Result<Summary, RewriteError>
+    where
+        F: FnMut(&CellError),
+    {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .from_reader(self.reader);
+        let mut writer = WriterBuilder::new()
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .from_writer(self.writer);
+
+        let mut record = StringRecord::new();
+        let header = if self.has_headers && reader.read_record(&mut record)? {
+            writer.write_record(&record)?;
+            Some(record.clone())
+        } else {
+            None
+        };
+        let rules = self
+            .rules
+            .iter()
+            .map(|(column, from, to)| {
+                let index = match column {
+                    Column::Index(index) => *index,
+                    Column::Name(name) => header
+                        .as_ref()
+                        .and_then(|header| header.iter().position(|h| h == name))
+                        .ok_or_else(|| RewriteError::UnknownColumn(name.clone()))?,
+                };
+                Ok((index, *from, *to))
+            })
+            .collect::<Result<Vec<_>, RewriteError>>()?;
+
+        let mut summary = Summary::default();
+        let mut row: Vec<String> = Vec::new();
+        while reader.read_record(&mut record)? {
+            summary.records += 1;
+            row.clear();
+            row.extend(record.iter().map(str::to_string));
+            for &(index, from, to) in &rules {
+                let Some(cell) = row.get_mut(index) else {
+                    continue;
+                };
+                if cell.trim().is_empty() {
+                    continue;
+                }
+                match convert_cell(cell, from, to, self.hex) {
+                    Ok(converted) => {
+                        *cell = converted;
+                        summary.converted += 1;
+                    }
+                    Err(kind) => {
+                        summary.failed += 1;
+                        on_error(&CellError {
+                            line: record.position().map_or(0, |p| p.line()),
+                            column: index,
+                            input: cell.clone(),
+                            kind,
+                        });
+                    }
+                }
+            }
+            writer.write_record(&row)?;
+        }
+        writer.flush().map_err(csv::Error::from)?;
+        Ok(summary)
+    }
+}
+
+/// Convert the contents of one cell.
+fn convert_cell(cell: &str, from: Epoch, to: Target, hex: bool) -> Result<String, CellErrorKind> {
+    let num = if hex {
+        parse_hex(cell)
+    } else {
+        parse_value(cell).and_then(|value| {
+            value
+                .as_i64()
+                .ok_or_else(|| ParseValueError::new(cell, 0, "an integer"))
+        })
+    }
+    .map_err(CellErrorKind::Parse)?;
+
+    match to {
+        Target::Iso8601 => from
+            .try_to_datetime(num)
+            .map(|ndt| ndt.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+            .map_err(CellErrorKind::Conversion),
+        Target::Epoch(to) => match rescale(num, from, to) {
+            Some(n) => Ok(n.to_string()),
+            None => from
+                .try_to_datetime(num)
+                .and_then(|ndt| to.try_from_datetime(ndt))
+                .map(|n| n.to_string())
+                .map_err(CellErrorKind::Conversion),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn rewrite(
+        rewriter: impl FnOnce(&[u8], &mut Vec<u8>) -> Result<Summary, RewriteError>,
+        input: &str,
+    ) -> (Summary, String) {
+        let mut output = Vec::new();
+        let summary = rewriter(input.as_bytes(), &mut output).unwrap();
+        (summary, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn quoting_preserved() {
+        let input =
+            "1,\"Hello, world\",12879041490654321\n2,\"say \"\"hi\"\"\",12879041490000000\n";
+        let (summary, output) = rewrite(
+            |r, w| {
+                CsvEpochRewriter::new(r, w)
+                    .column(2, Epoch::Chrome, Target::Iso8601)
+                    .run()
+            },
+            input,
+        );
+        assert_eq!(summary.records, 2);
+        assert_eq!(summary.converted, 2);
+        assert_eq!(
+            output,
+            "1,\"Hello, world\",2009-02-13T23:31:30.654321\n2,\"say \"\"hi\"\"\",2009-02-13T23:31:30\n"
+        );
+    }
+    #[test]
+    fn several_columns() {
+        let input = "a\tb\tc\n1234567890\tx\t1234567891000\n";
+        let (summary, output) = rewrite(
+            |r, w| {
+                CsvEpochRewriter::new(r, w)
+                    .delimiter(b'\t')
+                    .has_headers(true)
+                    .named_column("a", Epoch::Unix, Target::Epoch(Epoch::Java))
+                    .named_column("c", Epoch::Java, Target::Epoch(Epoch::Unix))
+                    .run()
+            },
+            input,
+        );
+        assert_eq!(summary.converted, 2);
+        assert_eq!(output, "a\tb\tc\n1234567890000\tx\t1234567891\n");
+    }
+    #[test]
+    fn ragged_rows() {
+        let input = "1234567890\n\n1,1234567890\n";
+        let (summary, output) = rewrite(
+            |r, w| {
+                CsvEpochRewriter::new(r, w)
+                    .column(1, Epoch::Unix, Target::Iso8601)
+                    .run()
+            },
+            input,
+        );
+        assert_eq!(summary.records, 2);
+        assert_eq!(output, "1234567890\n1,2009-02-13T23:31:30\n");
+    }
+    #[test]
+    fn bad_cells() {
+        let mut errors = Vec::new();
+        let input = "0x499602d2\n1.5\n9223372036854775807\n";
+        let summary = CsvEpochRewriter::new(input.as_bytes(), std::io::sink())
+            .column(0, Epoch::Java, Target::Iso8601)
+            .run_with(|e| errors.push(e.clone()))
+            .unwrap();
+        assert_eq!(summary.converted, 1);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(errors[0].line, 2);
+        assert!(matches!(errors[0].kind, CellErrorKind::Parse(_)));
+        assert_eq!(errors[1].line, 3);
+        assert!(matches!(errors[1].kind, CellErrorKind::Conversion(_)));
+    }
+    #[test]
+    fn out_of_range_target() {
+        let mut errors = Vec::new();
+        let input = "946684800\n0\n";
+        let summary = CsvEpochRewriter::new(input.as_bytes(), std::io::sink())
+            .column(0, Epoch::Unix, Target::Epoch(Epoch::Zigbee))
+            .run_with(|e| errors.push(e.clone()))
+            .unwrap();
+        assert_eq!(summary.converted, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(errors[0].line, 2);
+        assert_eq!(
+            errors[0].kind,
+            CellErrorKind::Conversion(ConversionError::Range)
+        );
+    }
+    #[test]
+    fn hex_cells() {
+        let (_, output) = rewrite(
+            |r, w| {
+                CsvEpochRewriter::new(r, w)
+                    .hex(true)
+                    .column(0, Epoch::WindowsFile, Target::Epoch(Epoch::Unix))
+                    .run()
+            },
+            "1c98e333296f500\n",
+        );
+        assert_eq!(output, "1234567890\n");
+    }
+    #[test]
+    fn google_calendar_target() {
+        let (_, output) = rewrite(
+            |r, w| {
+                CsvEpochRewriter::new(r, w)
+                    .column(0, Epoch::Unix, Target::Epoch(Epoch::GoogleCalendar))
+                    .run()
+            },
+            "1234567890\n",
+        );
+        assert_eq!(output, "1297899090\n");
+    }
+    #[test]
+    fn unknown_column() {
+        let result = CsvEpochRewriter::new("a\n1\n".as_bytes(), std::io::sink())
+            .named_column("b", Epoch::Unix, Target::Iso8601)
+            .has_headers(true)
+            .run();
+        assert!(matches!(result, Err(RewriteError::UnknownColumn(name)) if name == "b"));
+        let result = CsvEpochRewriter::new("a\n1\n".as_bytes(), std::io::sink())
+            .named_column("a", Epoch::Unix, Target::Iso8601)
+            .run();
+        assert!(matches!(result, Err(RewriteError::UnknownColumn(_))));
+    }
+    #[test]
+    fn csv_error_source() {
+        struct Broken;
+        impl Write for Broken {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let err = CsvEpochRewriter::new("1\n".as_bytes(), Broken)
+            .column(0, Epoch::Unix, Target::Iso8601)
+            .run()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "could not read or write the CSV");
+        assert!(err.source().unwrap().to_string().contains("disk full"));
+    }
+    #[test]
+    fn targets() {
+        assert_eq!("ISO8601".parse(), Ok(Target::Iso8601));
+        assert_eq!("filetime".parse(), Ok(Target::Epoch(Epoch::WindowsFile)));
+        assert!("bogus".parse::<Target>().is_err());
+        assert_eq!(Target::Epoch(Epoch::Unix).to_string(), "unix");
+    }
+}
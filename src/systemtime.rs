@@ -0,0 +1,279 @@
+//! Windows `SYSTEMTIME` structures.
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+#[cfg(feature = "clock")]
+use chrono::{Local, LocalResult};
+
+/// The years a `SYSTEMTIME` can hold (those a FILETIME can hold).
+const YEARS: std::ops::RangeInclusive<u16> = 1601..=30827;
+
+/// A Windows `SYSTEMTIME`: eight 16-bit fields in this order. The day
+/// of the week counts from Sunday as 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Systemtime {
+    pub year: u16,
+    pub month: u16,
+    pub day_of_week: u16,
+    pub day: u16,
+    pub hour: u16,
+    pub minute: u16,
+    pub second: u16,
+    pub milliseconds: u16,
+}
+
+impl Systemtime {
+    /// Read a `SYSTEMTIME` as it is laid out in memory (*e.g.*, in a
+    /// REG_BINARY registry value): eight little-endian 16-bit fields.
+    pub fn from_le_bytes(bytes: [u8; 16]) -> Systemtime {
+        let field = |i: usize| u16::from_le_bytes([bytes[2 * i], bytes[2 * i + 1]]);
+        Systemtime {
+            year: field(0),
+            month: field(1),
+            day_of_week: field(2),
+            day: field(3),
+            hour: field(4),
+            minute: field(5),
+            second: field(6),
+            milliseconds: field(7),
+        }
+    }
+
+    /// Lay out this `SYSTEMTIME` as eight little-endian 16-bit fields.
+    pub fn to_le_bytes(self) -> [u8; 16] {
+        let fields = [
+            self.year,
+            self.month,
+            self.day_of_week,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+            self.milliseconds,
+        ];
+        let mut bytes = [0; 16];
+        for (chunk, field) in bytes.chunks_exact_mut(2).zip(fields) {
+            chunk.copy_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// Convert the fields of a Windows `SYSTEMTIME` to a NaiveDateTime.
+/// As with `SystemTimeToFileTime`, the day of the week is not needed.
+/// Returns None if any field is out of range, including years outside
+/// 1601 to 30827.
+///
+/// ```
+/// use epochs::systemtime_to_datetime;
+/// let ndt = systemtime_to_datetime(2009, 2, 13, 23, 31, 30, 654).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30.654");
+/// ```
+pub fn systemtime_to_datetime(
+    year: u16,
+    month: u16,
+    day: u16,
+    hour: u16,
+    minute: u16,
+    second: u16,
+    millis: u16,
+) -> Option<NaiveDateTime> {
+    if !YEARS.contains(&year) {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year.into(), month.into(), day.into())?.and_hms_milli_opt(
+        hour.into(),
+        minute.into(),
+        second.into(),
+        // and_hms_milli_opt would take 1000 and up as a leap second.
+        (millis < 1000).then_some(millis)?.into(),
+    )
+}
+
+/// Convert the given NaiveDateTime to a [SYSTEMTIME](struct.Systemtime.html),
+/// including the day of the week. Anything finer than a millisecond is
+/// dropped. Returns None for years outside 1601 to 30827.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::datetime_to_systemtime;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let st = datetime_to_systemtime(ndt).unwrap();
+/// assert_eq!((st.year, st.month, st.day), (2009, 2, 13));
+/// assert_eq!(st.day_of_week, 5);
+/// ```
+pub fn datetime_to_systemtime(ndt: NaiveDateTime) -> Option<Systemtime> {
+    let year = u16::try_from(ndt.year()).ok()?;
+    if !YEARS.contains(&year) {
+        return None;
+    }
+    Some(Systemtime {
+        year,
+        month: ndt.month() as u16,
+        day_of_week: ndt.weekday().num_days_from_sunday() as u16,
+        day: ndt.day() as u16,
+        hour: ndt.hour() as u16,
+        minute: ndt.minute() as u16,
+        // A leap second is written as second 59.
+        second: ndt.second().min(59) as u16,
+        milliseconds: (ndt.nanosecond().min(999_999_999) / 1_000_000) as u16,
+    })
+}
+
+/// Convert a 16-byte little-endian `SYSTEMTIME` (as stored, *e.g.*, in
+/// registry values) to a NaiveDateTime. See
+/// [systemtime_to_datetime](fn.systemtime_to_datetime.html).
+///
+/// ```
+/// use epochs::systemtime_from_le_bytes;
+/// let bytes = [
+///     0xd9, 0x07, 0x02, 0x00, 0x05, 0x00, 0x0d, 0x00,
+///     0x17, 0x00, 0x1f, 0x00, 0x1e, 0x00, 0x00, 0x00,
+/// ];
+/// let ndt = systemtime_from_le_bytes(&bytes).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn systemtime_from_le_bytes(bytes: &[u8; 16]) -> Option<NaiveDateTime> {
+    let st = Systemtime::from_le_bytes(*bytes);
+    systemtime_to_datetime(
+        st.year,
+        st.month,
+        st.day,
+        st.hour,
+        st.minute,
+        st.second,
+        st.milliseconds,
+    )
+}
+
+/// Like [systemtime_from_le_bytes](fn.systemtime_from_le_bytes.html),
+/// but for a `SYSTEMTIME` known to be in local time at the given offset
+/// from UTC, as many registry values are, returning it with that
+/// offset.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::FixedOffset;
+/// use epochs::systemtime_from_le_bytes_with_offset;
+/// let bytes = [
+///     0xd9, 0x07, 0x02, 0x00, 0x05, 0x00, 0x0d, 0x00,
+///     0x17, 0x00, 0x1f, 0x00, 0x1e, 0x00, 0x00, 0x00,
+/// ];
+/// let offset = FixedOffset::west_opt(5 * 60 * 60).unwrap();
+/// let dt = systemtime_from_le_bytes_with_offset(&bytes, offset).unwrap();
+/// assert_eq!(dt.to_rfc3339(), "2009-02-13T23:31:30-05:00");
+/// ```
+pub fn systemtime_from_le_bytes_with_offset(
+    bytes: &[u8; 16],
+    offset: FixedOffset,
+) -> Option<DateTime<FixedOffset>> {
+    systemtime_from_le_bytes(bytes)?
+        .and_local_timezone(offset)
+        .single()
+}
+
+/// Like [systemtime_from_le_bytes](fn.systemtime_from_le_bytes.html),
+/// but for a `SYSTEMTIME` in the local time zone of this machine, which
+/// may be ambiguous or may never have happened (see
+/// [fat_local](fn.fat_local.html)). Returns None if any field is out of
+/// range.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::Local;
+/// use epochs::{systemtime_from_le_bytes, systemtime_from_le_bytes_local};
+/// let bytes = [
+///     0xd9, 0x07, 0x02, 0x00, 0x05, 0x00, 0x0d, 0x00,
+///     0x17, 0x00, 0x1f, 0x00, 0x1e, 0x00, 0x00, 0x00,
+/// ];
+/// let ndt = systemtime_from_le_bytes(&bytes).unwrap();
+/// assert_eq!(systemtime_from_le_bytes_local(&bytes), Some(ndt.and_local_timezone(Local)));
+/// ```
+#[cfg(feature = "clock")]
+pub fn systemtime_from_le_bytes_local(bytes: &[u8; 16]) -> Option<LocalResult<DateTime<Local>>> {
+    Some(systemtime_from_le_bytes(bytes)?.and_local_timezone(Local))
+}
+
+/// Convert the given DateTime to a [SYSTEMTIME](struct.Systemtime.html)
+/// in its own offset from UTC. See
+/// [datetime_to_systemtime](fn.datetime_to_systemtime.html).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::DateTime;
+/// use epochs::datetime_to_systemtime_with_offset;
+/// let dt = DateTime::parse_from_rfc3339("2009-02-14T04:31:30+05:00").unwrap();
+/// let st = datetime_to_systemtime_with_offset(dt).unwrap();
+/// assert_eq!((st.day, st.hour, st.minute), (14, 4, 31));
+/// ```
+pub fn datetime_to_systemtime_with_offset(dt: DateTime<FixedOffset>) -> Option<Systemtime> {
+    datetime_to_systemtime(dt.naive_local())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn systemtime_limits() {
+        let ndt = systemtime_to_datetime(1601, 1, 1, 0, 0, 0, 0).unwrap();
+        assert_eq!(ndt.to_string(), "1601-01-01 00:00:00");
+        let ndt = systemtime_to_datetime(30827, 12, 31, 23, 59, 59, 999).unwrap();
+        assert_eq!(ndt.to_string(), "+30827-12-31 23:59:59.999");
+        assert!(systemtime_to_datetime(1600, 12, 31, 23, 59, 59, 999).is_none());
+        assert!(systemtime_to_datetime(30828, 1, 1, 0, 0, 0, 0).is_none());
+    }
+    #[test]
+    fn systemtime_bad_fields() {
+        assert!(systemtime_to_datetime(2009, 13, 1, 0, 0, 0, 0).is_none());
+        assert!(systemtime_to_datetime(2009, 2, 29, 0, 0, 0, 0).is_none());
+        assert!(systemtime_to_datetime(2009, 2, 13, 24, 0, 0, 0).is_none());
+        assert!(systemtime_to_datetime(2009, 2, 13, 23, 60, 0, 0).is_none());
+        assert!(systemtime_to_datetime(2009, 2, 13, 23, 59, 60, 0).is_none());
+        assert!(systemtime_to_datetime(2009, 2, 13, 23, 59, 59, 1000).is_none());
+    }
+    #[test]
+    fn bytes_round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_milli_opt(12, 34, 56, 789)
+            .unwrap();
+        let st = datetime_to_systemtime(ndt).unwrap();
+        assert_eq!(st.day_of_week, 4);
+        let bytes = st.to_le_bytes();
+        assert_eq!(Systemtime::from_le_bytes(bytes), st);
+        assert_eq!(systemtime_from_le_bytes(&bytes), Some(ndt));
+    }
+    #[test]
+    fn to_systemtime_drops_micros() {
+        let ndt = NaiveDate::from_ymd_opt(1970, 1, 4)
+            .unwrap()
+            .and_hms_micro_opt(0, 0, 0, 1999)
+            .unwrap();
+        let st = datetime_to_systemtime(ndt).unwrap();
+        assert_eq!(st.day_of_week, 0);
+        assert_eq!(st.milliseconds, 1);
+    }
+    #[test]
+    fn to_systemtime_out_of_range() {
+        let ndt = NaiveDate::from_ymd_opt(1600, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert!(datetime_to_systemtime(ndt).is_none());
+    }
+    #[test]
+    fn with_offset_round_trip() {
+        let offset = FixedOffset::east_opt(5 * 60 * 60 + 45 * 60).unwrap();
+        let st = systemtime_to_datetime(2009, 2, 13, 23, 31, 30, 654).unwrap();
+        let dt = st.and_local_timezone(offset).unwrap();
+        let bytes = datetime_to_systemtime_with_offset(dt)
+            .unwrap()
+            .to_le_bytes();
+        assert_eq!(
+            systemtime_from_le_bytes_with_offset(&bytes, offset),
+            Some(dt)
+        );
+    }
+}
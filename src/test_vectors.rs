@@ -0,0 +1,172 @@
+//! Known-good conversions, for checking other implementations (or
+//! bindings to this one) against the same values this crate is tested
+//! with.
+//!
+//! ```
+//! use epochs::test_vectors::VECTORS;
+//! for v in VECTORS {
+//!     let ndt = v.epoch.to_datetime(v.value).unwrap();
+//!     assert_eq!(ndt.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(), v.iso);
+//! }
+//! ```
+
+use crate::Epoch;
+
+/// A number in an epoch and the time it stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TestVector {
+    /// The epoch the number is in.
+    pub epoch: Epoch,
+    /// The raw number.
+    pub value: i64,
+    /// The time in UTC, as ISO 8601 with a `Z` and no, 3, 6, or 9
+    /// fractional digits, whichever is the fewest that holds it
+    /// (*e.g.*, `2010-03-04T14:50:16.559001Z`).
+    pub iso: &'static str,
+}
+
+const fn v(epoch: Epoch, value: i64, iso: &'static str) -> TestVector {
+    TestVector { epoch, value, iso }
+}
+
+/// The vectors, grouped by epoch. Each converts exactly in both
+/// directions. Every epoch has the same instant, 2009-02-13 23:31:30
+/// (1234567890 in Unix time), or for those counting whole days, the
+/// midnight before it, plus some with fractional seconds or from
+/// before the epoch where it has them.
+pub const VECTORS: &[TestVector] = &[
+    v(
+        Epoch::Apfs,
+        1_234_567_890_000_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(Epoch::Apfs, -1, "1969-12-31T23:59:59.999999999Z"),
+    v(Epoch::Cdma, 918_603_105, "2009-02-13T23:31:30Z"),
+    v(Epoch::CdmaFrames, 734_882_484_000, "2009-02-13T23:31:30Z"),
+    v(
+        Epoch::CdmaFrames,
+        734_882_484_001,
+        "2009-02-13T23:31:30.001250Z",
+    ),
+    v(
+        Epoch::Chrome,
+        12_879_041_490_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(
+        Epoch::Chrome,
+        12_912_187_816_559_001,
+        "2010-03-04T14:50:16.559001Z",
+    ),
+    v(Epoch::Cics, 3_443_556_690_000, "2009-02-13T23:31:30Z"),
+    v(Epoch::Cics, 3_476_703_016_559, "2010-03-04T14:50:16.559Z"),
+    v(Epoch::Cocoa, 256_260_690, "2009-02-13T23:31:30Z"),
+    v(Epoch::Cocoa, -978_307_200, "1970-01-01T00:00:00Z"),
+    v(Epoch::Excel1900Date, 39_857, "2009-02-13T00:00:00Z"),
+    v(Epoch::Excel1900Date, 59, "1900-02-28T00:00:00Z"),
+    v(Epoch::Excel1900Date, 61, "1900-03-01T00:00:00Z"),
+    v(Epoch::Excel1904Date, 38_395, "2009-02-13T00:00:00Z"),
+    v(Epoch::Filemaker, 63_370_164_690, "2009-02-13T23:31:30Z"),
+    v(Epoch::Filemaker, 0, "0001-01-01T00:00:00Z"),
+    v(Epoch::GarminFit, 603_502_290, "2009-02-13T23:31:30Z"),
+    v(Epoch::GoogleCalendar, 1_297_899_090, "2009-02-13T23:31:30Z"),
+    v(Epoch::Java, 1_234_567_890_000, "2009-02-13T23:31:30Z"),
+    v(Epoch::Java, -1, "1969-12-31T23:59:59.999Z"),
+    v(Epoch::MjdDays, 54_875, "2009-02-13T00:00:00Z"),
+    v(Epoch::MjdDays, 0, "1858-11-17T00:00:00Z"),
+    v(
+        Epoch::Mozilla,
+        1_234_567_890_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(
+        Epoch::Mozilla,
+        1_234_567_890_654_321,
+        "2009-02-13T23:31:30.654321Z",
+    ),
+    v(Epoch::Palm, 3_317_412_690, "2009-02-13T23:31:30Z"),
+    v(Epoch::QuickTime, 3_317_412_690, "2009-02-13T23:31:30Z"),
+    v(Epoch::RataDie, 733_451, "2009-02-13T00:00:00Z"),
+    v(Epoch::RataDie, 1, "0001-01-01T00:00:00Z"),
+    v(Epoch::SasDate, 17_941, "2009-02-13T00:00:00Z"),
+    v(Epoch::SasDate, -1, "1959-12-31T00:00:00Z"),
+    v(Epoch::StataDate, 17_941, "2009-02-13T00:00:00Z"),
+    v(
+        Epoch::Symbian,
+        63_401_787_090_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(
+        Epoch::Tandem,
+        212_101_327_890_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(
+        Epoch::Tandem,
+        210_866_760_000_000_000,
+        "1970-01-01T00:00:00Z",
+    ),
+    v(Epoch::Tai, 1_234_567_924, "2009-02-13T23:31:30Z"),
+    v(Epoch::Tandem, 0, "-4713-11-24T12:00:00Z"),
+    v(Epoch::Unix, 1_234_567_890, "2009-02-13T23:31:30Z"),
+    v(Epoch::Unix, -1_234_567_890, "1930-11-18T00:28:30Z"),
+    v(Epoch::UnixRight, 1_234_567_914, "2009-02-13T23:31:30Z"),
+    v(
+        Epoch::UuidV1,
+        134_538_606_900_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(
+        Epoch::UuidV1,
+        0x1dc7711a73088f5,
+        "2007-10-10T09:17:41.739749300Z",
+    ),
+    v(
+        Epoch::UuidV1,
+        0x1ea4f7dca4892ce,
+        "2020-02-14T23:00:27.148155Z",
+    ),
+    v(
+        Epoch::WindowsDate,
+        633_701_646_900_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(
+        Epoch::WindowsDate,
+        634_496_538_123_456_789,
+        "2011-08-22T23:50:12.345678900Z",
+    ),
+    v(
+        Epoch::WindowsFile,
+        128_790_414_900_000_000,
+        "2009-02-13T23:31:30Z",
+    ),
+    v(
+        Epoch::WindowsFile,
+        0x1cabbaa00ca9000,
+        "2010-03-04T14:50:16.559001600Z",
+    ),
+    v(Epoch::Zigbee, 287_883_090, "2009-02-13T23:31:30Z"),
+];
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::parse_datetime;
+
+    #[test]
+    fn both_directions() {
+        for v in VECTORS {
+            let ndt = parse_datetime(v.iso).unwrap_or_else(|| panic!("{v:?}"));
+            assert_eq!(v.epoch.to_datetime(v.value), Some(ndt), "{v:?}");
+            assert_eq!(v.epoch.try_from_datetime(ndt), Ok(v.value), "{v:?}");
+        }
+    }
+    #[test]
+    fn every_epoch() {
+        for epoch in Epoch::ALL {
+            assert!(VECTORS.iter().any(|v| v.epoch == epoch), "{epoch}");
+        }
+    }
+}
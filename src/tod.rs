@@ -0,0 +1,207 @@
+//! IBM z/Architecture time-of-day clock values, and the IBM i
+//! (AS/400) standard time format built on them.
+
+use chrono::{DateTime, NaiveDateTime};
+
+/// The TOD clock starts at 1900-01-01, which is 2,208,988,800 seconds
+/// before the Unix epoch.
+const TOD_OFFSET: i64 = 2_208_988_800;
+
+/// The IBM i standard time format starts at 1928-08-23 12:03:06.314752,
+/// which is 903,873,786,314,752 microseconds after 1900-01-01, so that
+/// 2000-01-01 has the high bit set.
+const DTS_MICROS: i64 = 903_873_786_314_752;
+
+/// TOD clock time (*e.g.*, from STCK, or in SMF records) counts from
+/// 1900-01-01, with bit 51 (numbering from the most significant bit)
+/// worth one microsecond. The twelve bits below that are fractions of a
+/// microsecond, which are truncated to nanoseconds. The 64-bit clock
+/// rolls over in 2042; see
+/// [tod_clock_extended](fn.tod_clock_extended.html) for the format
+/// which does not.
+///
+/// ```
+/// use epochs::tod_clock;
+/// let ndt = tod_clock(0xc3be_5854_5788_0000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn tod_clock(num: u64) -> Option<NaiveDateTime> {
+    from_micros((num >> 12) as i64, (num & 0xfff) as u128, 12)
+}
+
+/// Convert the given NaiveDateTime to a [TOD clock](fn.tod_clock.html)
+/// time. Returns None for times before 1900 or after the clock rolls
+/// over in 2042.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_tod_clock;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_tod_clock(ndt), Some(0xc3be_5854_5788_0000));
+/// ```
+pub fn to_tod_clock(ndt: NaiveDateTime) -> Option<u64> {
+    let (micros, fraction) = to_micros(ndt, 12)?;
+    let high = u64::try_from(micros).ok()?.checked_mul(1 << 12)?;
+    Some(high | fraction as u64)
+}
+
+/// Extended TOD clock time (from STCKE) is 128 bits: an epoch index
+/// byte counting rollovers of the 64-bit clock, 104 bits of clock with
+/// bit 59 of the whole value worth one microsecond, and a 16-bit
+/// programmable field, which is ignored.
+///
+/// ```
+/// use epochs::tod_clock_extended;
+/// let ndt = tod_clock_extended(0x00c3_be58_5457_8800_0000_0000_0000_0000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn tod_clock_extended(num: u128) -> Option<NaiveDateTime> {
+    let micros = (num >> 68) as i64;
+    let fraction = (num >> 16) & ((1 << 52) - 1);
+    from_micros(micros, fraction, 52)
+}
+
+/// Convert the given NaiveDateTime to an [extended TOD
+/// clock](fn.tod_clock_extended.html) time, with a zero programmable
+/// field. Returns None for times before 1900.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_tod_clock_extended;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_tod_clock_extended(ndt), Some(0x00c3_be58_5457_8800_0000_0000_0000_0000));
+/// ```
+pub fn to_tod_clock_extended(ndt: NaiveDateTime) -> Option<u128> {
+    let (micros, fraction) = to_micros(ndt, 52)?;
+    let micros = u128::try_from(micros).ok()?;
+    if micros >= 1 << 60 {
+        return None;
+    }
+    Some(micros << 68 | fraction << 16)
+}
+
+/// IBM i (AS/400) standard time format (`*DTS`, as in journal entries
+/// and MI instructions) is laid out like the [TOD clock](fn.tod_clock.html),
+/// with bit 51 worth one microsecond, but counts from 1928-08-23
+/// 12:03:06.314752 so that 2000-01-01 is `0x8000_0000_0000_0000`.
+///
+/// ```
+/// use epochs::as400_dts;
+/// let ndt = as400_dts(0x905d_4015_0f88_0000).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn as400_dts(num: u64) -> Option<NaiveDateTime> {
+    let micros = ((num >> 12) as i64).checked_add(DTS_MICROS)?;
+    from_micros(micros, (num & 0xfff) as u128, 12)
+}
+
+/// Convert the given NaiveDateTime to an [IBM i standard
+/// time](fn.as400_dts.html). Returns None for times before 1928-08-23
+/// 12:03:06.314752 or after the format rolls over in 2071.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::to_as400_dts;
+/// let ndt = NaiveDateTime::parse_from_str("2009-02-13 23:31:30", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert_eq!(to_as400_dts(ndt), Some(0x905d_4015_0f88_0000));
+/// ```
+pub fn to_as400_dts(ndt: NaiveDateTime) -> Option<u64> {
+    let (micros, fraction) = to_micros(ndt, 12)?;
+    let micros = u64::try_from(micros - DTS_MICROS).ok()?;
+    let high = micros.checked_mul(1 << 12)?;
+    Some(high | fraction as u64)
+}
+
+/// Build a NaiveDateTime from the microseconds since 1900 and a binary
+/// fraction of a microsecond with the given number of bits.
+fn from_micros(micros: i64, fraction: u128, bits: u32) -> Option<NaiveDateTime> {
+    let seconds = micros.div_euclid(1_000_000) - TOD_OFFSET;
+    let nanos = micros.rem_euclid(1_000_000) as u32 * 1000 + ((fraction * 1000) >> bits) as u32;
+    DateTime::from_timestamp(seconds, nanos).map(|dt| dt.naive_utc())
+}
+
+/// Split the given NaiveDateTime into the microseconds since 1900 and
+/// a binary fraction of a microsecond with the given number of bits.
+/// Returns None for times before 1900.
+fn to_micros(ndt: NaiveDateTime, bits: u32) -> Option<(i64, u128)> {
+    let dt = ndt.and_utc();
+    let seconds = dt.timestamp().checked_add(TOD_OFFSET)?;
+    if seconds < 0 {
+        return None;
+    }
+    let nanos = dt.timestamp_subsec_nanos().min(999_999_999);
+    let micros = seconds.checked_mul(1_000_000)? + (nanos / 1000) as i64;
+    // Round up, as for the other binary fractions, so converting back
+    // gives the same nanoseconds.
+    let fraction = (((nanos % 1000) as u128) << bits).div_ceil(1000);
+    Some((micros, fraction.min((1 << bits) - 1)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn tod_clock_epoch() {
+        let ndt = tod_clock(0).unwrap();
+        assert_eq!(ndt.to_string(), "1900-01-01 00:00:00");
+    }
+    #[test]
+    fn tod_clock_max() {
+        let ndt = tod_clock(u64::MAX).unwrap();
+        assert_eq!(ndt.to_string(), "2042-09-17 23:53:47.370495999");
+    }
+    #[test]
+    fn to_tod_clock_out_of_range() {
+        let ndt = NaiveDate::from_ymd_opt(1899, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert_eq!(to_tod_clock(ndt), None);
+        let ndt = NaiveDate::from_ymd_opt(2042, 9, 18)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(to_tod_clock(ndt), None);
+        assert!(to_tod_clock_extended(ndt).is_some());
+    }
+    #[test]
+    fn tod_clock_extended_epoch_index() {
+        // Epoch index 1 starts where the 64-bit clock rolls over.
+        let ndt = tod_clock_extended(1 << 120).unwrap();
+        let rollover = tod_clock(u64::MAX).unwrap() + chrono::Duration::nanoseconds(1);
+        assert_eq!(ndt, rollover);
+    }
+    #[test]
+    fn as400_dts_range() {
+        let ndt = as400_dts(0).unwrap();
+        assert_eq!(ndt.to_string(), "1928-08-23 12:03:06.314752");
+        let ndt = as400_dts(1 << 63).unwrap();
+        assert_eq!(ndt.to_string(), "2000-01-01 00:00:00");
+        let ndt = as400_dts(u64::MAX).unwrap();
+        assert_eq!(ndt.to_string(), "2071-05-10 11:56:53.685247999");
+        // Fractions of a nanosecond are lost.
+        assert_eq!(to_as400_dts(ndt), Some(u64::MAX - 3));
+        assert_eq!(to_as400_dts(ndt + chrono::Duration::nanoseconds(1)), None);
+        let ndt = as400_dts(0).unwrap() - chrono::Duration::microseconds(1);
+        assert_eq!(to_as400_dts(ndt), None);
+    }
+    #[test]
+    fn round_trip() {
+        let ndt = NaiveDate::from_ymd_opt(2010, 3, 4)
+            .unwrap()
+            .and_hms_nano_opt(14, 50, 16, 559_001_999)
+            .unwrap();
+        assert_eq!(tod_clock(to_tod_clock(ndt).unwrap()), Some(ndt));
+        assert_eq!(as400_dts(to_as400_dts(ndt).unwrap()), Some(ndt));
+        assert_eq!(
+            tod_clock_extended(to_tod_clock_extended(ndt).unwrap()),
+            Some(ndt)
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Take apart [version 1 UUIDs](fn.uuid_v1.html) into all of their
+//! fields, not just the timestamp.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+use crate::{to_uuid_v1, uuid_v1};
+
+/// The fields of a version 1 UUID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Uuid1Fields {
+    /// When the UUID was made.
+    pub timestamp: NaiveDateTime,
+    /// The 14-bit clock sequence, which the generator changes when the
+    /// clock goes backwards or the node changes.
+    pub clock_seq: u16,
+    /// The node, usually the MAC address of the machine which made the
+    /// UUID.
+    pub node: [u8; 6],
+}
+
+impl Uuid1Fields {
+    /// Put the fields back together into a UUID, or None if the
+    /// timestamp is before 1582-10-15 or too late for 60 bits of
+    /// ticks, or the clock sequence is too big for 14 bits.
+    ///
+    /// ```
+    /// use epochs::uuid_v1_fields;
+    /// let uuid = 0xca4892ce_4f7d_11ea_b77f_2e728ce88125;
+    /// assert_eq!(uuid_v1_fields(uuid).unwrap().to_u128(), Some(uuid));
+    /// ```
+    pub fn to_u128(&self) -> Option<u128> {
+        let ticks = u64::try_from(to_uuid_v1(self.timestamp)).ok()?;
+        if ticks >> 60 != 0 || self.clock_seq >> 14 != 0 {
+            return None;
+        }
+        let ticks = u128::from(ticks);
+        let time_low = ticks & 0xffff_ffff;
+        let time_mid = (ticks >> 32) & 0xffff;
+        let time_hi = ticks >> 48;
+        let mut node = [0; 16];
+        node[10..].copy_from_slice(&self.node);
+        Some(
+            time_low << 96
+                | time_mid << 80
+                | (0x1000 | time_hi) << 64
+                | u128::from(0x8000 | self.clock_seq) << 48
+                | u128::from_be_bytes(node),
+        )
+    }
+}
+
+impl FromStr for Uuid1Fields {
+    type Err = ParseUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_uuid_v1(s)
+    }
+}
+
+/// The error returned when a string is not a version 1 UUID, saying
+/// where it went wrong and what was expected there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseUuidError {
+    input: String,
+    offset: usize,
+    expected: &'static str,
+}
+
+impl ParseUuidError {
+    fn new(input: &str, offset: usize, expected: &'static str) -> ParseUuidError {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(input, offset, expected, "invalid UUID");
+        ParseUuidError {
+            input: input.to_string(),
+            offset,
+            expected,
+        }
+    }
+
+    /// The string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The byte offset into the string where parsing failed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// What was expected at that offset.
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+}
+
+impl fmt::Display for ParseUuidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid UUID: {:?}: expected {} at offset {}",
+            self.input, self.expected, self.offset
+        )
+    }
+}
+
+impl Error for ParseUuidError {}
+
+/// Take apart the given version 1 UUID, or return None if it is some
+/// other version, or not the RFC 4122 variant.
+///
+/// ```
+/// use epochs::uuid_v1_fields;
+/// let fields = uuid_v1_fields(0xca4892ce_4f7d_11ea_b77f_2e728ce88125).unwrap();
+/// assert_eq!(fields.timestamp.to_string(), "2020-02-14 23:00:27.148155");
+/// assert_eq!(fields.clock_seq, 0x377f);
+/// assert_eq!(fields.node, [0x2e, 0x72, 0x8c, 0xe8, 0x81, 0x25]);
+/// // Version 4 UUIDs are random.
+/// assert_eq!(uuid_v1_fields(0x1b4e28ba_2fa1_41d2_883f_0016d3cca427), None);
+/// ```
+pub fn uuid_v1_fields(uuid: u128) -> Option<Uuid1Fields> {
+    let bytes = uuid.to_be_bytes();
+    let time_hi_and_version = u16::from_be_bytes([bytes[6], bytes[7]]);
+    if time_hi_and_version >> 12 != 1 || bytes[8] >> 6 != 0b10 {
+        return None;
+    }
+    let time_low = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let time_mid = u16::from_be_bytes([bytes[4], bytes[5]]);
+    let ticks = i64::from(time_hi_and_version & 0x0fff) << 48
+        | i64::from(time_mid) << 32
+        | i64::from(time_low);
+    Some(Uuid1Fields {
+        // Even 60 bits of ticks is only as far as the year 5236.
+        timestamp: uuid_v1(ticks)?,
+        clock_seq: u16::from_be_bytes([bytes[8], bytes[9]]) & 0x3fff,
+        node: bytes[10..].try_into().ok()?,
+    })
+}
+
+/// Parse the given string as a version 1 UUID and take it apart. The
+/// UUID may be in the usual 8-4-4-4-12 form or 32 hex digits without
+/// hyphens, in either case, and may be wrapped in braces or have a
+/// `urn:uuid:` prefix.
+///
+/// ```
+/// use epochs::parse_uuid_v1;
+/// let fields = parse_uuid_v1("ca4892ce-4f7d-11ea-b77f-2e728ce88125").unwrap();
+/// assert_eq!(fields.timestamp.to_string(), "2020-02-14 23:00:27.148155");
+/// let err = parse_uuid_v1("ca4892ce-4f7d-11ea-b77f-2e728ce8812").unwrap_err();
+/// assert_eq!((err.offset(), err.expected()), (35, "a hex digit"));
+/// let err = parse_uuid_v1("{ca4892ce-4f7d-41ea-b77f-2e728ce88125}").unwrap_err();
+/// assert_eq!((err.offset(), err.expected()), (15, "version 1"));
+/// ```
+pub fn parse_uuid_v1(s: &str) -> Result<Uuid1Fields, ParseUuidError> {
+    let err = |offset, expected| ParseUuidError::new(s, offset, expected);
+    let mut start = s.len() - s.trim_start().len();
+    let mut body = s.trim();
+    if body.len() >= 9 && body[..9].eq_ignore_ascii_case("urn:uuid:") {
+        start += 9;
+        body = &body[9..];
+    }
+    if let Some(inner) = body.strip_prefix('{') {
+        body = inner
+            .strip_suffix('}')
+            .ok_or_else(|| err(start + body.len(), "a closing brace"))?;
+        start += 1;
+    }
+
+    let hyphenated = body.as_bytes().get(8) == Some(&b'-');
+    let (len, version_at, variant_at) = if hyphenated {
+        (36, 14, 19)
+    } else {
+        (32, 12, 16)
+    };
+    let mut uuid = 0u128;
+    for (i, c) in body.char_indices() {
+        if i >= len {
+            return Err(err(start + i, "the end of the UUID"));
+        }
+        if hyphenated && [8, 13, 18, 23].contains(&i) {
+            if c != '-' {
+                return Err(err(start + i, "a hyphen"));
+            }
+            continue;
+        }
+        let digit = c
+            .to_digit(16)
+            .ok_or_else(|| err(start + i, "a hex digit"))?;
+        uuid = uuid << 4 | u128::from(digit);
+    }
+    if body.len() < len {
+        let expected = if hyphenated && [8, 13, 18, 23].contains(&body.len()) {
+            "a hyphen"
+        } else {
+            "a hex digit"
+        };
+        return Err(err(start + body.len(), expected));
+    }
+
+    uuid_v1_fields(uuid).ok_or_else(|| {
+        if body.as_bytes()[version_at] != b'1' {
+            err(start + version_at, "version 1")
+        } else {
+            err(start + variant_at, "the RFC 4122 variant (8, 9, a, or b)")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const UUID: u128 = 0xca4892ce_4f7d_11ea_b77f_2e728ce88125;
+
+    #[test]
+    fn every_form() {
+        let expected = uuid_v1_fields(UUID).unwrap();
+        for s in [
+            "ca4892ce-4f7d-11ea-b77f-2e728ce88125",
+            "CA4892CE-4F7D-11EA-B77F-2E728CE88125",
+            "ca4892ce4f7d11eab77f2e728ce88125",
+            "{ca4892ce-4f7d-11ea-b77f-2e728ce88125}",
+            "urn:uuid:ca4892ce-4f7d-11ea-b77f-2e728ce88125",
+            " ca4892ce-4f7d-11ea-b77f-2e728ce88125\n",
+        ] {
+            assert_eq!(parse_uuid_v1(s), Ok(expected), "{s:?}");
+            assert_eq!(s.parse(), Ok(expected), "{s:?}");
+        }
+    }
+    #[test]
+    fn matches_uuid_v1() {
+        let fields = uuid_v1_fields(UUID).unwrap();
+        assert_eq!(Some(fields.timestamp), uuid_v1(0x1ea4f7dca4892ce));
+    }
+    #[test]
+    fn malformed() {
+        for (s, offset, expected) in [
+            ("", 0, "a hex digit"),
+            ("ca4892ce-4f7d-11ea-b77f", 23, "a hyphen"),
+            ("ca4892ce-4f7d_11ea-b77f-2e728ce88125", 13, "a hyphen"),
+            ("ca4892ce-4f7d-11ea-b77f-2e728ce8812g", 35, "a hex digit"),
+            (
+                "ca4892ce-4f7d-11ea-b77f-2e728ce881255",
+                36,
+                "the end of the UUID",
+            ),
+            (
+                "ca4892ce-4f7d-11ea-777f-2e728ce88125",
+                19,
+                "the RFC 4122 variant (8, 9, a, or b)",
+            ),
+            ("ca4892ce4f7d41eab77f2e728ce88125", 12, "version 1"),
+            (
+                "{ca4892ce-4f7d-11ea-b77f-2e728ce88125",
+                37,
+                "a closing brace",
+            ),
+            (
+                "  urn:uuid:xa4892ce-4f7d-11ea-b77f-2e728ce88125",
+                11,
+                "a hex digit",
+            ),
+        ] {
+            let err = parse_uuid_v1(s).unwrap_err();
+            assert_eq!((err.offset(), err.expected()), (offset, expected), "{s:?}");
+        }
+        assert_eq!(
+            parse_uuid_v1("nope").unwrap_err().to_string(),
+            "invalid UUID: \"nope\": expected a hex digit at offset 0"
+        );
+    }
+    #[test]
+    fn round_trip() {
+        let fields = Uuid1Fields {
+            timestamp: crate::unix(1_234_567_890).unwrap(),
+            clock_seq: 0x3fff,
+            node: [1, 2, 3, 4, 5, 6],
+        };
+        let uuid = fields.to_u128().unwrap();
+        assert_eq!(uuid_v1_fields(uuid), Some(fields));
+        let too_much = Uuid1Fields {
+            clock_seq: 0x4000,
+            ..fields
+        };
+        assert_eq!(too_much.to_u128(), None);
+        let too_early = Uuid1Fields {
+            timestamp: crate::unix(-12_219_292_801).unwrap(),
+            ..fields
+        };
+        assert_eq!(too_early.to_u128(), None);
+    }
+}
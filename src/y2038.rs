@@ -0,0 +1,114 @@
+//! Helpers for the year 2038 problem, when signed 32-bit Unix time
+//! runs out at 2038-01-19 03:14:07.
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::{unix_i32, unix_u32};
+
+/// How long until the given signed 32-bit Unix time wraps around. This
+/// is zero at 2038-01-19 03:14:07, the last representable second.
+///
+/// ```
+/// use epochs::y2038_headroom;
+/// let left = y2038_headroom(1_234_567_890);
+/// assert_eq!(left.num_days(), 10_566);
+/// ```
+pub fn y2038_headroom(num: i32) -> Duration {
+    Duration::seconds(i64::from(i32::MAX) - i64::from(num))
+}
+
+/// Whether the given signed 32-bit Unix time is within the given margin
+/// of wrapping around (*e.g.*, for flagging certificate expiry dates
+/// or scheduled events that will soon be unrepresentable).
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::Duration;
+/// use epochs::y2038_at_risk;
+/// assert!(!y2038_at_risk(1_234_567_890, Duration::days(365)));
+/// assert!(y2038_at_risk(2_140_000_000, Duration::days(365)));
+/// ```
+pub fn y2038_at_risk(num: i32, margin: Duration) -> bool {
+    y2038_headroom(num) <= margin
+}
+
+/// Whether the given time is too late to be a signed 32-bit Unix time,
+/// or within the given margin of being so.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::{Duration, NaiveDateTime};
+/// use epochs::y2038_at_risk_datetime;
+/// let ndt = NaiveDateTime::parse_from_str("2040-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// assert!(y2038_at_risk_datetime(ndt, Duration::zero()));
+/// ```
+pub fn y2038_at_risk_datetime(ndt: NaiveDateTime, margin: Duration) -> bool {
+    let seconds_left = i64::from(i32::MAX) - ndt.and_utc().timestamp();
+    seconds_left <= margin.num_seconds()
+}
+
+/// Interpret a signed 32-bit Unix time that may have wrapped around
+/// past 2038. Clocks that overflow go negative, back to 1901, so a
+/// time earlier than `not_before` (say, when the device or log was
+/// made) is taken to have wrapped and read as unsigned instead,
+/// landing between 2038 and 2106.
+///
+/// ```
+///# extern crate chrono;
+/// use chrono::NaiveDateTime;
+/// use epochs::y2038_unwrap;
+/// let not_before = NaiveDateTime::parse_from_str("2020-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+/// let ndt = y2038_unwrap(i32::MIN, not_before).unwrap();
+/// assert_eq!(ndt.to_string(), "2038-01-19 03:14:08");
+/// let ndt = y2038_unwrap(1_234_567_890, not_before).unwrap();
+/// assert_eq!(ndt.to_string(), "2009-02-13 23:31:30");
+/// ```
+pub fn y2038_unwrap(num: i32, not_before: NaiveDateTime) -> Option<NaiveDateTime> {
+    let ndt = unix_i32(num)?;
+    if num < 0 && ndt < not_before {
+        unix_u32(num as u32)
+    } else {
+        Some(ndt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn midnight(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn headroom_at_the_end() {
+        assert_eq!(y2038_headroom(i32::MAX), Duration::zero());
+        assert!(y2038_at_risk(i32::MAX, Duration::zero()));
+        assert!(!y2038_at_risk(i32::MAX - 1, Duration::zero()));
+        assert_eq!(y2038_headroom(i32::MIN), Duration::seconds(u32::MAX.into()));
+    }
+    #[test]
+    fn at_risk_datetime() {
+        let last = unix_i32(i32::MAX).unwrap();
+        assert!(y2038_at_risk_datetime(last, Duration::zero()));
+        let before = last - Duration::seconds(1);
+        assert!(!y2038_at_risk_datetime(before, Duration::zero()));
+        assert!(y2038_at_risk_datetime(before, Duration::seconds(1)));
+        assert!(!y2038_at_risk_datetime(
+            midnight(2009, 2, 13),
+            Duration::days(365)
+        ));
+    }
+    #[test]
+    fn unwrap_keeps_pre_1970_when_allowed() {
+        let ndt = y2038_unwrap(-1, midnight(1960, 1, 1)).unwrap();
+        assert_eq!(ndt.to_string(), "1969-12-31 23:59:59");
+        let ndt = y2038_unwrap(-1, midnight(1970, 1, 1)).unwrap();
+        assert_eq!(ndt.to_string(), "2106-02-07 06:28:15");
+    }
+}